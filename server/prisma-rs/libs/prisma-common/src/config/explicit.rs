@@ -1,4 +1,4 @@
-use super::ConnectionLimit;
+use super::{ConnectionLimit, QueryLogging};
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -15,11 +15,25 @@ pub struct ExplicitConfig {
     pub schema: Option<String>,
     pub database: Option<String>,
     pub management_schema: Option<String>,
+    pub statement_cache_size: Option<usize>,
+    pub max_in_flight_queries: Option<usize>,
+    /// Executes identical concurrent reads once.
+    pub single_flight_reads: Option<bool>,
+    /// Refuses starting if the tables don't match the data model.
+    pub validate_schema: Option<bool>,
+    pub table_prefix: Option<String>,
 
     pooled: Option<bool>,
     connection_limit: Option<u32>,
     migrations: Option<bool>,
     active: Option<bool>,
+
+    query_log_level: Option<String>,
+    redact_query_parameters: Option<bool>,
+    slow_query_threshold_ms: Option<u64>,
+    #[serde(default)]
+    scrubbed_query_columns: Vec<String>,
+    tag_queries: Option<bool>,
 }
 
 impl ConnectionLimit for ExplicitConfig {
@@ -31,3 +45,25 @@ impl ConnectionLimit for ExplicitConfig {
         self.pooled
     }
 }
+
+impl QueryLogging for ExplicitConfig {
+    fn query_log_level(&self) -> Option<&str> {
+        self.query_log_level.as_ref().map(|level| level.as_str())
+    }
+
+    fn redact_query_parameters(&self) -> bool {
+        self.redact_query_parameters.unwrap_or(false)
+    }
+
+    fn slow_query_threshold_ms(&self) -> Option<u64> {
+        self.slow_query_threshold_ms
+    }
+
+    fn scrubbed_query_columns(&self) -> &[String] {
+        &self.scrubbed_query_columns
+    }
+
+    fn tag_queries(&self) -> bool {
+        self.tag_queries.unwrap_or(false)
+    }
+}
@@ -1,7 +1,7 @@
-use super::ConnectionLimit;
+use super::{ConnectionLimit, QueryLogging};
 use std::path::PathBuf;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FileConfig {
     pub connector: String,
@@ -15,9 +15,39 @@ pub struct FileConfig {
     pub pooled: Option<bool>,
     pub schema: Option<String>,
     pub management_schema: Option<String>,
+    pub statement_cache_size: Option<usize>,
+    pub max_in_flight_queries: Option<usize>,
+    /// Executes identical concurrent reads once.
+    pub single_flight_reads: Option<bool>,
+    /// Refuses starting if the tables don't match the data model. Tenant
+    /// databases are validated when opened, which needs separate tenant
+    /// connections.
+    pub validate_schema: Option<bool>,
+    pub table_prefix: Option<String>,
+    /// Routes every request to the database `{tenants_folder}/{tenant}.db`
+    /// of the tenant given in the `prisma-tenant` header. Requires
+    /// `trustedGateway`, requests fail without a tenant otherwise.
+    pub tenants_folder: Option<String>,
+    /// The number of tenant databases kept open, defaults to 64.
+    pub max_tenants: Option<usize>,
+    /// Attaches the database of the tenant to shared connections for every
+    /// transaction, instead of opening a pool per tenant.
+    pub shared_tenant_connections: Option<bool>,
+    /// The roles of the `prisma-role` header that can only read.
+    pub read_only_roles: Option<Vec<String>>,
+    /// Further databases to spread the records of sharded models over, the
+    /// `database_file` being the first shard.
+    pub shard_files: Option<Vec<String>>,
 
     migrations: Option<bool>,
     active: Option<bool>,
+
+    query_log_level: Option<String>,
+    redact_query_parameters: Option<bool>,
+    slow_query_threshold_ms: Option<u64>,
+    #[serde(default)]
+    scrubbed_query_columns: Vec<String>,
+    tag_queries: Option<bool>,
 }
 
 impl FileConfig {
@@ -39,6 +69,12 @@ impl FileConfig {
             None => file_name,
         }
     }
+
+    /// The schema the tables are qualified with, the `schema` if set or the
+    /// database name otherwise.
+    pub fn schema_name(&self) -> String {
+        self.schema.clone().unwrap_or_else(|| self.db_name())
+    }
 }
 
 impl ConnectionLimit for FileConfig {
@@ -50,3 +86,25 @@ impl ConnectionLimit for FileConfig {
         self.pooled
     }
 }
+
+impl QueryLogging for FileConfig {
+    fn query_log_level(&self) -> Option<&str> {
+        self.query_log_level.as_ref().map(|level| level.as_str())
+    }
+
+    fn redact_query_parameters(&self) -> bool {
+        self.redact_query_parameters.unwrap_or(false)
+    }
+
+    fn slow_query_threshold_ms(&self) -> Option<u64> {
+        self.slow_query_threshold_ms
+    }
+
+    fn scrubbed_query_columns(&self) -> &[String] {
+        &self.scrubbed_query_columns
+    }
+
+    fn tag_queries(&self) -> bool {
+        self.tag_queries.unwrap_or(false)
+    }
+}
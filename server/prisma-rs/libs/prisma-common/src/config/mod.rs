@@ -28,6 +28,25 @@ pub trait ConnectionLimit {
     }
 }
 
+pub trait QueryLogging {
+    /// The level to log executed queries at, e.g. `debug`. No queries are
+    /// logged if not set.
+    fn query_log_level(&self) -> Option<&str>;
+
+    /// Log the queries without the values of their parameters.
+    fn redact_query_parameters(&self) -> bool;
+
+    /// Queries taking longer than this are logged as a warning.
+    fn slow_query_threshold_ms(&self) -> Option<u64>;
+
+    /// Columns whose values are never logged, e.g. `password`.
+    fn scrubbed_query_columns(&self) -> &[String];
+
+    /// Prepend the operation and trace context of the request as a comment
+    /// to the queries.
+    fn tag_queries(&self) -> bool;
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum PrismaDatabase {
@@ -41,7 +60,16 @@ impl PrismaDatabase {
         match self {
             PrismaDatabase::Explicit(config) => config.database.clone(),
             PrismaDatabase::ConnectionString(config) => config.database.clone(),
-            PrismaDatabase::File(config) => Some(config.db_name()),
+            PrismaDatabase::File(config) => Some(config.schema_name()),
+        }
+    }
+
+    /// Prepended to the table names of the data model.
+    pub fn table_prefix(&self) -> Option<String> {
+        match self {
+            PrismaDatabase::Explicit(config) => config.table_prefix.clone(),
+            PrismaDatabase::ConnectionString(_) => None,
+            PrismaDatabase::File(config) => config.table_prefix.clone(),
         }
     }
 }
@@ -54,6 +82,11 @@ pub struct PrismaConfig {
     pub cluster_address: Option<String>,
     pub rabbit_uri: Option<String>,
     pub enable_management_api: Option<bool>,
+    /// The server is only reachable through a gateway authenticating the
+    /// requests, setting the `prisma-tenant`, `prisma-role`, `prisma-actor`
+    /// and `prisma-setting-{name}` headers and removing them from the
+    /// requests of clients. The headers are ignored otherwise.
+    pub trusted_gateway: Option<bool>,
     pub databases: BTreeMap<String, PrismaDatabase>,
 }
 
@@ -88,6 +88,9 @@ impl FieldTemplate {
                     is_auto_generated: st.is_auto_generated,
                     manifestation: st.manifestation,
                     behaviour: st.behaviour,
+                    uuid_storage: st.uuid_storage,
+                    max_length: st.max_length,
+                    native_type: st.native_type,
                     model,
                 };
 
@@ -1,7 +1,7 @@
 use super::FieldManifestation;
 use crate::prelude::*;
 use once_cell::sync::OnceCell;
-use prisma_query::ast::Column;
+use prisma_query::ast::{Column, Table};
 use std::sync::{Arc, Weak};
 
 pub type RelationFieldRef = Arc<RelationField>;
@@ -125,6 +125,18 @@ impl RelationField {
         }
     }
 
+    /// The table the relation columns are read from when querying the related
+    /// nodes of this field. If the table of the related model holds the foreign
+    /// key, it is queried directly, otherwise the relation table is joined under
+    /// `Relation::TABLE_ALIAS`.
+    pub fn relation_columns_table(&self) -> Table {
+        if self.related_field().relation_is_inlined_in_parent() {
+            self.related_model().table()
+        } else {
+            Relation::TABLE_ALIAS.into()
+        }
+    }
+
     pub fn opposite_column(&self) -> Column {
         match self.relation_side {
             RelationSide::A => self.relation().model_b_column(),
@@ -143,7 +155,8 @@ impl RelationField {
         let model = self.model();
         let schema = model.schema();
         let db_name = self.db_name();
-        let parts = ((schema.db_name.as_ref(), model.db_name()), db_name.as_ref());
+        let table_name = model.table_name();
+        let parts = ((schema.db_name.as_ref(), table_name.as_ref()), db_name.as_ref());
 
         parts.into()
     }
@@ -24,6 +24,9 @@ pub struct ScalarFieldTemplate {
     pub is_auto_generated: bool,
     pub manifestation: Option<FieldManifestation>,
     pub behaviour: Option<FieldBehaviour>,
+    pub uuid_storage: Option<UuidStorage>,
+    pub max_length: Option<usize>,
+    pub native_type: Option<String>,
 }
 
 #[derive(DebugStub)]
@@ -37,6 +40,9 @@ pub struct ScalarField {
     pub is_auto_generated: bool,
     pub manifestation: Option<FieldManifestation>,
     pub behaviour: Option<FieldBehaviour>,
+    pub uuid_storage: Option<UuidStorage>,
+    pub max_length: Option<usize>,
+    pub native_type: Option<String>,
     #[debug_stub = "#ModelWeakRef#"]
     pub model: ModelWeakRef,
 }
@@ -62,6 +68,21 @@ pub enum IdStrategy {
     Sequence,
 }
 
+/// How a `UUID` field is stored in the database column.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq)]
+pub enum UuidStorage {
+    /// The hyphenated string representation, e.g. `CHAR(36)`.
+    Text,
+    /// The raw 16 bytes, e.g. `BINARY(16)`.
+    Binary,
+}
+
+impl Default for UuidStorage {
+    fn default() -> Self {
+        UuidStorage::Text
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq)]
 pub enum ScalarListStrategy {
     Embedded,
@@ -135,7 +156,8 @@ impl ScalarField {
     }
 
     pub fn as_column(&self) -> Column {
-        ((self.schema().db_name.as_str(), self.model().db_name()), self.db_name()).into()
+        let table_name = self.model().table_name();
+        ((self.schema().db_name.as_str(), table_name.as_str()), self.db_name()).into()
     }
 
     pub fn id_behaviour_clone(&self) -> Option<FieldBehaviour> {
@@ -156,6 +178,15 @@ impl ScalarField {
         }
     }
 
+    /// The storage format of the field, if it's a `UUID`. Defaults to text
+    /// when no hint is given in the data model.
+    pub fn uuid_storage(&self) -> Option<UuidStorage> {
+        match self.type_identifier {
+            TypeIdentifier::UUID => Some(self.uuid_storage.unwrap_or_default()),
+            _ => None,
+        }
+    }
+
     pub fn scalar_list_table<'a>(&'a self) -> ScalarListTable<'a> {
         ScalarListTable::new(self)
     }
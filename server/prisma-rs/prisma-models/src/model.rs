@@ -15,6 +15,12 @@ pub struct ModelTemplate {
     pub is_embedded: bool,
     pub fields: Vec<FieldTemplate>,
     pub manifestation: Option<ModelManifestation>,
+    pub view: Option<ViewManifestation>,
+    #[serde(default)]
+    pub unique_indexes: Vec<UniqueIndex>,
+    /// The field choosing the shard of a record, if the model is sharded.
+    #[serde(default)]
+    pub shard_key: Option<String>,
 }
 
 #[derive(DebugStub)]
@@ -23,6 +29,9 @@ pub struct Model {
     pub stable_identifier: String,
     pub is_embedded: bool,
     pub manifestation: Option<ModelManifestation>,
+    pub view: Option<ViewManifestation>,
+    pub unique_indexes: Vec<UniqueIndex>,
+    pub shard_key: Option<String>,
 
     fields: OnceCell<Fields>,
 
@@ -36,6 +45,24 @@ pub struct ModelManifestation {
     pub db_name: String,
 }
 
+/// The model is read from a database view instead of a table.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewManifestation {
+    /// Writes to the model are passed on to the view, which the database
+    /// must support.
+    pub updatable: bool,
+}
+
+/// A unique index over several fields. Records can be selected by the values
+/// of all its fields.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UniqueIndex {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
 impl ModelTemplate {
     pub fn build(self, schema: SchemaWeakRef) -> ModelRef {
         let model = Arc::new(Model {
@@ -44,6 +71,9 @@ impl ModelTemplate {
             is_embedded: self.is_embedded,
             fields: OnceCell::new(),
             manifestation: self.manifestation,
+            view: self.view,
+            unique_indexes: self.unique_indexes,
+            shard_key: self.shard_key,
             schema: schema,
         });
 
@@ -86,7 +116,13 @@ impl Model {
     }
 
     pub fn table(&self) -> Table {
-        (self.schema().db_name.as_str(), self.db_name()).into()
+        let schema = self.schema();
+        (schema.db_name.as_str(), schema.table_name(self.db_name()).as_str()).into()
+    }
+
+    /// The name of the table in the database, see `Schema::table_name`.
+    pub fn table_name(&self) -> String {
+        self.schema().table_name(self.db_name())
     }
 
     pub fn fields(&self) -> &Fields {
@@ -100,6 +136,27 @@ impl Model {
         self.schema().is_legacy()
     }
 
+    pub fn is_view(&self) -> bool {
+        self.view.is_some()
+    }
+
+    /// Models backed by views are read-only, unless the view is updatable.
+    pub fn is_read_only(&self) -> bool {
+        self.view.map(|view| !view.updatable).unwrap_or(false)
+    }
+
+    pub fn find_unique_index(&self, name: &str) -> Option<&UniqueIndex> {
+        self.unique_indexes.iter().find(|index| index.name == name)
+    }
+
+    /// The field choosing the shard of a record, `None` if the model is not
+    /// sharded.
+    pub fn shard_key_field(&self) -> Option<Arc<ScalarField>> {
+        self.shard_key
+            .as_ref()
+            .and_then(|name| self.fields().find_from_scalar(name).ok())
+    }
+
     pub fn db_name(&self) -> &str {
         self.db_name_opt().unwrap_or_else(|| self.name.as_ref())
     }
@@ -1,7 +1,6 @@
 use crate::{DomainError, DomainResult};
 use chrono::{DateTime, Utc};
 use graphql_parser::query::Value as GraphqlValue;
-use rusqlite::types::{FromSql, FromSqlResult, ValueRef};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{convert::TryFrom, fmt};
@@ -10,6 +9,9 @@ use uuid::Uuid;
 #[cfg(feature = "sql")]
 use prisma_query::ast::*;
 
+#[cfg(feature = "sqlite")]
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ValueRef};
+
 pub type PrismaListValue = Option<Vec<PrismaValue>>;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -221,12 +223,18 @@ impl From<PrismaValue> for DatabaseValue {
     }
 }
 
+#[cfg(feature = "sqlite")]
 impl FromSql for GraphqlId {
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
-        value
-            .as_str()
-            .map(|strval| GraphqlId::String(strval.to_string()))
-            .or_else(|_| value.as_i64().map(|intval| GraphqlId::Int(intval as usize)))
+        match value {
+            ValueRef::Blob(bytes) => Uuid::from_slice(bytes)
+                .map(GraphqlId::UUID)
+                .map_err(|err| FromSqlError::Other(Box::new(err))),
+            _ => value
+                .as_str()
+                .map(|strval| GraphqlId::String(strval.to_string()))
+                .or_else(|_| value.as_i64().map(|intval| GraphqlId::Int(intval as usize))),
+        }
     }
 }
 
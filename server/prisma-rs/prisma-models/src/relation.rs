@@ -272,9 +272,21 @@ impl Relation {
         use RelationLinkManifestation::*;
 
         match self.manifestation {
-            Some(RelationTable(ref m)) => m.table.clone().into(),
             Some(Inline(ref m)) => self.schema().find_model(&m.in_table_of_model_name).unwrap().table(),
-            None => format!("_{}", self.name).into(),
+            _ => self.relation_table_name().into(),
+        }
+    }
+
+    /// The name of the `relation_table` in the database.
+    pub fn relation_table_name(&self) -> String {
+        use RelationLinkManifestation::*;
+
+        let schema = self.schema();
+
+        match self.manifestation {
+            Some(RelationTable(ref m)) => schema.table_name(&m.table),
+            Some(Inline(ref m)) => schema.find_model(&m.in_table_of_model_name).unwrap().table_name(),
+            None => schema.table_name(&format!("_{}", self.name)),
         }
     }
 
@@ -13,7 +13,7 @@ impl<'a> ScalarListTable<'a> {
     pub const VALUE_FIELD_NAME: &'static str = "value";
 
     pub fn new(parent_field: &'a ScalarField) -> Self {
-        let table_name = format!("{}_{}", parent_field.model().db_name(), parent_field.db_name());
+        let table_name = format!("{}_{}", parent_field.model().table_name(), parent_field.db_name());
 
         Self {
             parent_field,
@@ -21,6 +21,10 @@ impl<'a> ScalarListTable<'a> {
         }
     }
 
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
     pub fn table(&self) -> Table {
         let schema = self.parent_field.schema();
         let database_name = schema.db_name.as_ref();
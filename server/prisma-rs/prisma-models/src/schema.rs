@@ -12,6 +12,7 @@ pub struct SchemaTemplate {
     pub relations: Vec<RelationTemplate>,
     pub enums: Vec<PrismaEnum>,
     pub version: Option<String>,
+    pub table_prefix: Option<String>,
 }
 
 #[derive(DebugStub)]
@@ -19,6 +20,9 @@ pub struct Schema {
     pub enums: Vec<PrismaEnum>,
     pub version: Option<String>,
     pub db_name: String,
+    /// Prepended to the names of all tables, e.g. to share a database with
+    /// other applications.
+    pub table_prefix: Option<String>,
 
     models: OnceCell<Vec<ModelRef>>,
     relations: OnceCell<Vec<RelationRef>>,
@@ -40,6 +44,7 @@ impl SchemaTemplate {
             enums: self.enums,
             version: self.version,
             db_name: db_name,
+            table_prefix: self.table_prefix,
             relation_fields: OnceCell::new(),
         });
 
@@ -91,6 +96,14 @@ impl Schema {
         self.version.is_none()
     }
 
+    /// The name of the table in the database, with the prefix if set.
+    pub fn table_name(&self, name: &str) -> String {
+        match self.table_prefix {
+            Some(ref prefix) => format!("{}{}", prefix, name),
+            None => name.to_string(),
+        }
+    }
+
     pub fn fields_requiring_model(&self, model: ModelRef) -> Vec<RelationFieldRef> {
         self.relation_fields()
             .into_iter()
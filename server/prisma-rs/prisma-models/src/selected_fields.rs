@@ -1,4 +1,4 @@
-use crate::{ModelRef, Relation, RelationField, ScalarField, TypeIdentifier};
+use crate::{ModelRef, RelationField, ScalarField, TypeIdentifier};
 use once_cell::unsync::OnceCell;
 use prisma_query::ast::Column;
 use std::sync::Arc;
@@ -128,14 +128,14 @@ impl SelectedFields {
                         relation
                             .column_for_relation_side(from_field.relation_side.opposite())
                             .alias(Self::RELATED_MODEL_ALIAS)
-                            .table(Relation::TABLE_ALIAS),
+                            .table(from_field.relation_columns_table()),
                     );
 
                     result.push(
                         relation
                             .column_for_relation_side(from_field.relation_side)
                             .alias(Self::PARENT_MODEL_ALIAS)
-                            .table(Relation::TABLE_ALIAS),
+                            .table(from_field.relation_columns_table()),
                     );
                 };
 
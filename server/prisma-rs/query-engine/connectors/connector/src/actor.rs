@@ -0,0 +1,21 @@
+use crate::scoped::{scoped, with_scoped};
+use std::cell::RefCell;
+
+thread_local! {
+    static ACTOR: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Runs `f` with `actor`, e.g. the id of the user sending the request, as the
+/// actor of the current thread. Connectors keeping an audit log record it
+/// for the mutations of `f`.
+pub fn with_actor<F, T>(actor: String, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_scoped(&ACTOR, Some(actor), f)
+}
+
+/// The actor of the current thread, if set with `with_actor`.
+pub fn actor() -> Option<String> {
+    scoped(&ACTOR)
+}
@@ -0,0 +1,110 @@
+use crate::scoped::{scoped, with_scoped};
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+thread_local! {
+    static CANCELLATION: RefCell<Option<CancellationToken>> = RefCell::new(None);
+}
+
+type Callback = Arc<Fn() + Send + Sync>;
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    next_id: AtomicUsize,
+    callbacks: Mutex<Vec<(usize, Callback)>>,
+}
+
+/// Cancels the queries of a request, e.g. when the client disconnected or a
+/// deadline passed. Cloned tokens cancel the same request.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+/// Unregisters a callback of `on_cancel` when dropped.
+pub struct CancelRegistration {
+    inner: Arc<Inner>,
+    id: usize,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interrupts the running queries. Queries started later fail right
+    /// away.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+
+        // Called without the lock, so callbacks can register and drop
+        // registrations themselves.
+        let callbacks: Vec<Callback> = self
+            .inner
+            .callbacks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, callback)| Arc::clone(callback))
+            .collect();
+
+        for callback in callbacks {
+            callback();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Calls `f` when the token is cancelled, as long as the registration is
+    /// kept. Connectors use this to interrupt the statement in flight.
+    pub fn on_cancel<F>(&self, f: F) -> CancelRegistration
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let id = self.inner.next_id.fetch_add(1, Ordering::SeqCst);
+        let mut callbacks = self.inner.callbacks.lock().unwrap();
+
+        // Checked under the lock, so `cancel` either sees the callback or
+        // cancelled before it is called here, never both.
+        if self.is_cancelled() {
+            drop(callbacks);
+            f();
+        } else {
+            callbacks.push((id, Arc::new(f)));
+        }
+
+        CancelRegistration {
+            inner: Arc::clone(&self.inner),
+            id,
+        }
+    }
+}
+
+impl Drop for CancelRegistration {
+    fn drop(&mut self) {
+        let id = self.id;
+        self.inner.callbacks.lock().unwrap().retain(|(other, _)| *other != id);
+    }
+}
+
+/// Runs `f` with the token as the cancellation of the current thread. The
+/// connectors check it for every transaction and statement.
+pub fn with_cancellation<F, T>(token: CancellationToken, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_scoped(&CANCELLATION, Some(token), f)
+}
+
+/// The token of the current thread, if set with `with_cancellation`.
+pub fn cancellation() -> Option<CancellationToken> {
+    scoped(&CANCELLATION)
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+/// The version of the database server or library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DatabaseVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl DatabaseVersion {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Parses versions such as `3.26.0` or `8.0.15-log`, ignoring anything
+    /// after the numbers. Missing parts are zero.
+    pub fn parse(version: &str) -> Option<Self> {
+        let numbers = version.split(|c: char| !c.is_ascii_digit() && c != '.').next()?;
+        let mut parts = numbers.split('.').map(|part| part.parse::<u32>());
+
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+
+        Some(Self::new(major, minor, patch))
+    }
+
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        *self >= Self::new(major, minor, 0)
+    }
+}
+
+impl fmt::Display for DatabaseVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The features of the database behind a connector, detected when
+/// connecting. Query strategies should be chosen on these instead of on the
+/// type of the database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capabilities {
+    pub version: DatabaseVersion,
+    /// `INSERT ... RETURNING` to read generated values without a second
+    /// query.
+    pub returning: bool,
+    /// Window functions such as `ROW_NUMBER() OVER (...)`.
+    pub window_functions: bool,
+    /// Functions for querying JSON values.
+    pub json: bool,
+    /// An atomic insert-or-update, like `ON CONFLICT DO UPDATE`.
+    pub native_upsert: bool,
+    /// Schema changes can be rolled back with the transaction.
+    pub transactional_ddl: bool,
+    /// The maximum number of bound parameters in one statement.
+    pub max_bind_parameters: usize,
+}
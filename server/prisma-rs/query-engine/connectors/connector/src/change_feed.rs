@@ -0,0 +1,88 @@
+use crate::{filter::Filter, ConnectorResult, DataResolver, QueryArguments, ScalarCompare};
+use prisma_models::prelude::*;
+use std::sync::Arc;
+
+/// The position of a consumer in the change feed of a model, the last record
+/// it has seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeCursor {
+    /// The value of the field the changes are ordered by.
+    pub position: PrismaValue,
+    pub id: GraphqlId,
+}
+
+#[derive(Debug)]
+pub struct ChangePage {
+    /// The changed records, with the field the changes are ordered by and
+    /// the id selected.
+    pub nodes: ManyNodes,
+    /// Where to continue reading, the cursor given if nothing changed.
+    pub cursor: Option<ChangeCursor>,
+    /// True if more records changed than fit on the page.
+    pub has_more: bool,
+}
+
+/// Reads the records of the model of `field` written after `cursor`, ordered
+/// by `field` and then by id, e.g. for consumers syncing incrementally from
+/// an `updatedAt` timestamp or a sequence column. Records with the same value
+/// of `field` as the cursor are not skipped, but records committed late with
+/// a lower value than the cursor are.
+pub fn changes_since(
+    data_resolver: &DataResolver,
+    field: Arc<ScalarField>,
+    cursor: Option<ChangeCursor>,
+    limit: u32,
+    selected_fields: &SelectedFields,
+) -> ConnectorResult<ChangePage> {
+    let model = field.model();
+    let id_field = model.fields().id();
+
+    let mut query_arguments = QueryArguments::default();
+
+    query_arguments.first = Some(limit);
+    query_arguments.order_by = Some(OrderBy {
+        field: Arc::clone(&field),
+        sort_order: SortOrder::Ascending,
+    });
+
+    query_arguments.filter = cursor.as_ref().map(|cursor| {
+        Filter::or(vec![
+            field.greater_than(cursor.position.clone()),
+            Filter::and(vec![
+                field.equals(cursor.position.clone()),
+                id_field.greater_than(cursor.id.clone()),
+            ]),
+        ])
+    });
+
+    let mut selected_fields = selected_fields.clone();
+
+    for required in &[&field, &id_field] {
+        if !selected_fields
+            .scalar
+            .iter()
+            .any(|selected| selected.field.name == required.name)
+        {
+            selected_fields.add_scalar(Arc::clone(*required), true);
+        }
+    }
+
+    // One more record than asked for is returned if there are more.
+    let mut nodes = data_resolver.get_nodes(model, query_arguments, &selected_fields)?;
+    let has_more = nodes.nodes.len() > limit as usize;
+    nodes.nodes.truncate(limit as usize);
+
+    let next_cursor = match nodes.nodes.last() {
+        Some(last) => Some(ChangeCursor {
+            position: last.get_field_value(&nodes.field_names, field.db_name())?.clone(),
+            id: last.get_id_value(&nodes.field_names, Arc::clone(&model))?.clone(),
+        }),
+        None => cursor,
+    };
+
+    Ok(ChangePage {
+        nodes,
+        cursor: next_cursor,
+        has_more,
+    })
+}
@@ -1,4 +1,4 @@
-use crate::filter::Filter;
+use crate::filter::{CountCondition, Filter};
 use prisma_models::PrismaValue;
 
 /// Comparing methods for scalars.
@@ -43,6 +43,14 @@ pub trait ScalarCompare {
     where
         T: Into<PrismaValue>;
 
+    fn matches_pattern<T>(&self, val: T) -> Filter
+    where
+        T: Into<PrismaValue>;
+
+    fn not_matches_pattern<T>(&self, val: T) -> Filter
+    where
+        T: Into<PrismaValue>;
+
     fn less_than<T>(&self, val: T) -> Filter
     where
         T: Into<PrismaValue>;
@@ -78,6 +86,10 @@ pub trait RelationCompare {
         T: Into<Filter>;
 
     fn one_relation_is_null(&self) -> Filter;
+
+    fn one_relation_is_not_null(&self) -> Filter;
+
+    fn count_related(&self, condition: CountCondition) -> Filter;
 }
 
 pub trait ScalarListCompare {
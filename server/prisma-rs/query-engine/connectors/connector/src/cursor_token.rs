@@ -0,0 +1,94 @@
+use crate::{error::ConnectorError, ConnectorResult};
+use once_cell::sync::OnceCell;
+use prisma_models::prelude::{GraphqlId, PrismaValue};
+use ring::{digest, hmac};
+use serde::{Deserialize, Serialize};
+
+/// Tells tokens apart from the ids of records, which are cursors as well.
+const TOKEN_PREFIX: &str = "cursor:";
+
+static SIGNING_KEY: OnceCell<hmac::SigningKey> = OnceCell::INIT;
+
+/// The position of a record in an ordered read, given to `after` and
+/// `before` as an opaque string instead of the id of the record. It holds
+/// the values the record is ordered by, so the cursor doesn't have to read
+/// the record again, and is signed so clients can't change them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CursorToken {
+    /// The values of the fields the read is ordered by, before the id.
+    pub order_values: Vec<PrismaValue>,
+    pub id: GraphqlId,
+}
+
+impl CursorToken {
+    pub fn new(order_values: Vec<PrismaValue>, id: GraphqlId) -> Self {
+        Self { order_values, id }
+    }
+
+    /// Signs the tokens with `secret`, so they stay valid over restarts and
+    /// on all servers sharing it. Without a secret the tokens are signed with
+    /// a random key of the process. Returns false if tokens were signed or
+    /// checked already, the secret is not used then.
+    pub fn set_secret(secret: &[u8]) -> bool {
+        SIGNING_KEY.set(hmac::SigningKey::new(&digest::SHA256, secret)).is_ok()
+    }
+
+    /// True if the cursor is meant to be a token, not an id.
+    pub fn is_token(cursor: &str) -> bool {
+        cursor.starts_with(TOKEN_PREFIX)
+    }
+
+    pub fn encode(&self) -> String {
+        // UNWRAP: Values and ids always serialize.
+        let payload = serde_json::to_vec(self).unwrap();
+        let signature = hmac::sign(signing_key(), &payload);
+
+        format!(
+            "{}{}.{}",
+            TOKEN_PREFIX,
+            base64::encode_config(&payload, base64::URL_SAFE_NO_PAD),
+            base64::encode_config(signature.as_ref(), base64::URL_SAFE_NO_PAD)
+        )
+    }
+
+    /// Fails with `InvalidCursor` for tokens not signed with the key in use,
+    /// including tokens that were changed after signing.
+    ///
+    /// ```rust
+    /// # use connector::*;
+    /// # use prisma_models::prelude::*;
+    /// let token = CursorToken::new(vec![PrismaValue::Int(3)], GraphqlId::Int(7));
+    /// let cursor = token.encode();
+    ///
+    /// assert_eq!(token, CursorToken::decode(&cursor).unwrap());
+    /// assert!(CursorToken::decode(&cursor.replace("cursor:", "cursor:x")).is_err());
+    /// ```
+    pub fn decode(cursor: &str) -> ConnectorResult<Self> {
+        if !Self::is_token(cursor) {
+            return Err(ConnectorError::InvalidCursor);
+        }
+
+        let mut parts = cursor[TOKEN_PREFIX.len()..].splitn(2, '.');
+
+        let mut decode_part = || {
+            parts
+                .next()
+                .and_then(|part| base64::decode_config(part, base64::URL_SAFE_NO_PAD).ok())
+                .ok_or(ConnectorError::InvalidCursor)
+        };
+
+        let payload = decode_part()?;
+        let signature = decode_part()?;
+
+        hmac::verify_with_own_key(signing_key(), &payload, &signature).map_err(|_| ConnectorError::InvalidCursor)?;
+
+        serde_json::from_slice(&payload).map_err(|_| ConnectorError::InvalidCursor)
+    }
+}
+
+fn signing_key() -> &'static hmac::SigningKey {
+    SIGNING_KEY.get_or_init(|| {
+        let secret: [u8; 32] = rand::random();
+        hmac::SigningKey::new(&digest::SHA256, &secret)
+    })
+}
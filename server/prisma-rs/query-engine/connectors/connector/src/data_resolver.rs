@@ -1,11 +1,18 @@
-use crate::{filter::NodeSelector, query_arguments::QueryArguments, ConnectorResult};
+use crate::{filter::RecordSelector, query_arguments::QueryArguments, Capabilities, ConnectorResult};
 use prisma_models::prelude::*;
 use prisma_models::ScalarFieldRef;
 
 pub trait DataResolver {
+    /// The features of the database, detected when connecting.
+    fn capabilities(&self) -> Capabilities;
+
+    /// Drops everything cached for the data model of `database`, e.g. after
+    /// the data model was replaced at runtime. Open connections are kept.
+    fn invalidate_caches(&self, database: &str);
+
     fn get_node_by_where(
         &self,
-        node_selector: &NodeSelector,
+        record_selector: &RecordSelector,
         selected_fields: &SelectedFields,
     ) -> ConnectorResult<Option<SingleNode>>;
 
@@ -0,0 +1,21 @@
+use crate::scoped::{scoped, with_scoped};
+use std::cell::RefCell;
+
+thread_local! {
+    static DATABASE_ROLE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Runs `f` with `role` as the database role of the current thread.
+/// Connectors supporting it restrict the transactions started in `f` to the
+/// privileges of the role, and lift the restriction again afterwards.
+pub fn with_database_role<F, T>(role: String, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_scoped(&DATABASE_ROLE, Some(role), f)
+}
+
+/// The role of the current thread, if set with `with_database_role`.
+pub fn database_role() -> Option<String> {
+    scoped(&DATABASE_ROLE)
+}
@@ -1,6 +1,6 @@
 use crate::filter::NodeSelector;
 use failure::{Error, Fail};
-use prisma_models::prelude::{DomainError, GraphqlId, ModelRef, PrismaValue};
+use prisma_models::prelude::{DomainError, GraphqlId, Model, ModelRef, PrismaValue};
 use std::fmt;
 
 #[cfg(feature = "sqlite")]
@@ -48,8 +48,8 @@ impl From<&NodeSelector> for NodeSelectorInfo {
 
 #[derive(Debug, Fail)]
 pub enum ConnectorError {
-    #[fail(display = "Unique constraint failed: {}", field_name)]
-    UniqueConstraintViolation { field_name: String },
+    #[fail(display = "Unique constraint failed: {}", constraint)]
+    UniqueConstraintViolation { constraint: String, fields: Vec<String> },
 
     #[fail(display = "Node does not exist.")]
     NodeDoesNotExist,
@@ -57,9 +57,28 @@ pub enum ConnectorError {
     #[fail(display = "Column does not exist")]
     ColumnDoesNotExist,
 
-    #[fail(display = "Error creating a database connection.")]
+    #[fail(display = "Error creating a database connection: {}", _0)]
     ConnectionError(Error),
 
+    #[fail(
+        display = "Timed out after {}ms waiting for a connection, all {} of {} connections are in use. Consider raising the connectionLimit of the database.",
+        waited_ms, in_use, connection_limit
+    )]
+    ConnectionPoolExhausted {
+        connection_limit: u32,
+        in_use: u32,
+        waited_ms: u64,
+    },
+
+    #[fail(
+        display = "The engine is overloaded, all {} queries allowed at the same time are running.",
+        max_in_flight
+    )]
+    Overloaded { max_in_flight: usize },
+
+    #[fail(display = "The query was cancelled.")]
+    QueryCancelled,
+
     #[fail(display = "Error querying the database: {}", _0)]
     QueryError(Error),
 
@@ -69,9 +88,23 @@ pub enum ConnectorError {
     #[fail(display = "The column value was different from the model")]
     ColumnReadFailure(Error),
 
+    #[fail(
+        display = "Could not convert column {} from {} to {}: {}",
+        index, actual, expected, reason
+    )]
+    ColumnConversionFailure {
+        index: usize,
+        expected: String,
+        actual: String,
+        reason: String,
+    },
+
     #[fail(display = "Field cannot be null: {}", field)]
     FieldCannotBeNull { field: String },
 
+    #[fail(display = "Value too long for column {} (max {})", column, max_length)]
+    ValueTooLong { column: String, max_length: usize },
+
     #[fail(display = "{}", _0)]
     DomainError(DomainError),
 
@@ -88,6 +121,14 @@ pub enum ConnectorError {
         model_b_name: String,
     },
 
+    /// SQLite doesn't tell which foreign key failed, so all relations of the
+    /// model written to are named.
+    #[fail(
+        display = "Foreign key constraint failed on the model {}, for one of the relations {:?}",
+        model, relations
+    )]
+    ForeignKeyViolation { model: String, relations: Vec<String> },
+
     #[fail(
         display = "The relation {} has no node for the model {} connected to a Node for the model {} on your mutation path.",
         relation_name, parent_name, child_name
@@ -102,6 +143,68 @@ pub enum ConnectorError {
 
     #[fail(display = "Conversion error: {}", _0)]
     ConversionError(Error),
+
+    #[fail(
+        display = "Batch failed at statement {} of {} after writing {} rows, all changes were rolled back: {}",
+        index, total, rows_written, cause
+    )]
+    BatchFailure {
+        index: usize,
+        total: usize,
+        rows_written: usize,
+        cause: Box<ConnectorError>,
+    },
+
+    #[fail(display = "{} is not supported by version {} of the database.", feature, version)]
+    UnsupportedByDatabase { feature: String, version: String },
+
+    #[fail(
+        display = "The model {} is read from a database view and can't be written to.",
+        model
+    )]
+    ReadOnlyModel { model: String },
+
+    #[fail(display = "The database does not match the data model:\n{}", report)]
+    SchemaDrift { problems: usize, report: String },
+
+    #[fail(display = "The operation has no tenant, but the database is routed by tenant.")]
+    TenantRequired,
+
+    #[fail(
+        display = "The tenant `{}` is invalid, only letters, digits, `-` and `_` are allowed.",
+        tenant
+    )]
+    InvalidTenant { tenant: String },
+
+    #[fail(
+        display = "The model {} is sharded by the field {}, which must be set when creating a record.",
+        model, field
+    )]
+    ShardKeyRequired { model: String, field: String },
+
+    #[fail(
+        display = "The field {} of the model {} is its shard key and can't be changed.",
+        field, model
+    )]
+    ShardKeyChanged { model: String, field: String },
+
+    #[fail(display = "The cursor is invalid or was not issued by this server.")]
+    InvalidCursor,
+
+    #[fail(
+        display = "The statement has {} outside of parameters, which strict mode rejects.",
+        found
+    )]
+    UnparameterizedSql { found: &'static str },
+
+    #[fail(display = "The operation was denied: {}", reason)]
+    OperationDenied { reason: String },
+
+    #[fail(
+        display = "The field {} of the model {} can't be filtered by with this condition.",
+        field, model
+    )]
+    UnsupportedCondition { model: String, field: String },
 }
 
 impl From<DomainError> for ConnectorError {
@@ -123,6 +226,137 @@ impl From<r2d2::Error> for ConnectorError {
     }
 }
 
+impl ConnectorError {
+    /// Names the model a statement wrote to and its relations in a foreign
+    /// key violation, which the database reports without them. Other errors
+    /// are returned as-is.
+    pub fn on_model(self, model: &Model) -> Self {
+        match self {
+            ConnectorError::ForeignKeyViolation { .. } => {
+                let mut relations: Vec<String> = model
+                    .fields()
+                    .relation()
+                    .iter()
+                    .map(|field| field.relation().name.clone())
+                    .collect();
+
+                relations.sort();
+                relations.dedup();
+
+                ConnectorError::ForeignKeyViolation {
+                    model: model.name.clone(),
+                    relations,
+                }
+            }
+            error => error,
+        }
+    }
+
+    /// A stable, machine-readable code for the kind of the error. Codes
+    /// starting with `CONNECTION_` mean the database could not be reached,
+    /// all others are failures of a query or its input.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConnectorError::ConnectionError(_) => "CONNECTION_ERROR",
+            ConnectorError::InvalidConnectionArguments => "CONNECTION_INVALID_ARGUMENTS",
+            ConnectorError::ConnectionPoolExhausted { .. } => "CONNECTION_POOL_EXHAUSTED",
+            ConnectorError::Overloaded { .. } => "ENGINE_OVERLOADED",
+            ConnectorError::QueryCancelled => "QUERY_CANCELLED",
+            ConnectorError::QueryError(_) => "QUERY_ERROR",
+            ConnectorError::UniqueConstraintViolation { .. } => "UNIQUE_CONSTRAINT_VIOLATION",
+            ConnectorError::NodeDoesNotExist => "NODE_DOES_NOT_EXIST",
+            ConnectorError::ColumnDoesNotExist => "COLUMN_DOES_NOT_EXIST",
+            ConnectorError::ColumnReadFailure(_) => "COLUMN_READ_FAILURE",
+            ConnectorError::ColumnConversionFailure { .. } => "COLUMN_CONVERSION_FAILURE",
+            ConnectorError::FieldCannotBeNull { .. } => "FIELD_CANNOT_BE_NULL",
+            ConnectorError::ValueTooLong { .. } => "VALUE_TOO_LONG",
+            ConnectorError::DomainError(_) => "DOMAIN_ERROR",
+            ConnectorError::NodeNotFoundForWhere(_) => "NODE_NOT_FOUND_FOR_WHERE",
+            ConnectorError::RelationViolation { .. } => "RELATION_VIOLATION",
+            ConnectorError::ForeignKeyViolation { .. } => "FOREIGN_KEY_VIOLATION",
+            ConnectorError::NodesNotConnected { .. } => "NODES_NOT_CONNECTED",
+            ConnectorError::ConversionError(_) => "CONVERSION_ERROR",
+            ConnectorError::BatchFailure { .. } => "BATCH_FAILURE",
+            ConnectorError::UnsupportedByDatabase { .. } => "UNSUPPORTED_BY_DATABASE",
+            ConnectorError::ReadOnlyModel { .. } => "READ_ONLY_MODEL",
+            ConnectorError::SchemaDrift { .. } => "SCHEMA_DRIFT",
+            ConnectorError::TenantRequired => "TENANT_REQUIRED",
+            ConnectorError::InvalidTenant { .. } => "INVALID_TENANT",
+            ConnectorError::ShardKeyRequired { .. } => "SHARD_KEY_REQUIRED",
+            ConnectorError::ShardKeyChanged { .. } => "SHARD_KEY_CHANGED",
+            ConnectorError::InvalidCursor => "INVALID_CURSOR",
+            ConnectorError::UnparameterizedSql { .. } => "UNPARAMETERIZED_SQL",
+            ConnectorError::OperationDenied { .. } => "OPERATION_DENIED",
+            ConnectorError::UnsupportedCondition { .. } => "UNSUPPORTED_CONDITION",
+        }
+    }
+
+    /// True if the database could not be reached, as opposed to a query
+    /// failing.
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            ConnectorError::ConnectionError(_)
+            | ConnectorError::ConnectionPoolExhausted { .. }
+            | ConnectorError::InvalidConnectionArguments => true,
+            _ => false,
+        }
+    }
+
+    /// True for errors that might not happen again when retrying the same
+    /// operation, such as a lost connection or a database locked by another
+    /// writer.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ConnectorError::ConnectionError(_)
+            | ConnectorError::ConnectionPoolExhausted { .. }
+            | ConnectorError::Overloaded { .. } => true,
+            ConnectorError::BatchFailure { cause, .. } => cause.is_retryable(),
+            #[cfg(feature = "sqlite")]
+            ConnectorError::QueryError(e) => {
+                e.iter_chain()
+                    .any(|cause| match cause.downcast_ref::<rusqlite::Error>() {
+                        Some(rusqlite::Error::SqliteFailure(ffi::Error { code, .. }, _)) => match code {
+                            ffi::ErrorCode::DatabaseBusy | ffi::ErrorCode::DatabaseLocked => true,
+                            _ => false,
+                        },
+                        _ => false,
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses the description of a failed unique index or primary key, e.g.
+    /// `UNIQUE constraint failed: User.firstName, User.lastName`, into the
+    /// constraint (`User.firstName, User.lastName`) and the violated columns
+    /// (`firstName` and `lastName`).
+    #[cfg(feature = "sqlite")]
+    fn sqlite_unique_violation(description: &str) -> ConnectorError {
+        let constraint = description.splitn(2, ": ").last().unwrap_or(description);
+
+        let fields = constraint
+            .split(", ")
+            .map(|column| column.rsplit('.').next().unwrap_or(column).to_string())
+            .collect();
+
+        ConnectorError::UniqueConstraintViolation {
+            constraint: constraint.to_string(),
+            fields,
+        }
+    }
+
+    /// Parses the column out of a failed not null constraint, e.g.
+    /// `NOT NULL constraint failed: User.name`.
+    #[cfg(feature = "sqlite")]
+    fn sqlite_null_violation(description: &str) -> ConnectorError {
+        let column = description.splitn(2, ": ").last().unwrap_or(description);
+
+        ConnectorError::FieldCannotBeNull {
+            field: column.rsplit('.').next().unwrap_or(column).to_string(),
+        }
+    }
+}
+
 #[cfg(feature = "sqlite")]
 impl From<rusqlite::Error> for ConnectorError {
     fn from(e: rusqlite::Error) -> ConnectorError {
@@ -135,13 +369,7 @@ impl From<rusqlite::Error> for ConnectorError {
                     extended_code: 2067,
                 },
                 Some(description),
-            ) => {
-                let splitted: Vec<&str> = description.split(": ").collect();
-
-                ConnectorError::UniqueConstraintViolation {
-                    field_name: splitted[1].into(),
-                }
-            }
+            ) => ConnectorError::sqlite_unique_violation(&description),
 
             rusqlite::Error::SqliteFailure(
                 ffi::Error {
@@ -149,12 +377,41 @@ impl From<rusqlite::Error> for ConnectorError {
                     extended_code: 1555,
                 },
                 Some(description),
-            ) => {
-                let splitted: Vec<&str> = description.split(": ").collect();
+            ) => ConnectorError::sqlite_unique_violation(&description),
 
-                ConnectorError::UniqueConstraintViolation {
-                    field_name: splitted[1].into(),
-                }
+            rusqlite::Error::SqliteFailure(
+                ffi::Error {
+                    code: ffi::ErrorCode::ConstraintViolation,
+                    extended_code: 787,
+                },
+                _,
+            ) => ConnectorError::ForeignKeyViolation {
+                model: String::new(),
+                relations: Vec::new(),
+            },
+
+            rusqlite::Error::SqliteFailure(
+                ffi::Error {
+                    code: ffi::ErrorCode::ConstraintViolation,
+                    extended_code: 1299,
+                },
+                Some(description),
+            ) => ConnectorError::sqlite_null_violation(&description),
+
+            rusqlite::Error::SqliteFailure(
+                ffi::Error {
+                    code: ffi::ErrorCode::OperationInterrupted,
+                    ..
+                },
+                _,
+            ) => ConnectorError::QueryCancelled,
+
+            rusqlite::Error::SqliteFailure(ffi::Error { code, .. }, _)
+                if code == ffi::ErrorCode::CannotOpen
+                    || code == ffi::ErrorCode::NotADatabase
+                    || code == ffi::ErrorCode::PermissionDenied =>
+            {
+                ConnectorError::ConnectionError(e.into())
             }
 
             e => ConnectorError::QueryError(e.into()),
@@ -22,7 +22,9 @@ pub enum Filter {
     Scalar(ScalarFilter),
     ScalarList(ScalarListFilter),
     OneRelationIsNull(OneRelationIsNullFilter),
+    OneRelationIsNotNull(OneRelationIsNotNullFilter),
     Relation(RelationFilter),
+    RelationCount(RelationCountFilter),
     NodeSubscription,
     BoolFilter(bool),
 }
@@ -59,12 +61,24 @@ impl From<OneRelationIsNullFilter> for Filter {
     }
 }
 
+impl From<OneRelationIsNotNullFilter> for Filter {
+    fn from(sf: OneRelationIsNotNullFilter) -> Self {
+        Filter::OneRelationIsNotNull(sf)
+    }
+}
+
 impl From<RelationFilter> for Filter {
     fn from(sf: RelationFilter) -> Self {
         Filter::Relation(sf)
     }
 }
 
+impl From<RelationCountFilter> for Filter {
+    fn from(sf: RelationCountFilter) -> Self {
+        Filter::RelationCount(sf)
+    }
+}
+
 impl From<bool> for Filter {
     fn from(b: bool) -> Self {
         Filter::BoolFilter(b)
@@ -79,3 +93,14 @@ impl From<NodeSelector> for Filter {
         })
     }
 }
+
+impl From<RecordSelector> for Filter {
+    fn from(record_selector: RecordSelector) -> Self {
+        match record_selector {
+            RecordSelector::Field(node_selector) => Filter::from(node_selector),
+            RecordSelector::Compound(node_selectors) => {
+                Filter::and(node_selectors.into_iter().map(Filter::from).collect())
+            }
+        }
+    }
+}
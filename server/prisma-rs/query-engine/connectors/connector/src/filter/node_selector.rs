@@ -30,3 +30,28 @@ impl NodeSelector {
         }
     }
 }
+
+/// Selects a single record, by a unique field or by all fields of a unique
+/// index.
+#[derive(Debug, Clone)]
+pub enum RecordSelector {
+    Field(NodeSelector),
+    /// The values of all fields of a compound unique index, e.g. `firstName`
+    /// and `lastName`. Never empty.
+    Compound(Vec<NodeSelector>),
+}
+
+impl RecordSelector {
+    pub fn model(&self) -> ModelRef {
+        match self {
+            RecordSelector::Field(node_selector) => node_selector.field.model(),
+            RecordSelector::Compound(node_selectors) => node_selectors[0].field.model(),
+        }
+    }
+}
+
+impl From<NodeSelector> for RecordSelector {
+    fn from(node_selector: NodeSelector) -> Self {
+        RecordSelector::Field(node_selector)
+    }
+}
@@ -15,6 +15,29 @@ pub struct OneRelationIsNullFilter {
     pub field: Arc<RelationField>,
 }
 
+/// The record is connected through the to-one relation `field`.
+#[derive(Debug, Clone)]
+pub struct OneRelationIsNotNullFilter {
+    pub field: Arc<RelationField>,
+}
+
+/// Compares the number of records related through `field`, without
+/// filtering the related records.
+#[derive(Debug, Clone)]
+pub struct RelationCountFilter {
+    pub field: Arc<RelationField>,
+    pub condition: CountCondition,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CountCondition {
+    Equals(usize),
+    LessThan(usize),
+    LessThanOrEquals(usize),
+    GreaterThan(usize),
+    GreaterThanOrEquals(usize),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RelationCondition {
     EveryRelatedNode,
@@ -263,4 +286,59 @@ impl RelationCompare for Arc<RelationField> {
             field: Arc::clone(self),
         })
     }
+
+    /// One of the relations is not `Null`.
+    /// ```rust
+    /// # use connector::{*, filter::*};
+    /// # use prisma_models::*;
+    /// # use serde_json;
+    /// # use std::{fs::File, sync::Arc};
+    /// #
+    /// # let tmp: SchemaTemplate = serde_json::from_reader(File::open("../sql-connector/test_schema.json").unwrap()).unwrap();
+    /// # let schema = tmp.build(String::from("test"));
+    /// # let user = schema.find_model("User").unwrap();
+    /// #
+    /// let rel_field = user.fields().find_from_relation_fields("sites").unwrap();
+    /// let filter = rel_field.one_relation_is_not_null();
+    ///
+    /// match filter {
+    ///     Filter::OneRelationIsNotNull(OneRelationIsNotNullFilter { field }) =>
+    ///         assert_eq!(String::from("sites"), field.name),
+    ///     _ => unreachable!()
+    /// };
+    /// ```
+    fn one_relation_is_not_null(&self) -> Filter {
+        Filter::from(OneRelationIsNotNullFilter {
+            field: Arc::clone(self),
+        })
+    }
+
+    /// The number of related records matches the condition.
+    /// ```rust
+    /// # use connector::{*, filter::*};
+    /// # use prisma_models::*;
+    /// # use serde_json;
+    /// # use std::{fs::File, sync::Arc};
+    /// #
+    /// # let tmp: SchemaTemplate = serde_json::from_reader(File::open("../sql-connector/test_schema.json").unwrap()).unwrap();
+    /// # let schema = tmp.build(String::from("test"));
+    /// # let user = schema.find_model("User").unwrap();
+    /// #
+    /// let rel_field = user.fields().find_from_relation_fields("sites").unwrap();
+    /// let filter = rel_field.count_related(CountCondition::GreaterThan(5));
+    ///
+    /// match filter {
+    ///     Filter::RelationCount(RelationCountFilter { field, condition }) => {
+    ///         assert_eq!(String::from("sites"), field.name);
+    ///         assert_eq!(CountCondition::GreaterThan(5), condition);
+    ///     }
+    ///     _ => unreachable!()
+    /// };
+    /// ```
+    fn count_related(&self, condition: CountCondition) -> Filter {
+        Filter::from(RelationCountFilter {
+            field: Arc::clone(self),
+            condition,
+        })
+    }
 }
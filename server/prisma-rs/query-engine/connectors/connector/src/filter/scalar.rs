@@ -19,12 +19,20 @@ pub enum ScalarCondition {
     NotStartsWith(PrismaValue),
     EndsWith(PrismaValue),
     NotEndsWith(PrismaValue),
+    /// A `LIKE` pattern, the other string conditions match their value
+    /// literally.
+    Pattern(PrismaValue),
+    NotPattern(PrismaValue),
     LessThan(PrismaValue),
     LessThanOrEquals(PrismaValue),
     GreaterThan(PrismaValue),
     GreaterThanOrEquals(PrismaValue),
     In(Option<Vec<PrismaValue>>),
     NotIn(Option<Vec<PrismaValue>>),
+    /// The values of a long `In` list, moved by the connector to the
+    /// temporary table of this name.
+    InTemporaryTable(String),
+    NotInTemporaryTable(String),
 }
 
 impl ScalarCompare for Arc<ScalarField> {
@@ -314,6 +322,73 @@ impl ScalarCompare for Arc<ScalarField> {
         })
     }
 
+    /// Field contains a match of the `LIKE` pattern, with `%` matching any
+    /// characters, `_` a single one and `\` escaping the next character.
+    /// ```rust
+    /// # use connector::{*, filter::*};
+    /// # use prisma_models::*;
+    /// # use prisma_query::ast::*;
+    /// # use serde_json;
+    /// # use std::{fs::File, sync::Arc};
+    /// #
+    /// # let tmp: SchemaTemplate = serde_json::from_reader(File::open("../sql-connector/test_schema.json").unwrap()).unwrap();
+    /// # let schema = tmp.build(String::from("test"));
+    /// # let model = schema.find_model("User").unwrap();
+    /// #
+    /// let field = model.fields().find_from_scalar("name").unwrap();
+    /// let filter = field.matches_pattern("mu_ti%");
+    ///
+    /// match filter {
+    ///     Filter::Scalar(ScalarFilter { field: field, condition: ScalarCondition::Pattern(val) }) => {
+    ///         assert_eq!(PrismaValue::from("mu_ti%"), val);
+    ///         assert_eq!(String::from("name"), field.name);
+    ///     }
+    ///     _ => unreachable!()
+    /// }
+    /// ```
+    fn matches_pattern<T>(&self, val: T) -> Filter
+    where
+        T: Into<PrismaValue>,
+    {
+        Filter::from(ScalarFilter {
+            field: Arc::clone(self),
+            condition: ScalarCondition::Pattern(val.into()),
+        })
+    }
+
+    /// Field contains no match of the `LIKE` pattern.
+    /// ```rust
+    /// # use connector::{*, filter::*};
+    /// # use prisma_models::*;
+    /// # use prisma_query::ast::*;
+    /// # use serde_json;
+    /// # use std::{fs::File, sync::Arc};
+    /// #
+    /// # let tmp: SchemaTemplate = serde_json::from_reader(File::open("../sql-connector/test_schema.json").unwrap()).unwrap();
+    /// # let schema = tmp.build(String::from("test"));
+    /// # let model = schema.find_model("User").unwrap();
+    /// #
+    /// let field = model.fields().find_from_scalar("name").unwrap();
+    /// let filter = field.not_matches_pattern("mu_ti%");
+    ///
+    /// match filter {
+    ///     Filter::Scalar(ScalarFilter { field: field, condition: ScalarCondition::NotPattern(val) }) => {
+    ///         assert_eq!(PrismaValue::from("mu_ti%"), val);
+    ///         assert_eq!(String::from("name"), field.name);
+    ///     }
+    ///     _ => unreachable!()
+    /// }
+    /// ```
+    fn not_matches_pattern<T>(&self, val: T) -> Filter
+    where
+        T: Into<PrismaValue>,
+    {
+        Filter::from(ScalarFilter {
+            field: Arc::clone(self),
+            condition: ScalarCondition::NotPattern(val.into()),
+        })
+    }
+
     /// Field is less than the given value.
     /// ```rust
     /// # use connector::{*, filter::*};
@@ -6,14 +6,37 @@ pub mod error;
 pub mod filter;
 pub mod mutaction;
 
+mod actor;
+mod cancellation;
+mod capabilities;
+mod change_feed;
 mod compare;
+mod cursor_token;
 mod data_resolver;
 mod database_mutaction_executor;
+mod database_role;
+mod node_cache;
 mod query_arguments;
+mod query_tags;
+mod request_context;
+mod scoped;
+mod session_settings;
+mod tenant;
 
+pub use actor::*;
+pub use cancellation::*;
+pub use capabilities::*;
+pub use change_feed::*;
 pub use compare::*;
+pub use cursor_token::*;
 pub use data_resolver::*;
 pub use database_mutaction_executor::*;
+pub use database_role::*;
+pub use node_cache::*;
 pub use query_arguments::*;
+pub use query_tags::*;
+pub use request_context::*;
+pub use session_settings::*;
+pub use tenant::*;
 
 pub type ConnectorResult<T> = Result<T, error::ConnectorError>;
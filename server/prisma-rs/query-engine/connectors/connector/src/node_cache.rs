@@ -0,0 +1,75 @@
+use crate::scoped::{scoped, with_scoped};
+use prisma_models::prelude::{GraphqlId, SingleNode};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+thread_local! {
+    static NODE_CACHE: RefCell<Option<NodeCache>> = RefCell::new(None);
+}
+
+/// A record read with the fields in the given order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct NodeKey {
+    database: String,
+    model: String,
+    id: GraphqlId,
+    field_names: Vec<String>,
+}
+
+/// The records read by their id in a request, so reading them again, e.g.
+/// for a deeply nested selection, doesn't query the database. Shared by the
+/// threads working on the request and cleared by every mutation.
+#[derive(Debug, Clone, Default)]
+pub struct NodeCache {
+    nodes: Arc<Mutex<HashMap<NodeKey, SingleNode>>>,
+}
+
+impl NodeCache {
+    /// The record, if it was read before with the same `field_names`.
+    pub fn get(&self, database: &str, model: &str, id: &GraphqlId, field_names: &[String]) -> Option<SingleNode> {
+        let key = NodeKey {
+            database: database.to_string(),
+            model: model.to_string(),
+            id: id.clone(),
+            field_names: field_names.to_vec(),
+        };
+
+        self.nodes.lock().ok().and_then(|nodes| nodes.get(&key).cloned())
+    }
+
+    pub fn insert(&self, database: &str, model: &str, id: GraphqlId, node: SingleNode) {
+        let key = NodeKey {
+            database: database.to_string(),
+            model: model.to_string(),
+            id,
+            field_names: node.field_names.clone(),
+        };
+
+        if let Ok(mut nodes) = self.nodes.lock() {
+            nodes.insert(key, node);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut nodes) = self.nodes.lock() {
+            nodes.clear();
+        }
+    }
+}
+
+/// Runs `f` with `cache` for the records read on the current thread, e.g. a
+/// new one for every request.
+pub fn with_node_cache<F, T>(cache: NodeCache, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_scoped(&NODE_CACHE, Some(cache), f)
+}
+
+/// The cache of the current thread, if set with `with_node_cache`.
+pub fn node_cache() -> Option<NodeCache> {
+    scoped(&NODE_CACHE)
+}
@@ -0,0 +1,57 @@
+use crate::scoped::{scoped, with_scoped};
+use std::cell::RefCell;
+
+thread_local! {
+    static QUERY_TAGS: RefCell<Vec<(String, String)>> = RefCell::new(Vec::new());
+}
+
+/// Runs `f` with tags describing the request, such as `operation` and
+/// `traceparent`. Connectors supporting it add the tags of the current thread
+/// to the statements they execute, so the queries can be correlated with the
+/// request on the database side.
+pub fn with_query_tags<F, T>(tags: Vec<(String, String)>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_scoped(&QUERY_TAGS, tags, f)
+}
+
+/// The tags set for the current thread, e.g. to pass them on to other
+/// threads working on the same request.
+pub fn query_tags() -> Vec<(String, String)> {
+    scoped(&QUERY_TAGS)
+}
+
+/// The tags of the current thread as an SQL comment, e.g.
+/// `/* traceparent=00-0af7..., operation=findManyUser */`. Characters other
+/// than alphanumerics and `-_.:` are percent-encoded, so a value can't end the
+/// comment.
+pub fn query_tags_comment() -> Option<String> {
+    QUERY_TAGS.with(|tags| {
+        let tags = tags.borrow();
+
+        if tags.is_empty() {
+            return None;
+        }
+
+        let pairs: Vec<String> = tags
+            .iter()
+            .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+            .collect();
+
+        Some(format!("/* {} */", pairs.join(", ")))
+    })
+}
+
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b':' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
@@ -0,0 +1,80 @@
+use crate::{
+    actor, cancellation, database_role, node_cache, query_tags, session_settings, tenant, with_actor,
+    with_cancellation, with_database_role, with_node_cache, with_query_tags, with_session_settings, with_tenant,
+    CancellationToken, NodeCache,
+};
+
+/// Everything set for the request on the current thread: the query tags,
+/// session settings, cancellation, tenant, database role, actor and node
+/// cache. Threads working on the same request get a copy with `current` and
+/// run with it.
+#[derive(Clone, Default)]
+pub struct RequestContext {
+    tags: Vec<(String, String)>,
+    settings: Vec<(String, String)>,
+    cancellation: Option<CancellationToken>,
+    tenant: Option<String>,
+    role: Option<String>,
+    actor: Option<String>,
+    node_cache: Option<NodeCache>,
+}
+
+impl RequestContext {
+    pub fn current() -> Self {
+        Self {
+            tags: query_tags(),
+            settings: session_settings(),
+            cancellation: cancellation(),
+            tenant: tenant(),
+            role: database_role(),
+            actor: actor(),
+            node_cache: node_cache(),
+        }
+    }
+
+    /// Runs `f` with the context set for the current thread, restoring the
+    /// previous one after.
+    pub fn run<F, T>(self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let Self {
+            tags,
+            settings,
+            cancellation,
+            tenant,
+            role,
+            actor,
+            node_cache,
+        } = self;
+
+        with_query_tags(tags, || {
+            let f = || with_session_settings(settings, f);
+
+            let f = || match cancellation {
+                Some(token) => with_cancellation(token, f),
+                None => f(),
+            };
+
+            let f = || match tenant {
+                Some(tenant) => with_tenant(tenant, f),
+                None => f(),
+            };
+
+            let f = || match role {
+                Some(role) => with_database_role(role, f),
+                None => f(),
+            };
+
+            let f = || match actor {
+                Some(actor) => with_actor(actor, f),
+                None => f(),
+            };
+
+            match node_cache {
+                Some(cache) => with_node_cache(cache, f),
+                None => f(),
+            }
+        })
+    }
+}
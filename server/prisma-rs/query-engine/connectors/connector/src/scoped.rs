@@ -0,0 +1,41 @@
+use std::{cell::RefCell, mem, thread::LocalKey};
+
+/// Puts the previous value back into the thread-local, also when unwinding.
+struct Restore<V: 'static> {
+    key: &'static LocalKey<RefCell<V>>,
+    previous: Option<V>,
+}
+
+impl<V: 'static> Drop for Restore<V> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            self.key.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
+
+/// Runs `f` with `value` in the thread-local `key`, restoring the value of
+/// the outer scope afterwards. For the settings of a request that the
+/// connectors read on the thread running it.
+pub(crate) fn with_scoped<V, F, T>(key: &'static LocalKey<RefCell<V>>, value: V, f: F) -> T
+where
+    V: 'static,
+    F: FnOnce() -> T,
+{
+    let previous = key.with(|current| mem::replace(&mut *current.borrow_mut(), value));
+
+    let _restore = Restore {
+        key,
+        previous: Some(previous),
+    };
+
+    f()
+}
+
+/// A copy of the value in the thread-local `key`.
+pub(crate) fn scoped<V>(key: &'static LocalKey<RefCell<V>>) -> V
+where
+    V: Clone + 'static,
+{
+    key.with(|current| current.borrow().clone())
+}
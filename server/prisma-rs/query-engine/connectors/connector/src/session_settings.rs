@@ -0,0 +1,36 @@
+use crate::scoped::{scoped, with_scoped};
+use std::cell::RefCell;
+
+thread_local! {
+    static SESSION_SETTINGS: RefCell<Vec<(String, String)>> = RefCell::new(Vec::new());
+}
+
+/// Runs `f` with settings describing the request, such as
+/// `app.current_user`. Connectors supporting it make the settings of the
+/// current thread available to the statements of its transactions, e.g. for
+/// views or policies filtering the rows by the user of the request.
+pub fn with_session_settings<F, T>(settings: Vec<(String, String)>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_scoped(&SESSION_SETTINGS, settings, f)
+}
+
+/// The settings set for the current thread, e.g. to pass them on to other
+/// threads working on the same request.
+pub fn session_settings() -> Vec<(String, String)> {
+    scoped(&SESSION_SETTINGS)
+}
+
+/// The value of the setting `name` of the current thread, the last one if set
+/// more than once.
+pub fn session_setting(name: &str) -> Option<String> {
+    SESSION_SETTINGS.with(|settings| {
+        settings
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    })
+}
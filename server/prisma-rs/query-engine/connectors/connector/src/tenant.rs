@@ -0,0 +1,43 @@
+use crate::{
+    error::ConnectorError,
+    scoped::{scoped, with_scoped},
+    ConnectorResult,
+};
+use std::cell::RefCell;
+
+thread_local! {
+    static TENANT: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Runs `f` with `tenant` as the tenant of the current thread. Connectors
+/// serving several tenants route the transactions started in `f` to the
+/// database of the tenant.
+pub fn with_tenant<F, T>(tenant: String, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_scoped(&TENANT, Some(tenant), f)
+}
+
+/// The tenant of the current thread, if set with `with_tenant`.
+pub fn tenant() -> Option<String> {
+    scoped(&TENANT)
+}
+
+/// The tenant of the current thread, failing if none is set or if it has
+/// characters other than letters, digits, `-` and `_`. Tenants usually end up
+/// in names of databases or files.
+pub fn require_tenant() -> ConnectorResult<String> {
+    let tenant = tenant().ok_or(ConnectorError::TenantRequired)?;
+
+    let is_valid = !tenant.is_empty()
+        && tenant
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if is_valid {
+        Ok(tenant)
+    } else {
+        Err(ConnectorError::InvalidTenant { tenant })
+    }
+}
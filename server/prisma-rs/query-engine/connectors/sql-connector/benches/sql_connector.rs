@@ -0,0 +1,143 @@
+#[macro_use]
+extern crate criterion;
+
+use connector::{filter::*, DataResolver, QueryArguments};
+use criterion::Criterion;
+use prisma_models::*;
+use prisma_query::visitor::{self, Visitor};
+use rusqlite::{Connection, NO_PARAMS};
+use sql_connector::{
+    database::{SqlDatabase, Sqlite},
+    internals::{AliasedCondition, SelectDefinition},
+    ToPrismaRow,
+};
+use std::{env, fs::File};
+
+const DB_NAME: &str = "sql_connector_bench";
+const ROWS: usize = 1000;
+
+/// The `User` model of the test schema. Models only keep a weak reference
+/// to their schema, so the schema must be kept alive with it.
+fn user_model() -> (SchemaRef, ModelRef) {
+    let template: SchemaTemplate = serde_json::from_reader(File::open("./test_schema.json").unwrap()).unwrap();
+    let schema = template.build(String::from(DB_NAME));
+    let model = schema.find_model("User").unwrap();
+
+    (schema, model)
+}
+
+/// A filter with a few conditions on the same field, like a typical search.
+fn filter(model: &ModelRef) -> Filter {
+    let name = model.fields().find_from_scalar("name").unwrap();
+
+    Filter::and(vec![
+        name.starts_with("foo"),
+        name.not_equals("foobar"),
+        Filter::or(vec![name.contains("bar"), name.ends_with("baz")]),
+    ])
+}
+
+fn query_building(c: &mut Criterion) {
+    let (schema, model) = user_model();
+
+    c.bench_function("build and visit a filtered select", move |b| {
+        let _schema = &schema;
+
+        b.iter(|| {
+            let query_arguments = QueryArguments::from(filter(&model));
+            visitor::Sqlite::build(query_arguments.into_select(model.clone()))
+        })
+    });
+}
+
+fn filter_conversion(c: &mut Criterion) {
+    let (schema, model) = user_model();
+
+    c.bench_function("convert a filter to a condition tree", move |b| {
+        let _schema = &schema;
+        b.iter(|| filter(&model).aliased_cond(None))
+    });
+}
+
+fn row_conversion(c: &mut Criterion) {
+    let conn = Connection::open_in_memory().unwrap();
+
+    conn.execute_batch("CREATE TABLE wide (a TEXT, b INTEGER, c REAL, d INTEGER, e TEXT, f TEXT, g INTEGER, h TEXT);")
+        .unwrap();
+
+    for i in 0..ROWS {
+        conn.execute(
+            "INSERT INTO wide VALUES (?, ?, 1.5, 1, 'ENUM', '{\"a\":1}', 1550000000000, 'text')",
+            &[&format!("id{}", i) as &rusqlite::types::ToSql, &(i as i64)],
+        )
+        .unwrap();
+    }
+
+    let idents = [
+        TypeIdentifier::GraphQLID,
+        TypeIdentifier::Int,
+        TypeIdentifier::Float,
+        TypeIdentifier::Boolean,
+        TypeIdentifier::Enum,
+        TypeIdentifier::Json,
+        TypeIdentifier::DateTime,
+        TypeIdentifier::String,
+    ];
+
+    c.bench_function("convert a wide result set", move |b| {
+        b.iter(|| {
+            let mut stmt = conn.prepare_cached("SELECT * FROM wide").unwrap();
+            let mut rows = stmt.query(NO_PARAMS).unwrap();
+            let mut result = Vec::with_capacity(ROWS);
+
+            while let Some(row) = rows.next() {
+                result.push(row.unwrap().to_prisma_row(&idents).unwrap());
+            }
+
+            result
+        })
+    });
+}
+
+fn sqlite_execution(c: &mut Criterion) {
+    let folder = env::temp_dir();
+    let conn = Connection::open(folder.join(format!("{}.db", DB_NAME))).unwrap();
+
+    conn.execute_batch("DROP TABLE IF EXISTS User; CREATE TABLE User (id TEXT PRIMARY KEY, name TEXT NOT NULL);")
+        .unwrap();
+
+    for i in 0..ROWS {
+        conn.execute(
+            "INSERT INTO User VALUES (?, ?)",
+            &[&format!("id{:04}", i), &format!("foo{}", i)],
+        )
+        .unwrap();
+    }
+
+    let sqlite = Sqlite::new(folder.to_string_lossy().into_owned(), 1, false).unwrap();
+    let database = SqlDatabase::new(sqlite);
+    let (schema, model) = user_model();
+    let selected_fields = SelectedFields::from(model.clone());
+
+    c.bench_function("find many users in SQLite", move |b| {
+        let _schema = &schema;
+
+        b.iter(|| {
+            let mut query_arguments = QueryArguments::from(filter(&model));
+            query_arguments.first = Some(100);
+
+            database
+                .get_nodes(model.clone(), query_arguments, &selected_fields)
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    query_building,
+    filter_conversion,
+    row_conversion,
+    sqlite_execution
+);
+criterion_main!(benches);
@@ -0,0 +1,163 @@
+use crate::{ChangeEvent, ChangeOperation, Transaction};
+use chrono::{DateTime, Utc};
+use connector::ConnectorResult;
+use prisma_models::prelude::*;
+use prisma_query::ast::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A change of a committed mutation, with who made it and when.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    #[serde(flatten)]
+    pub change: ChangeEvent,
+    /// Set with `with_actor` for the request.
+    pub actor: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Receives the audit entries of every committed mutation.
+pub trait AuditSink: Send + Sync {
+    /// Called once per transaction after it committed, with its entries in
+    /// the order the changes were made. A slow sink delays the response of
+    /// the mutation.
+    fn record(&self, entries: &[AuditEntry]);
+}
+
+impl<F> AuditSink for F
+where
+    F: Fn(&[AuditEntry]) + Send + Sync,
+{
+    fn record(&self, entries: &[AuditEntry]) {
+        self(entries)
+    }
+}
+
+#[derive(Clone)]
+enum Target {
+    Table(String),
+    Sink(Arc<AuditSink>),
+}
+
+/// Records the changes of every mutation, nested writes included. Resetting
+/// the data is not recorded.
+///
+/// Entries written to a table are written in the transaction of the
+/// mutation, so they exist if and only if the mutation committed. The table
+/// must exist in the database of the data model:
+///
+/// ```sql
+/// CREATE TABLE "Audit" (
+///     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+///     "model" TEXT NOT NULL,
+///     "operation" TEXT NOT NULL,
+///     "recordId" TEXT NOT NULL,
+///     "changedFields" TEXT NOT NULL,
+///     "actor" TEXT,
+///     "timestamp" INTEGER NOT NULL
+/// );
+/// ```
+#[derive(Clone)]
+pub struct AuditLog {
+    target: Target,
+}
+
+impl AuditLog {
+    pub const MODEL_COLUMN: &'static str = "model";
+    /// `create`, `update` or `delete`.
+    pub const OPERATION_COLUMN: &'static str = "operation";
+    pub const RECORD_ID_COLUMN: &'static str = "recordId";
+    /// A JSON array of the names of the fields.
+    pub const CHANGED_FIELDS_COLUMN: &'static str = "changedFields";
+    pub const ACTOR_COLUMN: &'static str = "actor";
+    /// Milliseconds since the epoch.
+    pub const TIMESTAMP_COLUMN: &'static str = "timestamp";
+
+    /// Writes the entries to `table`.
+    pub fn table<S>(table: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            target: Target::Table(table.into()),
+        }
+    }
+
+    /// Gives the entries to `sink` once the mutation committed.
+    pub fn sink<S>(sink: S) -> Self
+    where
+        S: AuditSink + 'static,
+    {
+        Self {
+            target: Target::Sink(Arc::new(sink)),
+        }
+    }
+
+    /// The entries of the `changes`, made now by `actor`.
+    pub fn entries(changes: &[ChangeEvent], actor: Option<&str>) -> Vec<AuditEntry> {
+        let timestamp = Utc::now();
+
+        changes
+            .iter()
+            .map(|change| AuditEntry {
+                change: change.clone(),
+                actor: actor.map(String::from),
+                timestamp,
+            })
+            .collect()
+    }
+
+    /// Adds the entries of `changes` to the table, in the transaction writing
+    /// them. Sinks get them with `record` after the commit.
+    pub(crate) fn write(
+        &self,
+        conn: &mut Transaction,
+        db_name: &str,
+        changes: &[ChangeEvent],
+        actor: Option<&str>,
+    ) -> ConnectorResult<()> {
+        let table = match self.target {
+            Target::Table(ref table) => table,
+            Target::Sink(_) => return Ok(()),
+        };
+
+        for entry in Self::entries(changes, actor) {
+            let operation = match entry.change.operation {
+                ChangeOperation::Create => "create",
+                ChangeOperation::Update => "update",
+                ChangeOperation::Delete => "delete",
+            };
+
+            let actor = match entry.actor {
+                Some(actor) => PrismaValue::String(actor),
+                None => PrismaValue::Null,
+            };
+
+            let insert = Insert::single_into((db_name, table.as_str()))
+                .value(Self::MODEL_COLUMN, PrismaValue::String(entry.change.model))
+                .value(Self::OPERATION_COLUMN, PrismaValue::String(operation.to_string()))
+                .value(Self::RECORD_ID_COLUMN, entry.change.id)
+                .value(
+                    Self::CHANGED_FIELDS_COLUMN,
+                    PrismaValue::String(serde_json::to_string(&entry.change.changed_fields)?),
+                )
+                .value(Self::ACTOR_COLUMN, actor)
+                .value(
+                    Self::TIMESTAMP_COLUMN,
+                    PrismaValue::Int(entry.timestamp.timestamp_millis()),
+                );
+
+            conn.insert(insert)?;
+        }
+
+        Ok(())
+    }
+
+    /// Gives the entries of the committed `changes` to the sink.
+    pub(crate) fn record(&self, changes: &[ChangeEvent], actor: Option<&str>) {
+        if let Target::Sink(ref sink) = self.target {
+            sink.record(&Self::entries(changes, actor));
+        }
+    }
+}
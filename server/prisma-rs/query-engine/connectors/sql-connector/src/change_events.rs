@@ -0,0 +1,141 @@
+use parking_lot::Mutex;
+use prisma_models::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::Sender;
+
+/// A record written by a mutation, published once the transaction committed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEvent {
+    pub model: String,
+    pub operation: ChangeOperation,
+    pub id: GraphqlId,
+    /// The fields given to the mutation, empty for deletes.
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+impl ChangeEvent {
+    pub fn written<S>(
+        model: &Model,
+        operation: ChangeOperation,
+        id: GraphqlId,
+        non_list_args: &PrismaArgs,
+        list_args: &[(S, PrismaListValue)],
+    ) -> Self
+    where
+        S: AsRef<str>,
+    {
+        let changed_fields = non_list_args
+            .keys()
+            .cloned()
+            .chain(list_args.iter().map(|(name, _)| name.as_ref().to_string()))
+            .collect();
+
+        Self {
+            model: model.name.clone(),
+            operation,
+            id,
+            changed_fields,
+        }
+    }
+
+    pub fn deleted(model: &Model, id: GraphqlId) -> Self {
+        Self {
+            model: model.name.clone(),
+            operation: ChangeOperation::Delete,
+            id,
+            changed_fields: Vec::new(),
+        }
+    }
+}
+
+/// Receives the changes of every committed mutation, e.g. to notify other
+/// systems without them polling the database. Resetting the data publishes
+/// no changes.
+pub trait ChangeSink: Send + Sync {
+    /// Called once per transaction with its changes in the order they were
+    /// made, nested writes included. A slow sink delays the response of the
+    /// mutation.
+    fn publish(&self, changes: &[ChangeEvent]);
+}
+
+impl<F> ChangeSink for F
+where
+    F: Fn(&[ChangeEvent]) + Send + Sync,
+{
+    fn publish(&self, changes: &[ChangeEvent]) {
+        self(changes)
+    }
+}
+
+/// Sends every change to a channel. Changes are dropped once the receiver is
+/// gone.
+pub struct ChannelSink {
+    sender: Mutex<Sender<ChangeEvent>>,
+}
+
+impl ChannelSink {
+    pub fn new(sender: Sender<ChangeEvent>) -> Self {
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+}
+
+impl ChangeSink for ChannelSink {
+    fn publish(&self, changes: &[ChangeEvent]) {
+        let sender = self.sender.lock();
+
+        for change in changes {
+            if sender.send(change.clone()).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// A row written by a statement of the engine, as reported by the update
+/// hook of SQLite. Rows of tables without a rowid are not reported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowChange {
+    pub operation: RowOperation,
+    /// The name the database is attached as.
+    pub database: String,
+    pub table: String,
+    pub row_id: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RowOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Receives the rows changed by every committed transaction, including the
+/// rows of relation and list tables. For embedding applications reacting to
+/// changes on the level of tables instead of models.
+pub trait RowChangeListener: Send + Sync {
+    /// Called once per transaction after it committed, with the rows in the
+    /// order they were written.
+    fn row_changes(&self, changes: &[RowChange]);
+}
+
+impl<F> RowChangeListener for F
+where
+    F: Fn(&[RowChange]) + Send + Sync,
+{
+    fn row_changes(&self, changes: &[RowChange]) {
+        self(changes)
+    }
+}
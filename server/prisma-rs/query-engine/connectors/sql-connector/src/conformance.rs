@@ -0,0 +1,344 @@
+use crate::{Transaction, Transactional};
+use connector::{error::ConnectorError, ConnectorResult};
+use prisma_models::{PrismaValue, TypeIdentifier};
+use prisma_query::ast::*;
+
+/// A scenario of the conformance suite and how it went.
+#[derive(Debug)]
+pub struct ScenarioOutcome {
+    pub scenario: &'static str,
+    pub result: ConnectorResult<()>,
+}
+
+impl ScenarioOutcome {
+    pub fn passed(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Runs the same scenarios of CRUD, filters, pagination, joins and
+/// transactions against any `Transactional`, to catch databases rendering or
+/// executing the queries of the engine differently. The scenarios only use
+/// the query AST and the `Transaction` trait, never dialect specific SQL.
+///
+/// The suite writes to two tables in `db`, created beforehand and emptied by
+/// every scenario:
+///
+/// ```sql
+/// CREATE TABLE "ConformanceParent" (
+///     "id" INTEGER PRIMARY KEY,
+///     "name" TEXT NOT NULL,
+///     "rank" INTEGER
+/// );
+///
+/// CREATE TABLE "ConformanceChild" (
+///     "id" INTEGER PRIMARY KEY,
+///     "parent" INTEGER REFERENCES "ConformanceParent" ("id"),
+///     "name" TEXT NOT NULL
+/// );
+/// ```
+pub struct ConformanceSuite<'a, T>
+where
+    T: Transactional,
+{
+    executor: &'a T,
+    db_name: String,
+}
+
+const PARENT_TABLE: &str = "ConformanceParent";
+const CHILD_TABLE: &str = "ConformanceChild";
+
+/// The identifiers of the columns `id`, `name` and `rank` of the parents.
+const PARENT_IDENTS: &[TypeIdentifier] = &[TypeIdentifier::Int, TypeIdentifier::String, TypeIdentifier::Int];
+
+impl<'a, T> ConformanceSuite<'a, T>
+where
+    T: Transactional,
+{
+    pub fn new<S>(executor: &'a T, db_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            executor,
+            db_name: db_name.into(),
+        }
+    }
+
+    /// Runs all scenarios, also after one of them failed.
+    pub fn run(&self) -> Vec<ScenarioOutcome> {
+        let scenarios: Vec<(&'static str, fn(&Self) -> ConnectorResult<()>)> = vec![
+            ("crud", Self::crud),
+            ("filters", Self::filters),
+            ("pagination", Self::pagination),
+            ("relations", Self::relations),
+            ("transactions", Self::transactions),
+        ];
+
+        scenarios
+            .into_iter()
+            .map(|(scenario, run)| ScenarioOutcome {
+                scenario,
+                result: self.reset().and_then(|_| run(self)),
+            })
+            .collect()
+    }
+
+    /// The failed scenarios, none if the database conforms.
+    pub fn failures(&self) -> Vec<ScenarioOutcome> {
+        self.run().into_iter().filter(|outcome| !outcome.passed()).collect()
+    }
+
+    fn crud(&self) -> ConnectorResult<()> {
+        self.transaction(|conn, suite| {
+            let id = conn.insert(suite.parent(1, "alice", Some(1)))?;
+            expect("create returns the inserted id", 1, id)?;
+
+            let rows = suite.parents(conn, ConditionTree::NoCondition)?;
+            expect("reading the created row", vec![parent_row(1, "alice", Some(1))], rows)?;
+
+            let update = Update::table(suite.table(PARENT_TABLE))
+                .set("name", PrismaValue::String("bob".into()))
+                .so_that(Column::from("id").equals(PrismaValue::Int(1)));
+
+            expect("updated rows", 1, conn.update(update)?)?;
+
+            let rows = suite.parents(conn, ConditionTree::NoCondition)?;
+            expect("reading the updated row", vec![parent_row(1, "bob", Some(1))], rows)?;
+
+            let delete =
+                Delete::from_table(suite.table(PARENT_TABLE)).so_that(Column::from("id").equals(PrismaValue::Int(1)));
+
+            expect("deleted rows", 1, conn.delete(delete)?)?;
+            expect("rows after deleting", 0, suite.count(conn, PARENT_TABLE)?)
+        })
+    }
+
+    fn filters(&self) -> ConnectorResult<()> {
+        self.transaction(|conn, suite| {
+            conn.insert(suite.parent(1, "alice", Some(1)))?;
+            conn.insert(suite.parent(2, "bob", Some(2)))?;
+            conn.insert(suite.parent(3, "carol", None))?;
+
+            let ids = |conn: &mut Transaction, condition: ConditionTree| -> ConnectorResult<Vec<i64>> {
+                let rows = suite.parents(conn, condition)?;
+                Ok(rows.into_iter().map(|row| row[0].clone()).filter_map(as_int).collect())
+            };
+
+            let equals = Column::from("name").equals(PrismaValue::String("bob".into()));
+            expect("equals", vec![2], ids(conn, equals.into())?)?;
+
+            let within = Column::from("id").in_selection(vec![PrismaValue::Int(1), PrismaValue::Int(3)]);
+            expect("in", vec![1, 3], ids(conn, within.into())?)?;
+
+            let null = Column::from("rank").is_null();
+            expect("is null", vec![3], ids(conn, null.into())?)?;
+
+            let less = Column::from("rank").less_than(PrismaValue::Int(2));
+            expect("less than, skipping null", vec![1], ids(conn, less.into())?)?;
+
+            let either = ConditionTree::or(
+                Column::from("name").equals(PrismaValue::String("alice".into())).into(),
+                Column::from("rank").is_null().into(),
+            );
+
+            expect("or", vec![1, 3], ids(conn, either)?)
+        })
+    }
+
+    fn pagination(&self) -> ConnectorResult<()> {
+        self.transaction(|conn, suite| {
+            for id in 1..=5 {
+                conn.insert(suite.parent(id, &format!("parent {}", id), Some(6 - id)))?;
+            }
+
+            expect(
+                "first page ascending",
+                vec![5, 4],
+                suite.page(conn, Column::from("rank").ascend(), 0)?,
+            )?;
+            expect(
+                "second page ascending",
+                vec![3, 2],
+                suite.page(conn, Column::from("rank").ascend(), 2)?,
+            )?;
+            expect(
+                "last page ascending",
+                vec![1],
+                suite.page(conn, Column::from("rank").ascend(), 4)?,
+            )?;
+            expect(
+                "first page descending",
+                vec![1, 2],
+                suite.page(conn, Column::from("rank").descend(), 0)?,
+            )?;
+            expect(
+                "past the end",
+                Vec::<i64>::new(),
+                suite.page(conn, Column::from("rank").ascend(), 5)?,
+            )
+        })
+    }
+
+    fn relations(&self) -> ConnectorResult<()> {
+        self.transaction(|conn, suite| {
+            conn.insert(suite.parent(1, "alice", None))?;
+            conn.insert(suite.parent(2, "bob", None))?;
+
+            for (id, parent, name) in &[
+                (1, Some(1), "a1"),
+                (2, Some(1), "a2"),
+                (3, Some(2), "b1"),
+                (4, None, "none"),
+            ] {
+                let insert = Insert::single_into(suite.table(CHILD_TABLE))
+                    .value("id", PrismaValue::Int(*id))
+                    .value("parent", parent.map(PrismaValue::Int).unwrap_or(PrismaValue::Null))
+                    .value("name", PrismaValue::String(name.to_string()));
+
+                conn.insert(insert)?;
+            }
+
+            let join = suite
+                .table(PARENT_TABLE)
+                .on(Column::from((CHILD_TABLE, "parent")).equals(Column::from((PARENT_TABLE, "id"))));
+
+            let select = Select::from_table(suite.table(CHILD_TABLE))
+                .column(Column::from((CHILD_TABLE, "name")))
+                .column(Column::from((PARENT_TABLE, "name")))
+                .inner_join(join)
+                .so_that(Column::from((PARENT_TABLE, "name")).equals(PrismaValue::String("alice".into())))
+                .order_by(Column::from((CHILD_TABLE, "id")).ascend());
+
+            let rows = conn.filter(select, &[TypeIdentifier::String, TypeIdentifier::String])?;
+            let names: Vec<Vec<PrismaValue>> = rows.into_iter().map(|row| row.values).collect();
+
+            let alice = || PrismaValue::String("alice".into());
+            let expected = vec![
+                vec![PrismaValue::String("a1".into()), alice()],
+                vec![PrismaValue::String("a2".into()), alice()],
+            ];
+
+            expect("children joined to their parent", expected, names)?;
+            expect("children", 4, suite.count(conn, CHILD_TABLE)?)
+        })
+    }
+
+    fn transactions(&self) -> ConnectorResult<()> {
+        let failed: ConnectorResult<()> = self.transaction(|conn, suite| {
+            conn.insert(suite.parent(1, "alice", None))?;
+            Err(ConnectorError::QueryError(failure::err_msg("Rolling back on purpose.")))
+        });
+
+        if failed.is_ok() {
+            return Err(mismatch("failing transaction", "an error", "success"));
+        }
+
+        self.transaction(|conn, suite| expect("rows after a rollback", 0, suite.count(conn, PARENT_TABLE)?))?;
+        self.transaction(|conn, suite| conn.insert(suite.parent(2, "bob", None)).map(|_| ()))?;
+        self.transaction(|conn, suite| expect("rows after a commit", 1, suite.count(conn, PARENT_TABLE)?))
+    }
+
+    fn transaction<F, R>(&self, f: F) -> ConnectorResult<R>
+    where
+        F: FnOnce(&mut Transaction, &Self) -> ConnectorResult<R>,
+    {
+        self.executor.with_transaction(&self.db_name, |conn| f(conn, self))
+    }
+
+    fn reset(&self) -> ConnectorResult<()> {
+        self.transaction(|conn, suite| {
+            conn.delete(Delete::from_table(suite.table(CHILD_TABLE)))?;
+            conn.delete(Delete::from_table(suite.table(PARENT_TABLE)))?;
+
+            Ok(())
+        })
+    }
+
+    fn table(&self, name: &str) -> Table {
+        Table::from((self.db_name.as_str(), name))
+    }
+
+    fn parent(&self, id: i64, name: &str, rank: Option<i64>) -> Insert {
+        Insert::single_into(self.table(PARENT_TABLE))
+            .value("id", PrismaValue::Int(id))
+            .value("name", PrismaValue::String(name.to_string()))
+            .value("rank", rank.map(PrismaValue::Int).unwrap_or(PrismaValue::Null))
+    }
+
+    fn select_parents(&self) -> Select {
+        Select::from_table(self.table(PARENT_TABLE))
+            .column(Column::from("id"))
+            .column(Column::from("name"))
+            .column(Column::from("rank"))
+    }
+
+    /// The parents matching `condition`, ordered by id.
+    fn parents(&self, conn: &mut Transaction, condition: ConditionTree) -> ConnectorResult<Vec<Vec<PrismaValue>>> {
+        let select = self
+            .select_parents()
+            .so_that(condition)
+            .order_by(Column::from("id").ascend());
+
+        let rows = conn.filter(select, PARENT_IDENTS)?;
+
+        Ok(rows.into_iter().map(|row| row.values).collect())
+    }
+
+    /// The ids of two parents ordered by `order`, skipping `skip`.
+    fn page(
+        &self,
+        conn: &mut Transaction,
+        order: (DatabaseValue, Option<Order>),
+        skip: usize,
+    ) -> ConnectorResult<Vec<i64>> {
+        let select = self.select_parents().order_by(order).offset(skip).limit(2);
+        let rows = conn.filter(select, PARENT_IDENTS)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| as_int(row.values[0].clone()))
+            .collect())
+    }
+
+    fn count(&self, conn: &mut Transaction, table: &str) -> ConnectorResult<i64> {
+        conn.find_int(Select::from_table(self.table(table)).value(count(asterisk())))
+    }
+}
+
+fn parent_row(id: i64, name: &str, rank: Option<i64>) -> Vec<PrismaValue> {
+    vec![
+        PrismaValue::Int(id),
+        PrismaValue::String(name.to_string()),
+        rank.map(PrismaValue::Int).unwrap_or(PrismaValue::Null),
+    ]
+}
+
+fn as_int(value: PrismaValue) -> Option<i64> {
+    match value {
+        PrismaValue::Int(i) => Some(i),
+        _ => None,
+    }
+}
+
+fn expect<V>(what: &str, expected: V, actual: V) -> ConnectorResult<()>
+where
+    V: PartialEq + std::fmt::Debug,
+{
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(mismatch(what, expected, actual))
+    }
+}
+
+fn mismatch<E, A>(what: &str, expected: E, actual: A) -> ConnectorError
+where
+    E: std::fmt::Debug,
+    A: std::fmt::Debug,
+{
+    ConnectorError::QueryError(failure::err_msg(format!(
+        "Conformance check `{}` failed: expected {:?}, got {:?}.",
+        what, expected, actual
+    )))
+}
@@ -1,4 +1,4 @@
-use connector::QueryArguments;
+use connector::{ConnectorResult, CursorToken, QueryArguments};
 use prisma_models::prelude::*;
 use prisma_query::ast::*;
 use std::sync::Arc;
@@ -12,6 +12,24 @@ enum CursorType {
 pub struct CursorCondition;
 
 impl CursorCondition {
+    /// Fails with `InvalidCursor` for cursors that look like tokens but can't
+    /// be decoded, e.g. forged ones. Reads check their cursors before
+    /// building the query, which can't fail.
+    pub fn check(query_arguments: &QueryArguments) -> ConnectorResult<()> {
+        let cursors = query_arguments.before.iter().chain(query_arguments.after.iter());
+
+        for cursor in cursors {
+            match cursor {
+                GraphqlId::String(s) if CursorToken::is_token(s) => {
+                    CursorToken::decode(s)?;
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn build(query_arguments: &QueryArguments, model: ModelRef) -> ConditionTree {
         match (
             query_arguments.before.as_ref(),
@@ -27,52 +45,68 @@ impl CursorCondition {
 
                 let sort_order: SortOrder = order_by.map(|order| order.sort_order).unwrap_or(SortOrder::Ascending);
 
-                let cursor_for = |cursor_type: CursorType, id: GraphqlId| {
+                let cursor_for = |cursor_type: CursorType, id: GraphqlId, order_value: Option<PrismaValue>| {
                     let model_id = model.fields().id();
-                    let where_condition = model_id.as_column().equals(id.clone());
 
-                    let select_query = Select::from_table(model.table())
-                        .column(field.as_column())
-                        .so_that(ConditionTree::single(where_condition));
+                    // Tokens carry the value of the field, ids need to read it.
+                    let value: DatabaseValue = match order_value {
+                        Some(value) => value.into(),
+                        None => {
+                            let where_condition = model_id.as_column().equals(id.clone());
+
+                            Select::from_table(model.table())
+                                .column(field.as_column())
+                                .so_that(ConditionTree::single(where_condition))
+                                .into()
+                        }
+                    };
 
                     let compare = match (cursor_type, sort_order) {
                         (CursorType::Before, SortOrder::Ascending) => field
                             .as_column()
-                            .equals(select_query.clone())
+                            .equals(value.clone())
                             .and(model_id.as_column().less_than(id))
-                            .or(field.as_column().less_than(select_query)),
+                            .or(field.as_column().less_than(value)),
                         (CursorType::Before, SortOrder::Descending) => field
                             .as_column()
-                            .equals(select_query.clone())
+                            .equals(value.clone())
                             .and(model_id.as_column().less_than(id))
-                            .or(field.as_column().greater_than(select_query)),
+                            .or(field.as_column().greater_than(value)),
                         (CursorType::After, SortOrder::Ascending) => field
                             .as_column()
-                            .equals(select_query.clone())
+                            .equals(value.clone())
                             .and(model_id.as_column().greater_than(id))
-                            .or(field.as_column().greater_than(select_query)),
+                            .or(field.as_column().greater_than(value)),
                         (CursorType::After, SortOrder::Descending) => field
                             .as_column()
-                            .equals(select_query.clone())
+                            .equals(value.clone())
                             .and(model_id.as_column().greater_than(id))
-                            .or(field.as_column().less_than(select_query)),
+                            .or(field.as_column().less_than(value)),
                     };
 
                     ConditionTree::single(compare)
                 };
 
+                let condition_for = |cursor_type: CursorType, cursor: &GraphqlId| match cursor {
+                    GraphqlId::String(s) if CursorToken::is_token(s) => match CursorToken::decode(s) {
+                        // Tokens of unordered reads have no values, these are read.
+                        Ok(token) => {
+                            let order_value = order_by.and_then(|_| token.order_values.into_iter().next());
+                            cursor_for(cursor_type, token.id, order_value)
+                        }
+                        // Rejected by `check` for reads, a query rendered
+                        // without it matches nothing.
+                        Err(_) => ConditionTree::NegativeCondition,
+                    },
+                    id => cursor_for(cursor_type, id.clone(), None),
+                };
+
                 let after_cursor = after
-                    .map(|id| {
-                        let id_val = id.clone();
-                        cursor_for(CursorType::After, id_val)
-                    })
+                    .map(|cursor| condition_for(CursorType::After, cursor))
                     .unwrap_or(ConditionTree::NoCondition);
 
                 let before_cursor = before
-                    .map(|id| {
-                        let id_val = id.clone();
-                        cursor_for(CursorType::Before, id_val)
-                    })
+                    .map(|cursor| condition_for(CursorType::Before, cursor))
                     .unwrap_or(ConditionTree::NoCondition);
 
                 ConditionTree::and(after_cursor, before_cursor)
@@ -80,3 +114,42 @@ impl CursorCondition {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use connector::error::ConnectorError;
+
+    fn after(cursor: GraphqlId) -> QueryArguments {
+        QueryArguments {
+            after: Some(cursor),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ids_and_tokens_are_valid_cursors() {
+        let token = CursorToken::new(vec![PrismaValue::Int(3)], GraphqlId::Int(7)).encode();
+
+        assert!(CursorCondition::check(&QueryArguments::default()).is_ok());
+        assert!(CursorCondition::check(&after(GraphqlId::Int(7))).is_ok());
+        assert!(CursorCondition::check(&after(GraphqlId::String(String::from("ckxyz")))).is_ok());
+        assert!(CursorCondition::check(&after(GraphqlId::String(token))).is_ok());
+    }
+
+    #[test]
+    fn forged_tokens_are_invalid_cursors() {
+        let token = CursorToken::new(vec![PrismaValue::Int(3)], GraphqlId::Int(7)).encode();
+        let forged = token.replace("cursor:", "cursor:x");
+
+        let query_arguments = QueryArguments {
+            before: Some(GraphqlId::String(forged)),
+            ..Default::default()
+        };
+
+        match CursorCondition::check(&query_arguments) {
+            Err(ConnectorError::InvalidCursor) => (),
+            res => panic!("Expected an invalid cursor, got {:?}", res),
+        }
+    }
+}
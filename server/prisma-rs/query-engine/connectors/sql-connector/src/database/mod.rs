@@ -1,7 +1,16 @@
+#[cfg(feature = "sqlite")]
 mod sqlite;
 
-use crate::Transactional;
+use crate::{
+    introspection::DatabaseSchema, validate_schema, AuditLog, ChangeSink, HealthStatus, OperationPolicy, Outbox,
+    OutboxEvent, PolicyRequest, RedactionRules, Transactional, TypeRegistry,
+};
+use connector::{error::ConnectorError, ConnectorResult};
+use prisma_models::Schema;
+use prisma_query::ast::Query;
+#[cfg(feature = "sqlite")]
 pub use sqlite::*;
+use std::{sync::Arc, time::Duration};
 
 /// A common interface for relational SQL databases.
 pub struct SqlDatabase<T>
@@ -9,6 +18,15 @@ where
     T: Transactional,
 {
     pub executor: T,
+    /// Conversions for native column types, applied after reading and before
+    /// writing values.
+    pub type_registry: TypeRegistry,
+    /// Fields read redacted, applied after the conversions of the registry.
+    pub redactions: RedactionRules,
+    change_sinks: Vec<Arc<ChangeSink>>,
+    outbox: Option<Outbox>,
+    audit_log: Option<AuditLog>,
+    policies: Vec<Arc<OperationPolicy>>,
 }
 
 impl<T> SqlDatabase<T>
@@ -16,6 +34,111 @@ where
     T: Transactional,
 {
     pub fn new(executor: T) -> Self {
-        Self { executor }
+        Self {
+            executor,
+            type_registry: TypeRegistry::default(),
+            redactions: RedactionRules::default(),
+            change_sinks: Vec::new(),
+            outbox: None,
+            audit_log: None,
+            policies: Vec::new(),
+        }
+    }
+
+    /// Writes the changes of every mutation to `outbox` in the transaction
+    /// of the mutation.
+    pub fn set_outbox(&mut self, outbox: Outbox) {
+        self.outbox = Some(outbox);
+    }
+
+    pub(crate) fn outbox(&self) -> Option<&Outbox> {
+        self.outbox.as_ref()
+    }
+
+    /// Records the changes of every mutation with the actor of the request.
+    pub fn set_audit_log(&mut self, audit_log: AuditLog) {
+        self.audit_log = Some(audit_log);
+    }
+
+    pub(crate) fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit_log.as_ref()
+    }
+
+    /// Claims up to `limit` events of the outbox for `consumer`, oldest first.
+    /// Events claimed longer than `lease` ago are claimed again. Without an
+    /// outbox there are no events.
+    pub fn claim_outbox_events(
+        &self,
+        db_name: &str,
+        consumer: &str,
+        limit: usize,
+        lease: Duration,
+    ) -> ConnectorResult<Vec<OutboxEvent>> {
+        match self.outbox {
+            Some(ref outbox) => self
+                .executor
+                .with_transaction(db_name, |conn| outbox.claim(conn, db_name, consumer, limit, lease)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Removes the published events `ids` from the outbox.
+    pub fn complete_outbox_events(&self, db_name: &str, ids: &[i64]) -> ConnectorResult<usize> {
+        match self.outbox {
+            Some(ref outbox) => self
+                .executor
+                .with_transaction(db_name, |conn| outbox.complete(conn, db_name, ids)),
+            None => Ok(0),
+        }
+    }
+
+    /// Publishes the records written by every committed mutation to `sink`.
+    pub fn add_change_sink(&mut self, sink: Arc<ChangeSink>) {
+        self.change_sinks.push(sink);
+    }
+
+    pub(crate) fn change_sinks(&self) -> &[Arc<ChangeSink>] {
+        &self.change_sinks
+    }
+
+    /// Consults `policy` before executing every operation, failing the
+    /// operation with `OperationDenied` if any policy denies it.
+    pub fn add_policy(&mut self, policy: Arc<OperationPolicy>) {
+        self.policies.push(policy);
+    }
+
+    pub(crate) fn check_policies(&self, request: &PolicyRequest) -> ConnectorResult<()> {
+        for policy in self.policies.iter() {
+            policy
+                .check(request)
+                .map_err(|reason| ConnectorError::OperationDenied { reason })?;
+        }
+
+        Ok(())
+    }
+
+    /// The plan of the database for the query, for debugging slow queries.
+    /// Mutations are planned but not executed.
+    pub fn explain(&self, db_name: &str, query: Query) -> ConnectorResult<String> {
+        self.executor.with_read_transaction(db_name, |conn| conn.explain(query))
+    }
+
+    /// Checks that the database can be queried, failing if no connection is
+    /// free within `timeout`.
+    pub fn health_check(&self, db_name: &str, timeout: Duration) -> HealthStatus {
+        self.executor.health_check(db_name, timeout)
+    }
+
+    /// The schema currently in the database, to compare it with the data
+    /// model.
+    pub fn introspect(&self, db_name: &str) -> ConnectorResult<DatabaseSchema> {
+        self.executor.introspect(db_name)
+    }
+
+    /// Fails with a description of every table and column of the data
+    /// model missing or of the wrong type in the database, e.g. to refuse
+    /// starting instead of failing at the first query.
+    pub fn validate_schema(&self, db_name: &str, schema: &Schema) -> ConnectorResult<()> {
+        validate_schema(&self.executor, db_name, schema)
     }
 }
@@ -1,115 +1,1098 @@
-use crate::*;
-use connector::*;
+use crate::{filter_conversion::render_for_sqlite, type_mapping::binary_uuid, *};
+use connector::{error::ConnectorError, *};
+use libsqlite3_sys as ffi;
+use parking_lot::Mutex;
 use prisma_models::{ProjectRef, TypeIdentifier};
 use prisma_query::{
-    ast::{Query, Select},
+    ast::{ParameterizedValue, Query, Select},
     visitor::{self, Visitor},
 };
+use r2d2::PooledConnection;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::{Connection, Transaction as SqliteTransaction, NO_PARAMS};
-use std::collections::HashSet;
+use rusqlite::{
+    hooks::Action,
+    types::{ToSql, ToSqlOutput},
+    Connection, NO_PARAMS,
+};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    env, fs,
+    marker::PhantomData,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{field, span, Level, Span};
 
 type Pool = r2d2::Pool<SqliteConnectionManager>;
 
+/// Setting this environment variable attaches the failed statement, a summary
+/// of its parameters and the SQLite error code to query errors.
+const DEBUG_QUERIES_VAR: &str = "PRISMA_DEBUG_QUERIES";
+
+/// Parameters longer than this are cut when attached to an error.
+const MAX_PARAMETER_LENGTH: usize = 64;
+
+/// The number of prepared statements kept per connection, if not configured.
+const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 65536;
+
+thread_local! {
+    /// The rows changed by the transaction running on the current thread,
+    /// filled by the update hook of its connection.
+    static ROW_CHANGES: RefCell<Vec<RowChange>> = RefCell::new(Vec::new());
+}
+
 /// SQLite is a C-language library that implements a small, fast,
 /// self-contained, high-reliability, full-featured, SQL database engine.
-pub struct Sqlite {
+///
+/// The queries are rendered by `V`, a custom visitor can render them for a
+/// database compatible with SQLite that needs a slightly different SQL.
+pub struct Sqlite<V = visitor::Sqlite>
+where
+    V: Visitor,
+{
     databases_folder_path: String,
+    database_files: HashMap<String, String>,
+    tenants_folder: Option<String>,
+    read_only_roles: HashSet<String>,
     pool: Pool,
     test_mode: bool,
+    query_log: QueryLog,
+    metrics: Option<Arc<Metrics>>,
+    tag_queries: bool,
+    strict_sql: bool,
+    statement_cache_capacity: usize,
+    interceptors: Vec<Arc<QueryInterceptor>>,
+    row_change_listeners: Vec<Arc<RowChangeListener>>,
+    query_cache: Option<Arc<QueryCache>>,
+    single_flight: Option<Arc<SingleFlight>>,
+    max_in_flight: Option<usize>,
+    in_flight: AtomicUsize,
+    capabilities: Capabilities,
+    /// The connection and database of a running test transaction.
+    test_transaction: Mutex<Option<(String, PooledConnection<SqliteConnectionManager>)>>,
+    visitor: PhantomData<fn() -> V>,
+}
+
+/// Holds a slot of the in-flight limit, freed when dropped.
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl<'a> Drop for InFlight<'a> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// A SQLite transaction, logging the queries with the settings of the
+/// connector.
+struct SqliteTransaction<'a, V>
+where
+    V: Visitor,
+{
+    id: usize,
+    inner: rusqlite::Savepoint<'a>,
+    query_log: QueryLog,
+    metrics: Option<Arc<Metrics>>,
+    tag_queries: bool,
+    strict_sql: bool,
+    statement_cache_capacity: usize,
+    interceptors: Vec<Arc<QueryInterceptor>>,
+    query_cache: Option<Arc<QueryCache>>,
+    single_flight: Option<Arc<SingleFlight>>,
+    db_name: String,
+    schema_version: Option<i64>,
+    written: bool,
+    cancellation: Option<CancellationToken>,
+    visitor: PhantomData<fn() -> V>,
+}
+
+impl<'a, V> SqliteTransaction<'a, V>
+where
+    V: Visitor,
+{
+    fn check_cancelled(&self) -> ConnectorResult<()> {
+        match self.cancellation {
+            Some(ref token) if token.is_cancelled() => Err(ConnectorError::QueryCancelled),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_query(&self, operation: &'static str, start: Instant) {
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_query(operation, start.elapsed());
+        }
+    }
+
+    /// Checks the statements rendered by the visitor in strict mode, before
+    /// the tags and interceptors are applied.
+    fn check_strict(&self, sql: &str) -> ConnectorResult<()> {
+        if self.strict_sql {
+            check_parameterized(sql)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Prepends the query tags of the request, if enabled.
+    fn tag(&self, sql: String) -> String {
+        match query_tags_comment() {
+            Some(comment) if self.tag_queries => format!("{} {}", comment, sql),
+            _ => sql,
+        }
+    }
+
+    /// Passes the statement through the `before` hooks of the interceptors.
+    fn intercept(
+        &self,
+        sql: String,
+        params: Vec<ParameterizedValue>,
+    ) -> ConnectorResult<(String, Vec<ParameterizedValue>)> {
+        self.interceptors
+            .iter()
+            .try_fold((sql, params), |(sql, params), interceptor| {
+                interceptor.before(sql, params)
+            })
+    }
+
+    fn intercepted(&self, sql: &str, result: Result<usize, &ConnectorError>) {
+        for interceptor in self.interceptors.iter() {
+            interceptor.after(sql, result);
+        }
+    }
+
+    /// The key of a read query in the result cache. The schema version is
+    /// read once per transaction.
+    fn cache_key(&mut self, sql: &str, params: &[ParameterizedValue]) -> ConnectorResult<QueryCacheKey> {
+        let schema_version = match self.schema_version {
+            Some(version) => version,
+            None => {
+                let pragma = format!("PRAGMA {}.schema_version", quote_identifier(&self.db_name));
+                let version: i64 = self.inner.query_row(&pragma, NO_PARAMS, |row| row.get(0))?;
+
+                self.schema_version = Some(version);
+                version
+            }
+        };
+
+        // Stable, so the last of the same setting still wins.
+        let mut settings = session_settings();
+        settings.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(QueryCacheKey {
+            database: self.db_name.clone(),
+            tenant: tenant(),
+            schema_version,
+            settings,
+            role: database_role(),
+            sql: sql.to_string(),
+            parameters: params.iter().map(|param| format!("{:?}", param)).collect(),
+        })
+    }
+
+    /// Executes a read query, without the result cache.
+    fn execute_filter(
+        &self,
+        sql: String,
+        params: Vec<ParameterizedValue>,
+        idents: &[TypeIdentifier],
+    ) -> ConnectorResult<Vec<PrismaRow>> {
+        let (sql, params) = self.intercept(self.tag(sql), params)?;
+        let context = QueryContext::new(&sql, &params, self.query_log.hides_parameters(&sql));
+        let logged = self.query_log.start(self.id, &sql, &params);
+        let start = Instant::now();
+
+        let result = self.with_statement(&sql, |stmt| {
+            let mut rows = stmt.query(bind(params))?;
+            let mut result = Vec::new();
+
+            while let Some(row) = rows.next() {
+                result.push(row?.to_prisma_row(idents)?);
+            }
+
+            Ok(result)
+        });
+
+        self.record_query("filter", start);
+        self.intercepted(&sql, result.as_ref().map(|rows| rows.len()));
+
+        if let Some(logged) = logged {
+            logged.finish(result.as_ref().map(|rows| rows.len()).unwrap_or(0));
+        }
+
+        context.attach(result)
+    }
+
+    /// Runs `f` with the statement for `sql`, taken from the statement cache
+    /// of the connection if enabled.
+    fn with_statement<F, T>(&self, sql: &str, f: F) -> ConnectorResult<T>
+    where
+        F: FnOnce(&mut rusqlite::Statement) -> ConnectorResult<T>,
+    {
+        if self.statement_cache_capacity > 0 {
+            f(&mut self.inner.prepare_cached(sql)?)
+        } else {
+            f(&mut self.inner.prepare(sql)?)
+        }
+    }
 }
 
-impl Transactional for Sqlite {
+/// Numbers the transactions for the query log.
+static TRANSACTION_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
+impl<V> Transactional for Sqlite<V>
+where
+    V: Visitor,
+{
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    fn health_check(&self, db: &str, timeout: Duration) -> HealthStatus {
+        let start = Instant::now();
+
+        let result = self
+            .pool
+            .get_timeout(timeout)
+            .map_err(ConnectorError::from)
+            .and_then(|mut conn| {
+                self.attach_database(&mut conn, db)?;
+
+                let result: ConnectorResult<i64> = conn
+                    .query_row("SELECT 1", NO_PARAMS, |row| row.get(0))
+                    .map_err(ConnectorError::from);
+
+                if self.test_mode {
+                    conn.execute("DETACH DATABASE ?", &[db])?;
+                }
+
+                result
+            });
+
+        let state = self.pool.state();
+
+        HealthStatus {
+            healthy: result.is_ok(),
+            duration_ms: as_millis(start.elapsed()),
+            error_code: result.as_ref().err().map(|e| e.code()),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+        }
+    }
+
+    fn introspect(&self, db: &str) -> ConnectorResult<introspection::DatabaseSchema> {
+        self.with_connection(db, |conn| introspection::introspect_sqlite(conn, db))
+    }
+
+    /// Prepared statements don't need to be dropped, SQLite prepares them
+    /// again if the schema of the database changed.
+    ///
+    /// Reads joining a flight after the invalidation of the cache would get
+    /// rows read before it, so the flights are invalidated first.
+    fn invalidate_caches(&self, db: &str) {
+        if let Some(ref single_flight) = self.single_flight {
+            single_flight.invalidate(db);
+        }
+
+        if let Some(ref cache) = self.query_cache {
+            cache.invalidate(db);
+        }
+    }
+
     fn with_transaction<F, T>(&self, db: &str, f: F) -> ConnectorResult<T>
     where
         F: FnOnce(&mut Transaction) -> ConnectorResult<T>,
     {
-        self.with_connection(db, |ref mut conn| {
-            let mut tx = conn.transaction()?;
-            tx.set_prepared_statement_cache_capacity(65536);
+        let span = span!(
+            Level::DEBUG,
+            "sql",
+            db_type = "sqlite",
+            db_name = db,
+            operation = "transaction",
+            duration_ms = field::Empty
+        );
+
+        let cancellation = cancellation();
+
+        match cancellation {
+            Some(ref token) if token.is_cancelled() => return Err(ConnectorError::QueryCancelled),
+            _ => (),
+        }
 
-            let result = f(&mut tx);
+        let _in_flight = match self.enter() {
+            Ok(in_flight) => in_flight,
+            Err(e) => {
+                if let Some(ref metrics) = self.metrics {
+                    metrics.record_error(e.code());
+                }
 
-            if result.is_ok() {
-                tx.commit()?;
+                return Err(e);
             }
+        };
 
-            result
-        })
+        let start = Instant::now();
+
+        let result = traced(span, || {
+            self.with_connection(db, |ref mut conn| {
+                // Interrupts the statement running on the connection.
+                let _registration = cancellation.as_ref().map(|token| {
+                    let handle = conn.get_interrupt_handle();
+                    token.on_cancel(move || handle.interrupt())
+                });
+
+                if !self.row_change_listeners.is_empty() {
+                    Self::watch_row_changes(conn);
+                }
+
+                let mut tx = SqliteTransaction {
+                    id: TRANSACTION_COUNTER.fetch_add(1, Ordering::Relaxed),
+                    // A savepoint starts a transaction of its own, or nests
+                    // in the one of a test transaction.
+                    inner: conn.savepoint()?,
+                    query_log: self.query_log.clone(),
+                    metrics: self.metrics.clone(),
+                    tag_queries: self.tag_queries,
+                    strict_sql: self.strict_sql,
+                    statement_cache_capacity: self.statement_cache_capacity,
+                    interceptors: self.interceptors.clone(),
+                    query_cache: self.query_cache.clone(),
+                    single_flight: self.single_flight.clone(),
+                    db_name: db.to_string(),
+                    schema_version: None,
+                    written: false,
+                    cancellation: cancellation.clone(),
+                    visitor: PhantomData,
+                };
+
+                if self.statement_cache_capacity > 0 {
+                    tx.inner
+                        .set_prepared_statement_cache_capacity(self.statement_cache_capacity);
+                } else {
+                    tx.inner.flush_prepared_statement_cache();
+                }
+
+                let result = f(&mut tx);
+                let written = tx.written;
+
+                let result = match result {
+                    Ok(value) => tx.inner.commit().map(|_| value).map_err(ConnectorError::from),
+                    Err(e) => Err(e),
+                };
+
+                if result.is_ok() && !self.row_change_listeners.is_empty() {
+                    self.publish_row_changes();
+                }
+
+                // Readers in other transactions may have cached the results
+                // from before the commit. Failed or rolled back transactions
+                // invalidate them too, as nothing they read may stay cached.
+                if written {
+                    self.invalidate_caches(db);
+                }
+
+                result
+            })
+        });
+
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_transaction(start.elapsed());
+
+            if let Err(ref e) = result {
+                metrics.record_error(e.code());
+            }
+        }
+
+        result
     }
 }
 
-impl<'a> Transaction for SqliteTransaction<'a> {
+impl<'a, V> WriteOperations for SqliteTransaction<'a, V>
+where
+    V: Visitor,
+{
     fn write(&mut self, q: Query) -> ConnectorResult<WriteItems> {
-        let (sql, params) = dbg!(visitor::Sqlite::build(q));
-        let mut stmt = self.prepare_cached(&sql)?;
+        traced(operation_span("write"), || {
+            self.check_cancelled()?;
+
+            let (sql, params) = render_for_sqlite::<V, _>(q);
+            self.check_strict(&sql)?;
+
+            if let Some(ref single_flight) = self.single_flight {
+                single_flight.invalidate(&self.db_name);
+            }
+
+            if let Some(ref cache) = self.query_cache {
+                cache.invalidate(&self.db_name);
+            }
+
+            self.written = true;
+
+            let (sql, params) = self.intercept(self.tag(sql), params)?;
+            let context = QueryContext::new(&sql, &params, self.query_log.hides_parameters(&sql));
+            let logged = self.query_log.start(self.id, &sql, &params);
+            let start = Instant::now();
 
-        Ok(WriteItems {
-            count: stmt.execute(params)? as usize,
-            last_id: self.last_insert_rowid() as usize,
+            let result = self.with_statement(&sql, |stmt| {
+                Ok(WriteItems {
+                    count: stmt.execute(bind(params))? as usize,
+                    last_id: self.inner.last_insert_rowid() as usize,
+                })
+            });
+
+            self.record_query("write", start);
+            self.intercepted(&sql, result.as_ref().map(|items| items.count));
+
+            if let Some(logged) = logged {
+                logged.finish(result.as_ref().map(|items| items.count).unwrap_or(0));
+            }
+
+            context.attach(result)
         })
     }
 
+    fn truncate(&mut self, project: ProjectRef) -> ConnectorResult<()> {
+        traced(operation_span("truncate"), || {
+            self.write(Query::from("PRAGMA foreign_keys = OFF"))?;
+
+            for delete in MutationBuilder::truncate_tables(project) {
+                self.delete(delete)?;
+            }
+
+            self.write(Query::from("PRAGMA foreign_keys = ON"))?;
+
+            Ok(())
+        })
+    }
+}
+
+impl<'a, V> ReadOperations for SqliteTransaction<'a, V>
+where
+    V: Visitor,
+{
     fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> ConnectorResult<Vec<PrismaRow>> {
-        let (sql, params) = dbg!(visitor::Sqlite::build(q));
+        traced(operation_span("filter"), || {
+            self.check_cancelled()?;
+
+            let (sql, params) = render_for_sqlite::<V, _>(q);
+            self.check_strict(&sql)?;
+
+            // Keyed without the tags, which differ for every request. After a
+            // write the rows include uncommitted changes, which must neither
+            // be shared nor cached.
+            let key = if self.written {
+                None
+            } else if self.query_cache.is_some() || self.single_flight.is_some() {
+                Some(self.cache_key(&sql, &params)?)
+            } else {
+                None
+            };
+
+            // Taken before reading, so rows read before a commit of another
+            // connection aren't cached after it invalidated the cache.
+            let generation = self.query_cache.as_ref().map(|cache| cache.generation(&self.db_name));
+
+            if let (Some(cache), Some(key)) = (self.query_cache.clone(), key.as_ref()) {
+                if let Some(rows) = cache.get(key) {
+                    return Ok(rows);
+                }
+            }
+
+            let single_flight = match (self.single_flight.clone(), key.clone()) {
+                (Some(single_flight), Some(key)) => Some((single_flight, key)),
+                _ => None,
+            };
+
+            let result = match single_flight {
+                Some((single_flight, key)) => single_flight.run(key, || self.execute_filter(sql, params, idents)),
+                None => self.execute_filter(sql, params, idents),
+            };
+
+            if let (Some(cache), Some(key), Some(generation), Ok(rows)) =
+                (self.query_cache.clone(), key, generation, &result)
+            {
+                cache.put(key, rows.clone(), generation);
+            }
+
+            result
+        })
+    }
+
+    /// Uses `EXPLAIN QUERY PLAN`, indenting the steps under their parents.
+    /// SQLite can't measure the actual execution like `EXPLAIN ANALYZE` would.
+    fn explain(&mut self, q: Query) -> ConnectorResult<String> {
+        traced(operation_span("explain"), || {
+            let (sql, params) = render_for_sqlite::<V, _>(q);
+            self.check_strict(&sql)?;
+
+            let mut stmt = self.inner.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+            let mut rows = stmt.query(bind(params))?;
+            let mut depths: HashMap<i64, usize> = HashMap::new();
+            let mut lines = Vec::new();
+
+            while let Some(row) = rows.next() {
+                let row = row?;
+                let id: i64 = row.get_checked(0)?;
+                let parent: i64 = row.get_checked(1)?;
+                let detail: String = row.get_checked(3)?;
+
+                let depth = depths.get(&parent).map(|depth| depth + 1).unwrap_or(0);
+                depths.insert(id, depth);
+
+                lines.push(format!("{}{}", "  ".repeat(depth), detail));
+            }
+
+            Ok(lines.join("\n"))
+        })
+    }
+}
+
+/// A span for a single operation in a transaction. The database name is in
+/// the parent span of the transaction.
+fn operation_span(operation: &'static str) -> Span {
+    span!(
+        Level::DEBUG,
+        "sql",
+        db_type = "sqlite",
+        operation = operation,
+        duration_ms = field::Empty
+    )
+}
 
-        let mut stmt = self.prepare_cached(&sql)?;
-        let mut rows = stmt.query(params)?;
-        let mut result = Vec::new();
+/// Runs `f` inside of the span, recording the time it took as `duration_ms`.
+fn traced<F, T>(span: Span, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _enter = span.enter();
+    let start = Instant::now();
+    let result = f();
 
-        while let Some(row) = rows.next() {
-            result.push(row?.to_prisma_row(idents)?);
+    span.record("duration_ms", &as_millis(start.elapsed()));
+
+    result
+}
+
+fn as_millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + u64::from(duration.subsec_millis())
+}
+
+/// Makes `current_setting(name)` available to the statements, returning the
+/// session setting of the thread executing the statement or `NULL`. Views
+/// can filter the rows by e.g. `current_setting('app.current_user')`, like
+/// row level security policies would.
+fn register_functions(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.create_scalar_function("current_setting", 1, false, |ctx| {
+        let name: String = ctx.get(0)?;
+        Ok(session_setting(&name))
+    })?;
+
+    Ok(())
+}
+
+/// A parameter bound to a statement. UUIDs of fields stored as
+/// `UuidStorage::Binary` are bound as their 16 bytes.
+enum SqliteParameter {
+    Value(ParameterizedValue),
+    Blob(Vec<u8>),
+}
+
+impl ToSql for SqliteParameter {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            SqliteParameter::Value(value) => value.to_sql(),
+            SqliteParameter::Blob(bytes) => Ok(ToSqlOutput::from(bytes.as_slice())),
         }
+    }
+}
 
-        Ok(result)
+fn bind(params: Vec<ParameterizedValue>) -> Vec<SqliteParameter> {
+    params
+        .into_iter()
+        .map(|param| {
+            let bytes = match param {
+                ParameterizedValue::Text(ref text) => binary_uuid(text),
+                _ => None,
+            };
+
+            match bytes {
+                Some(bytes) => SqliteParameter::Blob(bytes),
+                None => SqliteParameter::Value(param),
+            }
+        })
+        .collect()
+}
+
+/// The statement of a query, kept for errors in case query debugging is
+/// enabled.
+struct QueryContext {
+    sql: String,
+    parameters: Option<Vec<String>>,
+}
+
+impl QueryContext {
+    /// With `hide_parameters` only the number of parameters is attached, as
+    /// in the query log.
+    fn new(sql: &str, params: &[ParameterizedValue], hide_parameters: bool) -> Self {
+        let parameters = if env::var_os(DEBUG_QUERIES_VAR).is_some() {
+            let summaries = params
+                .iter()
+                .map(|param| {
+                    if hide_parameters {
+                        return String::from("?");
+                    }
+
+                    let summary = format!("{:?}", param);
+
+                    if summary.chars().count() > MAX_PARAMETER_LENGTH {
+                        let cut: String = summary.chars().take(MAX_PARAMETER_LENGTH).collect();
+                        format!("{}...", cut)
+                    } else {
+                        summary
+                    }
+                })
+                .collect();
+
+            Some(summaries)
+        } else {
+            None
+        };
+
+        Self {
+            sql: sql.to_string(),
+            parameters,
+        }
     }
 
-    fn truncate(&mut self, project: ProjectRef) -> ConnectorResult<()> {
-        self.write(Query::from("PRAGMA foreign_keys = OFF"))?;
+    /// Errors with a dedicated variant, such as constraint violations, are
+    /// returned untouched so they can still be matched on.
+    fn attach<T>(self, result: ConnectorResult<T>) -> ConnectorResult<T> {
+        match (result, self.parameters) {
+            (Err(ConnectorError::QueryError(e)), Some(parameters)) => {
+                let code = e
+                    .iter_chain()
+                    .filter_map(|cause| match cause.downcast_ref::<rusqlite::Error>() {
+                        Some(rusqlite::Error::SqliteFailure(ffi::Error { extended_code, .. }, _)) => {
+                            Some(extended_code.to_string())
+                        }
+                        _ => None,
+                    })
+                    .next()
+                    .unwrap_or_else(|| String::from("none"));
 
-        for delete in MutationBuilder::truncate_tables(project) {
-            self.delete(delete)?;
+                let message = format!(
+                    "{} (code: {}, query: `{}`, parameters: [{}])",
+                    e,
+                    code,
+                    self.sql,
+                    parameters.join(", ")
+                );
+
+                Err(ConnectorError::QueryError(e.context(message).into()))
+            }
+            (result, _) => result,
         }
+    }
+}
+
+/// Configures a `Sqlite` without a Prisma config, e.g. for embedding the
+/// connector into another application.
+#[derive(Debug, Clone)]
+pub struct SqliteBuilder {
+    databases_folder_path: String,
+    database_files: HashMap<String, String>,
+    connection_limit: u32,
+    connection_timeout: Option<Duration>,
+    init: InitConnection,
+    test_mode: bool,
+}
+
+/// Prepares every new connection of the pool, registering the functions of
+/// the connector before the configured statements run.
+#[derive(Debug, Clone, Default)]
+struct InitConnection {
+    busy_timeout: Option<Duration>,
+    statements: Vec<String>,
+}
+
+impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for InitConnection {
+    fn on_acquire(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        register_functions(conn)?;
 
-        self.write(Query::from("PRAGMA foreign_keys = ON"))?;
+        if let Some(timeout) = self.busy_timeout {
+            conn.busy_timeout(timeout)?;
+        }
+
+        for statement in self.statements.iter() {
+            conn.execute_batch(statement)?;
+        }
 
         Ok(())
     }
 }
 
+impl SqliteBuilder {
+    /// Attaches the databases from `{databases_folder_path}/{db_name}.db`,
+    /// with a pool of up to ten connections.
+    pub fn new<P>(databases_folder_path: P) -> Self
+    where
+        P: Into<String>,
+    {
+        Self {
+            databases_folder_path: databases_folder_path.into(),
+            database_files: HashMap::new(),
+            connection_limit: 10,
+            connection_timeout: None,
+            init: InitConnection::default(),
+            test_mode: false,
+        }
+    }
+
+    pub fn connection_limit(mut self, connection_limit: u32) -> Self {
+        self.connection_limit = connection_limit;
+        self
+    }
+
+    /// How long to wait for a free connection of the pool, 30 seconds if not
+    /// set.
+    pub fn connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = Some(timeout);
+        self
+    }
+
+    /// How long a statement waits for the lock of a database written by
+    /// another connection, before failing with `SQLITE_BUSY`.
+    pub fn busy_timeout(mut self, timeout: Duration) -> Self {
+        self.init.busy_timeout = Some(timeout);
+        self
+    }
+
+    /// Runs `statement` on every new connection, e.g. a `PRAGMA`. The
+    /// databases are attached afterwards, so statements for a database of
+    /// the engine need to be run on every transaction instead.
+    pub fn init_statement<S>(mut self, statement: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.init.statements.push(statement.into());
+        self
+    }
+
+    /// See `Sqlite::set_database_file`.
+    pub fn database_file<D, P>(mut self, db_name: D, path: P) -> Self
+    where
+        D: Into<String>,
+        P: Into<String>,
+    {
+        self.database_files.insert(db_name.into(), path.into());
+        self
+    }
+
+    /// Detaches the database after every transaction, for the tests writing
+    /// to the database files at the same time.
+    pub fn test_mode(mut self, test_mode: bool) -> Self {
+        self.test_mode = test_mode;
+        self
+    }
+
+    pub fn build(self) -> ConnectorResult<Sqlite> {
+        self.build_with_visitor()
+    }
+
+    /// Renders the queries with `V` instead of the SQLite visitor.
+    pub fn build_with_visitor<V>(self) -> ConnectorResult<Sqlite<V>>
+    where
+        V: Visitor,
+    {
+        let mut pool = r2d2::Pool::builder()
+            .max_size(self.connection_limit)
+            .connection_customizer(Box::new(self.init));
+
+        if let Some(timeout) = self.connection_timeout {
+            pool = pool.connection_timeout(timeout);
+        }
+
+        let pool = pool.build(SqliteConnectionManager::memory())?;
+        let capabilities = Sqlite::<V>::detect_capabilities(&*pool.get()?)?;
+
+        Ok(Sqlite {
+            databases_folder_path: self.databases_folder_path,
+            database_files: self.database_files,
+            tenants_folder: None,
+            read_only_roles: HashSet::new(),
+            pool,
+            test_mode: self.test_mode,
+            query_log: QueryLog::default(),
+            metrics: None,
+            tag_queries: false,
+            strict_sql: false,
+            statement_cache_capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+            interceptors: Vec::new(),
+            row_change_listeners: Vec::new(),
+            query_cache: None,
+            single_flight: None,
+            max_in_flight: None,
+            in_flight: AtomicUsize::new(0),
+            capabilities,
+            test_transaction: Mutex::new(None),
+            visitor: PhantomData,
+        })
+    }
+}
+
 impl Sqlite {
     /// Creates a new SQLite pool connected into local memory.
     pub fn new(databases_folder_path: String, connection_limit: u32, test_mode: bool) -> ConnectorResult<Sqlite> {
-        let pool = r2d2::Pool::builder()
-            .max_size(connection_limit)
-            .build(SqliteConnectionManager::memory())?;
+        SqliteBuilder::new(databases_folder_path)
+            .connection_limit(connection_limit)
+            .test_mode(test_mode)
+            .build()
+    }
+}
 
-        Ok(Sqlite {
-            databases_folder_path,
-            pool,
-            test_mode,
+impl<V> Sqlite<V>
+where
+    V: Visitor,
+{
+    /// Reads the features from the version and the compile options of the
+    /// SQLite library.
+    fn detect_capabilities(conn: &Connection) -> ConnectorResult<Capabilities> {
+        let version: String = conn.query_row("SELECT sqlite_version()", NO_PARAMS, |row| row.get(0))?;
+        let version = DatabaseVersion::parse(&version).ok_or_else(|| {
+            ConnectorError::ConnectionError(failure::err_msg(format!(
+                "Could not parse the SQLite version `{}`",
+                version
+            )))
+        })?;
+
+        let mut stmt = conn.prepare("PRAGMA compile_options")?;
+
+        let options = stmt
+            .query_map(NO_PARAMS, |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        let max_bind_parameters = options
+            .iter()
+            .filter_map(|option| {
+                let mut parts = option.splitn(2, '=');
+
+                match (parts.next(), parts.next()) {
+                    (Some("MAX_VARIABLE_NUMBER"), Some(value)) => value.parse().ok(),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or(if version.at_least(3, 32) { 32766 } else { 999 });
+
+        Ok(Capabilities {
+            version,
+            returning: version.at_least(3, 35),
+            window_functions: version.at_least(3, 25),
+            json: options.iter().any(|option| option == "ENABLE_JSON1") || version.at_least(3, 38),
+            native_upsert: version.at_least(3, 24),
+            transactional_ddl: true,
+            max_bind_parameters,
         })
     }
 
+    /// Sends measurements of queries, transactions and the pool to `metrics`.
+    pub fn set_metrics(&mut self, metrics: Arc<Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Logs all executed queries with the given settings. Nothing is logged
+    /// by default.
+    pub fn set_query_log(&mut self, query_log: QueryLog) {
+        self.query_log = query_log;
+    }
+
+    /// Prepends the tags set with `with_query_tags` as a comment to all
+    /// statements. Statements with different tags are prepared separately,
+    /// so tags unique to each request bypass the statement cache.
+    pub fn set_query_tagging(&mut self, enabled: bool) {
+        self.tag_queries = enabled;
+    }
+
+    /// Rejects statements with string literals, comments or several
+    /// statements before executing them, see `check_parameterized`. For
+    /// finding SQL written into statements instead of passed as parameters,
+    /// e.g. in interceptors or new query builders.
+    pub fn set_strict_sql(&mut self, enabled: bool) {
+        self.strict_sql = enabled;
+    }
+
+    /// The number of prepared statements cached per connection, the least
+    /// recently used being evicted first. `0` disables the cache, preparing
+    /// every statement again on execution.
+    pub fn set_statement_cache_capacity(&mut self, capacity: usize) {
+        self.statement_cache_capacity = capacity;
+    }
+
+    /// Adds a hook to inspect or rewrite the statements before they are
+    /// executed and to observe their results.
+    pub fn add_interceptor(&mut self, interceptor: Arc<QueryInterceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    /// Reports the rows changed by every committed transaction to
+    /// `listener`, using the update hook of SQLite.
+    pub fn add_row_change_listener(&mut self, listener: Arc<RowChangeListener>) {
+        self.row_change_listeners.push(listener);
+    }
+
+    /// Reads the results of queries from the cache, if stored. The results
+    /// of a database are invalidated when it's written to through this
+    /// connector, writes from other processes are not seen.
+    pub fn set_query_cache(&mut self, query_cache: Arc<QueryCache>) {
+        self.query_cache = Some(query_cache);
+    }
+
+    /// Executes identical read queries of concurrent transactions once,
+    /// see `SingleFlight`. Reads of transactions that wrote are executed on
+    /// their own, as they see the uncommitted changes.
+    pub fn set_single_flight(&mut self, enabled: bool) {
+        self.single_flight = if enabled {
+            Some(Arc::new(SingleFlight::new()))
+        } else {
+            None
+        };
+    }
+
+    /// Fails transactions right away with `Overloaded` while `max_in_flight`
+    /// of them are running, instead of waiting for a connection.
+    pub fn set_max_in_flight(&mut self, max_in_flight: usize) {
+        self.max_in_flight = Some(max_in_flight);
+    }
+
+    /// Attaches the file at `path` as the database `db_name`, instead of
+    /// `{db_name}.db` in the databases folder. Tables of a schema named
+    /// differently than its file are then found under the schema name.
+    pub fn set_database_file<D, P>(&mut self, db_name: D, path: P)
+    where
+        D: Into<String>,
+        P: Into<String>,
+    {
+        self.database_files.insert(db_name.into(), path.into());
+    }
+
+    /// Attaches the database `{tenants_folder}/{tenant}.db` of the tenant
+    /// set with `with_tenant` for every transaction, replacing the database
+    /// of the previous tenant on the connection. All tenants share the pool,
+    /// instead of a pool per tenant as with a `TenantRouter`.
+    pub fn set_tenants_folder<P>(&mut self, folder: P)
+    where
+        P: Into<String>,
+    {
+        self.tenants_folder = Some(folder.into());
+    }
+
+    /// Runs the transactions of operations with the role set by
+    /// `with_database_role` read-only, using `PRAGMA query_only`. SQLite has
+    /// no other privileges, so all other roles can read and write.
+    pub fn add_read_only_role<R>(&mut self, role: R)
+    where
+        R: Into<String>,
+    {
+        self.read_only_roles.insert(role.into());
+    }
+
+    /// Runs all following transactions on one connection inside a
+    /// transaction on `db`, until `rollback_test_transaction` discards their
+    /// changes. Successful transactions only release their savepoint, so the
+    /// later ones see their changes. For integration tests sharing a
+    /// database without seeing each other's data. Transactions wait for each
+    /// other, and attaching other databases fails while it runs.
+    pub fn begin_test_transaction(&self, db: &str) -> ConnectorResult<()> {
+        let mut test_transaction = self.test_transaction.lock();
+
+        if test_transaction.is_some() {
+            return Err(ConnectorError::QueryError(failure::err_msg(
+                "A test transaction is already running.",
+            )));
+        }
+
+        // Databases can't be attached inside a transaction.
+        let mut conn = self.get_connection()?;
+        self.attach_database(&mut conn, db)?;
+        conn.execute_batch("BEGIN")?;
+
+        *test_transaction = Some((db.to_string(), conn));
+
+        Ok(())
+    }
+
+    /// Rolls back the test transaction with the changes of all transactions
+    /// since `begin_test_transaction`. Does nothing without one.
+    pub fn rollback_test_transaction(&self) -> ConnectorResult<()> {
+        let test_transaction = self.test_transaction.lock().take();
+
+        if let Some((db, conn)) = test_transaction {
+            conn.execute_batch("ROLLBACK")?;
+            self.invalidate_caches(&db);
+
+            if self.test_mode {
+                conn.execute("DETACH DATABASE ?", &[db])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Takes a slot for a transaction, if any are free.
+    fn enter(&self) -> ConnectorResult<InFlight> {
+        let running = self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let in_flight = InFlight(&self.in_flight);
+
+        match self.max_in_flight {
+            Some(max_in_flight) if running >= max_in_flight => Err(ConnectorError::Overloaded { max_in_flight }),
+            _ => Ok(in_flight),
+        }
+    }
+
     /// When querying and we haven't yet loaded the database, it'll be loaded on
     /// or created to the configured database file.
     ///
     /// The database is then attached to the memory with an alias of `{db_name}`.
+    ///
+    /// With a tenants folder, the database of the current tenant is attached
+    /// instead, detaching the one of another tenant first.
     fn attach_database(&self, conn: &mut Connection, db_name: &str) -> ConnectorResult<()> {
         let mut stmt = conn.prepare("PRAGMA database_list")?;
 
-        let databases: HashSet<String> = stmt
+        let databases: HashMap<String, String> = stmt
             .query_map(NO_PARAMS, |row| {
                 let name: String = row.get(1);
-                name
+                let file: String = row.get(2);
+                (name, file)
             })?
             .map(|res| res.unwrap())
             .collect();
 
-        if !databases.contains(db_name) {
-            // This is basically hacked until we have a full rust stack with a migration engine.
-            // Currently, the scala tests use the JNA library to write to the database. This
-            let database_file_path = format!("{}/{}.db", self.databases_folder_path, db_name);
+        // This is basically hacked until we have a full rust stack with a migration engine.
+        // Currently, the scala tests use the JNA library to write to the database. This
+        let database_file_path = match (&self.tenants_folder, self.database_files.get(db_name)) {
+            (Some(folder), _) => format!("{}/{}.db", folder, require_tenant()?),
+            (None, Some(path)) => path.clone(),
+            (None, None) => format!("{}/{}.db", self.databases_folder_path, db_name),
+        };
+
+        let attached = match databases.get(db_name) {
+            // SQLite lists the absolute path of the attached file.
+            Some(file) if self.tenants_folder.is_some() => match fs::canonicalize(&database_file_path) {
+                Ok(path) if path == Path::new(file) => true,
+                _ => {
+                    conn.execute("DETACH DATABASE ?", &[db_name])?;
+                    false
+                }
+            },
+            Some(_) => true,
+            None => false,
+        };
+
+        if !attached {
             conn.execute("ATTACH DATABASE ? AS ?", &[database_file_path.as_ref(), db_name])?;
         }
 
@@ -117,14 +1100,82 @@ impl Sqlite {
         Ok(())
     }
 
+    /// Collects the rows changed on the connection for the transaction about
+    /// to start. The hook runs on the thread executing the statement.
+    fn watch_row_changes(conn: &Connection) {
+        ROW_CHANGES.with(|changes| changes.borrow_mut().clear());
+
+        conn.update_hook(Some(|action: Action, database: &str, table: &str, row_id: i64| {
+            let operation = match action {
+                Action::SQLITE_INSERT => RowOperation::Insert,
+                Action::SQLITE_UPDATE => RowOperation::Update,
+                Action::SQLITE_DELETE => RowOperation::Delete,
+                _ => return,
+            };
+
+            let change = RowChange {
+                operation,
+                database: database.to_string(),
+                table: table.to_string(),
+                row_id,
+            };
+
+            ROW_CHANGES.with(|changes| changes.borrow_mut().push(change));
+        }));
+    }
+
+    fn publish_row_changes(&self) {
+        let changes = ROW_CHANGES.with(|changes| changes.replace(Vec::new()));
+
+        if changes.is_empty() {
+            return;
+        }
+
+        for listener in self.row_change_listeners.iter() {
+            listener.row_changes(&changes);
+        }
+    }
+
+    /// Takes a connection from the pool. If the pool timed out with all
+    /// connections in use, the error tells how long we waited and how many
+    /// connections there are.
+    fn get_connection(&self) -> ConnectorResult<PooledConnection<SqliteConnectionManager>> {
+        let start = Instant::now();
+        let result = self.pool.get();
+        let state = self.pool.state();
+
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_pool(state.connections, state.idle_connections, self.pool.max_size());
+        }
+
+        result.map_err(|e| {
+            if state.idle_connections == 0 && state.connections >= self.pool.max_size() {
+                ConnectorError::ConnectionPoolExhausted {
+                    connection_limit: self.pool.max_size(),
+                    in_use: state.connections,
+                    waited_ms: as_millis(start.elapsed()),
+                }
+            } else {
+                ConnectorError::from(e)
+            }
+        })
+    }
+
     fn with_connection<F, T>(&self, db: &str, f: F) -> ConnectorResult<T>
     where
         F: FnOnce(&mut Connection) -> ConnectorResult<T>,
     {
-        let mut conn = self.pool.get()?;
-        self.attach_database(&mut conn, db)?;
+        let mut test_transaction = self.test_transaction.lock();
+
+        // Holding the lock lets the transactions take turns on the connection.
+        if let Some((_, ref mut conn)) = *test_transaction {
+            return self.run_on_connection(conn, db, f);
+        }
+
+        drop(test_transaction);
 
-        let result = f(&mut conn);
+        let mut conn = self.get_connection()?;
+        let result = self.run_on_connection(&mut conn, db, f);
 
         if self.test_mode {
             conn.execute("DETACH DATABASE ?", &[db])?;
@@ -132,4 +1183,381 @@ impl Sqlite {
 
         result
     }
+
+    fn run_on_connection<F, T>(&self, conn: &mut Connection, db: &str, f: F) -> ConnectorResult<T>
+    where
+        F: FnOnce(&mut Connection) -> ConnectorResult<T>,
+    {
+        self.attach_database(conn, db)?;
+
+        let read_only = match database_role() {
+            Some(ref role) => self.read_only_roles.contains(role),
+            None => false,
+        };
+
+        if read_only {
+            conn.execute_batch("PRAGMA query_only = ON")?;
+        }
+
+        let result = f(conn);
+
+        // The connection goes back to the pool for other roles.
+        if read_only {
+            conn.execute_batch("PRAGMA query_only = OFF")?;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::SqlDatabase;
+    use connector::{
+        filter::{Filter, NodeSelector},
+        mutaction::{CreateNode, DeleteNode, Identifier, NestedMutactions, TopLevelDatabaseMutaction},
+    };
+    use prisma_models::{
+        FieldTemplate, GraphqlId, PrismaArgs, PrismaValue, ScalarFieldTemplate, SchemaTemplate, SelectedFields,
+        TypeIdentifier, UuidStorage,
+    };
+    use prisma_query::ast::*;
+    use std::fs::File;
+    use uuid::Uuid;
+
+    fn sqlite(name: &str) -> Sqlite {
+        let folder = env::temp_dir().join(format!("prisma-sqlite-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+
+        let file = folder.join("test.db");
+        let _ = fs::remove_file(&file);
+
+        Sqlite::new(folder.to_string_lossy().into_owned(), 1, false).unwrap()
+    }
+
+    fn count_items(tx: &mut Transaction) -> ConnectorResult<usize> {
+        let select = Select::from_table(("test", "Item")).column("id");
+        Ok(tx.filter(select, &[TypeIdentifier::Int])?.len())
+    }
+
+    #[test]
+    fn rolled_back_reads_are_not_cached() {
+        let mut sqlite = sqlite("query-cache");
+        sqlite.set_query_cache(Arc::new(MemoryQueryCache::new(16)));
+
+        sqlite
+            .with_transaction("test", |tx| {
+                tx.write(Query::from(r#"CREATE TABLE "test"."Item" ("id" INTEGER PRIMARY KEY)"#))
+                    .map(|_| ())
+            })
+            .unwrap();
+
+        let result: ConnectorResult<()> = sqlite.with_transaction("test", |tx| {
+            tx.insert(Insert::single_into(("test", "Item")).value("id", PrismaValue::Int(1)))?;
+            assert_eq!(1, count_items(tx)?);
+
+            Err(ConnectorError::QueryError(failure::err_msg("Rolling back")))
+        });
+
+        assert!(result.is_err());
+
+        let count = sqlite.with_transaction("test", |tx| count_items(tx)).unwrap();
+        assert_eq!(0, count);
+    }
+
+    #[test]
+    fn reads_are_cached_per_session_settings() {
+        let mut sqlite = sqlite("query-cache-settings");
+        sqlite.set_query_cache(Arc::new(MemoryQueryCache::new(16)));
+
+        sqlite
+            .with_transaction("test", |tx| {
+                tx.write(Query::from(
+                    r#"CREATE TABLE "test"."Item" ("id" INTEGER PRIMARY KEY, "owner" TEXT NOT NULL)"#,
+                ))?;
+                tx.write(Query::from(
+                    r#"CREATE VIEW "test"."MyItem" AS SELECT "id" FROM "Item" WHERE "owner" = current_setting('app.current_user')"#,
+                ))?;
+
+                for (id, owner) in vec![(1, "alice"), (2, "bob"), (3, "bob")] {
+                    let insert = Insert::single_into(("test", "Item"))
+                        .value("id", PrismaValue::Int(id))
+                        .value("owner", PrismaValue::String(owner.to_string()));
+
+                    tx.insert(insert)?;
+                }
+
+                Ok(())
+            })
+            .unwrap();
+
+        let count_my_items = |user: &str| {
+            let settings = vec![(String::from("app.current_user"), user.to_string())];
+
+            with_session_settings(settings, || {
+                sqlite.with_transaction("test", |tx| {
+                    let select = Select::from_table(("test", "MyItem")).column("id");
+                    Ok(tx.filter(select, &[TypeIdentifier::Int])?.len())
+                })
+            })
+            .unwrap()
+        };
+
+        assert_eq!(1, count_my_items("alice"));
+        assert_eq!(2, count_my_items("bob"));
+        assert_eq!(1, count_my_items("alice"));
+    }
+
+    /// Reverses the plaintext, deterministically.
+    struct Reversed;
+
+    impl FieldCipher for Reversed {
+        fn encrypt(&self, plaintext: &str, _deterministic: bool) -> ConnectorResult<String> {
+            Ok(plaintext.chars().rev().collect())
+        }
+
+        fn decrypt(&self, ciphertext: &str) -> ConnectorResult<String> {
+            Ok(ciphertext.chars().rev().collect())
+        }
+    }
+
+    #[test]
+    fn deleted_records_are_decrypted() {
+        let sqlite = sqlite("delete-encrypted");
+
+        sqlite
+            .with_transaction("test", |tx| {
+                tx.write(Query::from(
+                    r#"CREATE TABLE "test"."User" ("id" TEXT PRIMARY KEY, "name" TEXT NOT NULL)"#,
+                ))?;
+                tx.write(Query::from(
+                    r#"CREATE TABLE "test"."User_cats" ("nodeId" TEXT, "position" INTEGER, "value" INTEGER)"#,
+                ))?;
+
+                let insert = Insert::single_into(("test", "User"))
+                    .value("id", PrismaValue::String(String::from("user1")))
+                    .value("name", PrismaValue::String(String::from("ecila")));
+
+                tx.insert(insert).map(|_| ())
+            })
+            .unwrap();
+
+        let template: SchemaTemplate = serde_json::from_reader(File::open("test_schema.json").unwrap()).unwrap();
+        let schema = template.build(String::from("test"));
+        let model = schema.find_model("User").unwrap();
+        let id = model.fields().find_from_scalar("id").unwrap();
+
+        let mut database = SqlDatabase::new(sqlite);
+        database
+            .type_registry
+            .register_field("User", "name", Encrypted::new(Reversed).deterministic());
+
+        let delete = TopLevelDatabaseMutaction::DeleteNode(DeleteNode {
+            where_: NodeSelector::from((id, GraphqlId::String(String::from("user1")))),
+        });
+
+        let result = database.execute(String::from("test"), delete).unwrap();
+
+        match result.identifier {
+            Identifier::Node(node) => {
+                let name = node.field_names.iter().position(|name| name == "name").unwrap();
+                assert_eq!(PrismaValue::String(String::from("alice")), node.node.values[name]);
+            }
+            identifier => panic!("Expected the deleted record, got {:?}", identifier),
+        }
+    }
+
+    #[test]
+    fn binary_uuids_round_trip() {
+        let sqlite = sqlite("binary-uuids");
+
+        sqlite
+            .with_transaction("test", |tx| {
+                tx.write(Query::from(
+                    r#"CREATE TABLE "test"."User" ("id" TEXT PRIMARY KEY, "name" TEXT NOT NULL, "token" BLOB)"#,
+                ))
+                .map(|_| ())
+            })
+            .unwrap();
+
+        let mut template: SchemaTemplate = serde_json::from_reader(File::open("test_schema.json").unwrap()).unwrap();
+        let user = template.models.iter_mut().find(|model| model.name == "User").unwrap();
+
+        user.fields.push(FieldTemplate::Scalar(ScalarFieldTemplate {
+            name: String::from("token"),
+            type_identifier: TypeIdentifier::UUID,
+            is_required: false,
+            is_list: false,
+            is_unique: false,
+            is_hidden: false,
+            is_auto_generated: false,
+            manifestation: None,
+            behaviour: None,
+            uuid_storage: Some(UuidStorage::Binary),
+            max_length: None,
+            native_type: None,
+        }));
+
+        let schema = template.build(String::from("test"));
+        let model = schema.find_model("User").unwrap();
+        let token = model.fields().find_from_scalar("token").unwrap();
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+        let mut args = PrismaArgs::new();
+        args.insert("id", GraphqlId::String(String::from("user1")));
+        args.insert("name", String::from("alice"));
+        args.insert("token", PrismaValue::Uuid(uuid));
+
+        let database = SqlDatabase::new(sqlite);
+
+        let create = TopLevelDatabaseMutaction::CreateNode(CreateNode {
+            model: Arc::clone(&model),
+            non_list_args: args,
+            list_args: Vec::new(),
+            nested_mutactions: NestedMutactions::default(),
+        });
+
+        database.execute(String::from("test"), create).unwrap();
+
+        let stored: (String, i64) = database
+            .executor
+            .get_connection()
+            .unwrap()
+            .query_row(
+                r#"SELECT typeof("token"), length("token") FROM "test"."User""#,
+                NO_PARAMS,
+                |row| (row.get(0), row.get(1)),
+            )
+            .unwrap();
+
+        assert_eq!((String::from("blob"), 16), stored);
+
+        let query_arguments = QueryArguments {
+            filter: Some(token.equals(PrismaValue::Uuid(uuid))),
+            ..Default::default()
+        };
+
+        let selected_fields = SelectedFields::from(Arc::clone(&model));
+        let result = database.get_nodes(model, query_arguments, &selected_fields).unwrap();
+
+        assert_eq!(1, result.nodes.len());
+
+        let position = result.field_names.iter().position(|name| name == "token").unwrap();
+        assert_eq!(PrismaValue::Uuid(uuid), result.nodes[0].values[position]);
+    }
+
+    #[test]
+    fn long_in_lists_are_read_from_temporary_tables() {
+        let sqlite = sqlite("value-tables");
+        let count = MAX_IN_LIST_LENGTH + 10;
+
+        sqlite
+            .with_transaction("test", |tx| {
+                tx.write(Query::from(
+                    r#"CREATE TABLE "test"."User" ("id" TEXT PRIMARY KEY, "name" TEXT NOT NULL, "cats" INTEGER)"#,
+                ))?;
+
+                for i in 0..count {
+                    let insert = Insert::single_into(("test", "User"))
+                        .value("id", PrismaValue::String(format!("user{}", i)))
+                        .value("name", PrismaValue::String(format!("User {}", i)));
+
+                    tx.insert(insert)?;
+                }
+
+                Ok(())
+            })
+            .unwrap();
+
+        let template: SchemaTemplate = serde_json::from_reader(File::open("test_schema.json").unwrap()).unwrap();
+        let schema = template.build(String::from("test"));
+        let model = schema.find_model("User").unwrap();
+        let name = model.fields().find_from_scalar("name").unwrap();
+        let selected_fields = SelectedFields::from(Arc::clone(&model));
+
+        // One name without a user, all of them in one list.
+        let names: Vec<String> = (0..=count).map(|i| format!("User {}", i)).collect();
+        let database = SqlDatabase::new(sqlite);
+
+        let query_arguments = QueryArguments {
+            filter: Some(name.is_in(Some(names.clone()))),
+            ..Default::default()
+        };
+
+        let result = database
+            .get_nodes(Arc::clone(&model), query_arguments, &selected_fields)
+            .unwrap();
+
+        assert_eq!(count, result.nodes.len());
+
+        // The first read dropped its table, the second creates it again.
+        let query_arguments = QueryArguments {
+            filter: Some(name.not_in(Some(names))),
+            ..Default::default()
+        };
+
+        let result = database.get_nodes(model, query_arguments, &selected_fields).unwrap();
+
+        assert!(result.nodes.is_empty());
+    }
+
+    #[test]
+    fn like_keeps_the_builtin_semantics() {
+        let sqlite = sqlite("functions");
+        let conn = sqlite.get_connection().unwrap();
+
+        let matches = |sql: &str| -> bool { conn.query_row(sql, NO_PARAMS, |row| row.get(0)).unwrap() };
+
+        assert!(!matches(r"SELECT 'a%' LIKE 'a\%'"));
+        assert!(matches(r"SELECT 'a%' LIKE 'a\%' ESCAPE '\'"));
+    }
+
+    #[test]
+    fn string_filters_match_wildcards_literally() {
+        let sqlite = sqlite("like-escapes");
+
+        sqlite
+            .with_transaction("test", |tx| {
+                tx.write(Query::from(
+                    r#"CREATE TABLE "test"."User" ("id" TEXT PRIMARY KEY, "name" TEXT NOT NULL)"#,
+                ))?;
+
+                for (id, name) in vec![("1", "100%"), ("2", "1000"), ("3", r"a_\b"), ("4", "axxb")] {
+                    let insert = Insert::single_into(("test", "User"))
+                        .value("id", PrismaValue::String(id.to_string()))
+                        .value("name", PrismaValue::String(name.to_string()));
+
+                    tx.insert(insert)?;
+                }
+
+                Ok(())
+            })
+            .unwrap();
+
+        let template: SchemaTemplate = serde_json::from_reader(File::open("test_schema.json").unwrap()).unwrap();
+        let schema = template.build(String::from("test"));
+        let model = schema.find_model("User").unwrap();
+        let name = model.fields().find_from_scalar("name").unwrap();
+        let selected_fields = SelectedFields::from(Arc::clone(&model));
+        let database = SqlDatabase::new(sqlite);
+
+        let count = |filter: Filter| {
+            let query_arguments = QueryArguments {
+                filter: Some(filter),
+                ..Default::default()
+            };
+
+            database
+                .get_nodes(Arc::clone(&model), query_arguments, &selected_fields)
+                .unwrap()
+                .nodes
+                .len()
+        };
+
+        assert_eq!(1, count(name.contains("0%")));
+        assert_eq!(1, count(name.starts_with(r"a_\")));
+        assert_eq!(1, count(name.ends_with(r"_\b")));
+        assert_eq!(3, count(name.not_contains("%")));
+    }
 }
@@ -0,0 +1,82 @@
+use crate::TypeMapping;
+use connector::{error::ConnectorError, filter::ScalarCondition, ConnectorResult};
+use prisma_models::prelude::*;
+use std::sync::Arc;
+
+/// Encrypts and decrypts the values of fields, e.g. with a key from a key
+/// management service. The ciphertext is stored as text, so it has to be
+/// encoded, e.g. with base64.
+pub trait FieldCipher: Send + Sync {
+    /// With `deterministic` the same plaintext must always give the same
+    /// ciphertext, otherwise it should differ for every call.
+    fn encrypt(&self, plaintext: &str, deterministic: bool) -> ConnectorResult<String>;
+
+    fn decrypt(&self, ciphertext: &str) -> ConnectorResult<String>;
+}
+
+/// A `TypeMapping` storing the values of `String` fields encrypted, for
+/// registering with `TypeRegistry::register_field`. The values are encrypted
+/// before the parameters are bound and decrypted when the rows are read.
+///
+/// Encrypted fields can't be filtered by, except for equality with
+/// `deterministic` encryption, which in turn shows which records have the
+/// same value. Ordering by them orders by the ciphertext.
+#[derive(Clone)]
+pub struct Encrypted {
+    cipher: Arc<FieldCipher>,
+    deterministic: bool,
+}
+
+impl Encrypted {
+    pub fn new<C>(cipher: C) -> Self
+    where
+        C: FieldCipher + 'static,
+    {
+        Self {
+            cipher: Arc::new(cipher),
+            deterministic: false,
+        }
+    }
+
+    /// Encrypts deterministically, allowing `equals`, `not` and `in` filters
+    /// on the field.
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
+    fn not_a_string(value: &PrismaValue) -> ConnectorError {
+        ConnectorError::ConversionError(failure::err_msg(format!(
+            "Only strings can be encrypted, got {:?}.",
+            value
+        )))
+    }
+}
+
+impl TypeMapping for Encrypted {
+    fn from_database(&self, value: PrismaValue) -> ConnectorResult<PrismaValue> {
+        match value {
+            PrismaValue::String(ciphertext) => Ok(PrismaValue::String(self.cipher.decrypt(&ciphertext)?)),
+            value => Err(Self::not_a_string(&value)),
+        }
+    }
+
+    fn to_database(&self, value: PrismaValue) -> ConnectorResult<PrismaValue> {
+        match value {
+            PrismaValue::String(plaintext) => Ok(PrismaValue::String(
+                self.cipher.encrypt(&plaintext, self.deterministic)?,
+            )),
+            value => Err(Self::not_a_string(&value)),
+        }
+    }
+
+    fn supports_condition(&self, condition: &ScalarCondition) -> bool {
+        match condition {
+            ScalarCondition::Equals(_)
+            | ScalarCondition::NotEquals(_)
+            | ScalarCondition::In(_)
+            | ScalarCondition::NotIn(_) => self.deterministic,
+            _ => false,
+        }
+    }
+}
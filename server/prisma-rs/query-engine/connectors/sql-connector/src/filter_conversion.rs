@@ -1,6 +1,6 @@
 use connector::filter::*;
 use prisma_models::prelude::*;
-use prisma_query::ast::*;
+use prisma_query::{ast::*, visitor::Visitor};
 
 #[derive(Clone, Copy, Debug)]
 /// A distinction in aliasing to separate the parent table and the joined data
@@ -57,8 +57,9 @@ impl Alias {
     /// A string representation of the current alias. The current mode can be
     /// overridden by defining the `mode_override`.
     ///
-    /// ```rust
-    /// # use sql_connector::{Alias, AliasMode};
+    #[cfg_attr(feature = "internals", doc = "```rust")]
+    #[cfg_attr(not(feature = "internals"), doc = "```ignore")]
+    /// # use sql_connector::internals::{Alias, AliasMode};
     ///
     /// let alias = Alias::default();
     ///
@@ -96,11 +97,11 @@ trait AliasedSelect {
 impl AliasedCondition for Filter {
     /// Conversion from a `Filter` to a query condition tree. Aliased when in a nested `SELECT`.
     ///
-    /// ```rust
-    /// # use sql_connector::*;
-    /// # use connector::*;
+    #[cfg_attr(feature = "internals", doc = "```rust")]
+    #[cfg_attr(not(feature = "internals"), doc = "```ignore")]
+    /// # use sql_connector::{*, internals::*};
+    /// # use connector::{*, filter::Filter};
     /// # use prisma_models::*;
-    /// # use connector::*;
     /// # use prisma_query::ast::*;
     /// # use serde_json;
     /// # use std::{fs::File, sync::Arc};
@@ -123,6 +124,28 @@ impl AliasedCondition for Filter {
     /// let expected: ConditionTree = ("t0", "name").equals("foo").into();
     ///
     /// assert_eq!(expected, cond);
+    ///
+    /// // Negated:
+    ///
+    /// let cond = Filter::not(vec![field.equals("foo")]).aliased_cond(None);
+    /// let expected: ConditionTree = (("test", "User"), "name").equals("foo").into();
+    ///
+    /// assert_eq!(expected.not(), cond);
+    ///
+    /// let sql = SqlDialect::Sqlite.render(Select::from_table(model.table()).so_that(cond)).sql;
+    /// assert!(sql.contains("NOT"));
+    ///
+    /// // Negated constants stay constant:
+    ///
+    /// assert_eq!(
+    ///     ConditionTree::NegativeCondition,
+    ///     Filter::not(vec![Filter::from(true)]).aliased_cond(None)
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ConditionTree::NoCondition,
+    ///     Filter::not(vec![Filter::from(false)]).aliased_cond(None)
+    /// );
     /// ```
     fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
         match self {
@@ -148,20 +171,25 @@ impl AliasedCondition for Filter {
                     })
                 }
             },
+            // Every filter is negated as a whole, relation filters included.
+            // Their sub-selects never return `NULL`, so a negated `IN` is
+            // false instead of unknown for the records it doesn't match.
             Filter::Not(mut filters) => match filters.pop() {
                 None => ConditionTree::NoCondition,
                 Some(filter) => {
-                    let right = (*filter).aliased_cond(alias).not();
+                    let right = negate((*filter).aliased_cond(alias));
 
                     filters.into_iter().rev().fold(right, |acc, filter| {
-                        let left = (*filter).aliased_cond(alias).not();
+                        let left = negate((*filter).aliased_cond(alias));
                         ConditionTree::and(left, acc)
                     })
                 }
             },
             Filter::Scalar(filter) => filter.aliased_cond(alias),
             Filter::OneRelationIsNull(filter) => filter.aliased_cond(alias),
+            Filter::OneRelationIsNotNull(filter) => filter.aliased_cond(alias),
             Filter::Relation(filter) => filter.aliased_cond(alias),
+            Filter::RelationCount(filter) => filter.aliased_cond(alias),
             Filter::BoolFilter(b) => {
                 if b {
                     ConditionTree::NoCondition
@@ -174,11 +202,26 @@ impl AliasedCondition for Filter {
     }
 }
 
+fn temporary_values(table: &str) -> Select {
+    Select::from_table(Table::from(("temp", table))).column("value")
+}
+
+/// Negates the tree, keeping conditions that are always true or false
+/// constant.
+fn negate(tree: ConditionTree) -> ConditionTree {
+    match tree {
+        ConditionTree::NoCondition => ConditionTree::NegativeCondition,
+        ConditionTree::NegativeCondition => ConditionTree::NoCondition,
+        tree => tree.not(),
+    }
+}
+
 impl AliasedCondition for ScalarFilter {
     /// Conversion from a `ScalarFilter` to a query condition tree. Aliased when in a nested `SELECT`.
     ///
-    /// ```rust
-    /// # use sql_connector::*;
+    #[cfg_attr(feature = "internals", doc = "```rust")]
+    #[cfg_attr(not(feature = "internals"), doc = "```ignore")]
+    /// # use sql_connector::{*, internals::*};
     /// # use prisma_models::*;
     /// # use connector::{*, filter::*};
     /// # use prisma_query::ast::*;
@@ -202,6 +245,24 @@ impl AliasedCondition for ScalarFilter {
     ///
     /// let expected: ConditionTree = ("t0", "name").equals("foo").into();
     /// assert_eq!(expected, sf.aliased_cond(Some(Alias::default())));
+    ///
+    /// // Long lists read from a temporary table, see `ValueTables`:
+    ///
+    /// let sf = ScalarFilter {
+    ///     field: Arc::clone(&field),
+    ///     condition: ScalarCondition::InTemporaryTable(String::from("_prisma_values_0")),
+    /// };
+    ///
+    /// let values = Select::from_table(Table::from(("temp", "_prisma_values_0"))).column("value");
+    /// let expected: ConditionTree = (("test", "User"), "name").in_selection(values).into();
+    /// let cond = sf.aliased_cond(None);
+    ///
+    /// assert_eq!(expected, cond);
+    ///
+    /// let captured = SqlDialect::Sqlite.render(Select::from_table(model.table()).so_that(cond));
+    /// assert!(captured.sql.contains("IN (SELECT"));
+    /// assert!(captured.sql.contains("_prisma_values_0"));
+    /// assert!(captured.params.is_empty());
     /// ```
     fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
         let column = match alias {
@@ -214,12 +275,14 @@ impl AliasedCondition for ScalarFilter {
             ScalarCondition::NotEquals(PrismaValue::Null) => column.is_not_null(),
             ScalarCondition::Equals(value) => column.equals(value),
             ScalarCondition::NotEquals(value) => column.not_equals(value),
-            ScalarCondition::Contains(value) => column.like(format!("{}", value)),
-            ScalarCondition::NotContains(value) => column.not_like(format!("{}", value)),
-            ScalarCondition::StartsWith(value) => column.begins_with(format!("{}", value)),
-            ScalarCondition::NotStartsWith(value) => column.not_begins_with(format!("{}", value)),
-            ScalarCondition::EndsWith(value) => column.ends_into(format!("{}", value)),
-            ScalarCondition::NotEndsWith(value) => column.not_ends_into(format!("{}", value)),
+            ScalarCondition::Contains(value) => column.like(escape_like(value)),
+            ScalarCondition::NotContains(value) => column.not_like(escape_like(value)),
+            ScalarCondition::StartsWith(value) => column.begins_with(escape_like(value)),
+            ScalarCondition::NotStartsWith(value) => column.not_begins_with(escape_like(value)),
+            ScalarCondition::EndsWith(value) => column.ends_into(escape_like(value)),
+            ScalarCondition::NotEndsWith(value) => column.not_ends_into(escape_like(value)),
+            ScalarCondition::Pattern(value) => column.like(format!("{}", value)),
+            ScalarCondition::NotPattern(value) => column.not_like(format!("{}", value)),
             ScalarCondition::LessThan(value) => column.less_than(value),
             ScalarCondition::LessThanOrEquals(value) => column.less_than_or_equals(value),
             ScalarCondition::GreaterThan(value) => column.greater_than(value),
@@ -236,17 +299,80 @@ impl AliasedCondition for ScalarFilter {
             },
             ScalarCondition::In(None) => column.is_null(),
             ScalarCondition::NotIn(None) => column.is_not_null(),
+            ScalarCondition::InTemporaryTable(table) => column.in_selection(temporary_values(&table)),
+            ScalarCondition::NotInTemporaryTable(table) => column.not_in_selection(temporary_values(&table)),
         };
 
         ConditionTree::single(condition)
     }
 }
 
+/// Escapes the wildcards of `LIKE` in the value with a backslash, so it is
+/// matched literally. The backslash is the default escape character of MySQL
+/// and Postgres, SQLite statements get an `ESCAPE` clause for it with
+/// `with_like_escapes`.
+fn escape_like(value: PrismaValue) -> String {
+    let value = format!("{}", value);
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if c == '%' || c == '_' || c == '\\' {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Renders the query with `V` for SQLite, the `LIKE` patterns escaping with a
+/// backslash like on the other databases.
+pub(crate) fn render_for_sqlite<V, Q>(query: Q) -> (String, Vec<ParameterizedValue>)
+where
+    V: Visitor,
+    Q: Into<Query>,
+{
+    let (sql, params) = V::build(query);
+    (with_like_escapes(sql), params)
+}
+
+/// Adds `ESCAPE '\'` to the `LIKE` comparisons of a statement rendered for
+/// SQLite, which has no escape character without it. The patterns are always
+/// bound as parameters, so the comparisons end with a `?`.
+pub(crate) fn with_like_escapes(sql: String) -> String {
+    const LIKE: &str = " LIKE ?";
+
+    if !sql.contains(LIKE) {
+        return sql;
+    }
+
+    let mut escaped = String::with_capacity(sql.len() + 16);
+    let mut rest = sql.as_str();
+
+    while let Some(position) = rest.find(LIKE) {
+        let end = position + LIKE.len();
+        escaped.push_str(&rest[..end]);
+        rest = &rest[end..];
+
+        // Numbered parameters and clauses written by hand are kept.
+        let numbered = rest.starts_with(|c: char| c.is_ascii_digit());
+
+        if !numbered && !rest.trim_start().starts_with("ESCAPE") {
+            escaped.push_str(" ESCAPE '\\'");
+        }
+    }
+
+    escaped.push_str(rest);
+    escaped
+}
+
 impl AliasedCondition for RelationFilter {
     /// Conversion from a `RelationFilter` to a query condition tree. Aliased when in a nested `SELECT`.
     ///
-    /// ```rust
-    /// # use sql_connector::*;
+    #[cfg_attr(feature = "internals", doc = "```rust")]
+    #[cfg_attr(not(feature = "internals"), doc = "```ignore")]
+    /// # use sql_connector::{*, internals::*};
     /// # use prisma_models::*;
     /// # use connector::*;
     /// # use prisma_query::ast::*;
@@ -275,7 +401,7 @@ impl AliasedCondition for RelationFilter {
     ///     let sub_cond: ConditionTree = ("j0", "name").equals("Blog").into();
     ///     let sub_select = Select::from_table(Table::from("_UserToSites").alias("t0"))
     ///         .column(("t0", "B"))
-    ///         .so_that(sub_cond.not())
+    ///         .so_that(ConditionTree::and(sub_cond.not(), ("t0", "B").is_not_null().into()))
     ///         .inner_join(join_data);
     ///
     ///     let expected: ConditionTree = (("test", "User"), "id")
@@ -296,9 +422,10 @@ impl AliasedCondition for RelationFilter {
     ///         .alias("j0")
     ///         .on(("j0", "id").equals(Column::from(("t0", "A"))));
     ///
+    ///     let sub_cond: ConditionTree = ("j0", "name").equals("Blog").into();
     ///     let sub_select = Select::from_table(Table::from("_UserToSites").alias("t0"))
     ///         .column(("t0", "B"))
-    ///         .so_that(("j0", "name").equals("Blog"))
+    ///         .so_that(ConditionTree::and(sub_cond, ("t0", "B").is_not_null().into()))
     ///         .inner_join(join_data);
     ///
     ///     let expected: ConditionTree = (("test", "User"), "id")
@@ -318,9 +445,10 @@ impl AliasedCondition for RelationFilter {
     ///         .alias("j0")
     ///         .on(("j0", "id").equals(Column::from(("t0", "A"))));
     ///
+    ///     let sub_cond: ConditionTree = ("j0", "name").equals("Blog").into();
     ///     let sub_select = Select::from_table(Table::from("_UserToSites").alias("t0"))
     ///         .column(("t0", "B"))
-    ///         .so_that(("j0", "name").equals("Blog"))
+    ///         .so_that(ConditionTree::and(sub_cond, ("t0", "B").is_not_null().into()))
     ///         .inner_join(join_data);
     ///
     ///     let expected: ConditionTree = (("test", "User"), "id")
@@ -333,8 +461,11 @@ impl AliasedCondition for RelationFilter {
     fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
         let id = self.field.model().id_column();
 
+        // The alias is the one of the enclosing row. With self-relations every
+        // level reads the same table, so pointing to an outer level would
+        // still run, but compare against the wrong row.
         let column = match alias {
-            Some(ref alias) => id.table(alias.dec().to_string(None)),
+            Some(ref alias) => id.table(alias.to_string(None)),
             None => id,
         };
 
@@ -395,10 +526,11 @@ impl AliasedSelect for RelationFilter {
                 .into();
 
                 let conditions = tree.invert_if(condition.invert_of_subselect());
+                let connected = this_column.clone().is_not_null().into();
 
                 Select::from_table(relation.relation_table().alias(alias.to_string(None)))
                     .column(this_column)
-                    .so_that(conditions)
+                    .so_that(ConditionTree::and(conditions, connected))
             }
             nested_filter => {
                 let tree = nested_filter.aliased_cond(Some(alias.flip(AliasMode::Join)));
@@ -417,21 +549,113 @@ impl AliasedSelect for RelationFilter {
                     .on(id_column.equals(other_column));
 
                 let table = relation.relation_table().alias(alias.to_string(Some(AliasMode::Table)));
+                let connected = this_column.clone().is_not_null().into();
 
                 Select::from_table(table)
                     .column(this_column)
                     .inner_join(join)
-                    .so_that(tree.invert_if(condition.invert_of_subselect()))
+                    .so_that(ConditionTree::and(
+                        tree.invert_if(condition.invert_of_subselect()),
+                        connected,
+                    ))
             }
         }
     }
 }
 
+impl AliasedCondition for RelationCountFilter {
+    /// Conversion from a `RelationCountFilter` to a query condition tree.
+    /// Aliased when in a nested `SELECT`.
+    ///
+    /// The count is never computed, a record has at least `n` related records
+    /// if a correlated sub-select skipping `n - 1` of them still returns one.
+    /// The sub-select stops at that row, so the condition stays cheap for
+    /// records with many relations.
+    ///
+    #[cfg_attr(feature = "internals", doc = "```rust")]
+    #[cfg_attr(not(feature = "internals"), doc = "```ignore")]
+    /// # use sql_connector::{*, internals::*};
+    /// # use prisma_models::*;
+    /// # use connector::{*, filter::*};
+    /// # use prisma_query::ast::*;
+    /// # use serde_json;
+    /// # use std::{fs::File, sync::Arc};
+    /// #
+    /// # let template: SchemaTemplate = serde_json::from_reader(File::open("./test_schema.json").unwrap()).unwrap();
+    /// let schema = template.build(String::from("test"));
+    /// let user = schema.find_model("User").unwrap();
+    /// let rf = user.fields().find_from_relation_fields("sites").unwrap();
+    ///
+    /// let user_id = Column::from((("test", "User"), "id"));
+    ///
+    /// let at_least = |count: usize| -> ConditionTree {
+    ///     let select = Select::from_table(Table::from("_UserToSites").alias("t0"))
+    ///         .column(("t0", "B"))
+    ///         .so_that(("t0", "B").equals(user_id.clone()))
+    ///         .offset(count - 1)
+    ///         .limit(1);
+    ///
+    ///     user_id.clone().in_selection(select).into()
+    /// };
+    ///
+    /// // Exactly two sites: a second one, but no third.
+    /// let cond = rf.count_related(CountCondition::Equals(2)).aliased_cond(None);
+    /// assert_eq!(ConditionTree::and(at_least(2), at_least(3).not()), cond);
+    ///
+    /// // Rendered, both sub-selects stop after one row.
+    /// let sql = SqlDialect::Sqlite.render(Select::from_table(user.table()).so_that(cond)).sql;
+    /// assert_eq!(2, sql.matches("IN (SELECT").count());
+    /// assert_eq!(2, sql.matches("OFFSET").count());
+    /// assert!(sql.contains("NOT"));
+    ///
+    /// // No sites at all, and an always matching minimum of none.
+    /// let cond = rf.count_related(CountCondition::Equals(0)).aliased_cond(None);
+    /// assert_eq!(at_least(1).not(), cond);
+    ///
+    /// let cond = rf.count_related(CountCondition::GreaterThanOrEquals(0)).aliased_cond(None);
+    /// assert_eq!(ConditionTree::NoCondition, cond);
+    /// ```
+    fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
+        let id = self.field.model().id_column();
+
+        let column = match alias {
+            Some(ref alias) => id.table(alias.to_string(None)),
+            None => id,
+        };
+
+        let sub_alias = alias.map(|a| a.inc(AliasMode::Table)).unwrap_or(Alias::default());
+        let this_column = self.field.relation_column().table(sub_alias.to_string(None));
+        let table = self.field.relation().relation_table().alias(sub_alias.to_string(None));
+
+        let at_least = |count: usize| -> ConditionTree {
+            let select = Select::from_table(table.clone())
+                .column(this_column.clone())
+                .so_that(this_column.clone().equals(column.clone()))
+                .offset(count - 1)
+                .limit(1);
+
+            column.clone().in_selection(select).into()
+        };
+
+        match self.condition {
+            CountCondition::Equals(0) => at_least(1).not(),
+            CountCondition::Equals(n) => ConditionTree::and(at_least(n), at_least(n + 1).not()),
+            CountCondition::LessThan(0) => ConditionTree::NegativeCondition,
+            CountCondition::LessThan(n) => at_least(n).not(),
+            CountCondition::LessThanOrEquals(n) => at_least(n + 1).not(),
+            CountCondition::GreaterThan(n) => at_least(n + 1),
+            CountCondition::GreaterThanOrEquals(0) => ConditionTree::NoCondition,
+            CountCondition::GreaterThanOrEquals(n) => at_least(n),
+        }
+    }
+}
+
 impl AliasedCondition for OneRelationIsNullFilter {
     /// Conversion from a `OneRelationIsNullFilter` to a query condition tree. Aliased when in a nested `SELECT`.
     ///
-    /// ```rust
-    /// # use sql_connector::*;
+    #[cfg_attr(feature = "internals", doc = "```rust")]
+    #[cfg_attr(not(feature = "internals"), doc = "```ignore")]
+    /// # use sql_connector::{*, internals::*};
     /// # use prisma_models::*;
     /// # use connector::*;
     /// # use prisma_query::ast::*;
@@ -447,8 +671,11 @@ impl AliasedCondition for OneRelationIsNullFilter {
     /// let rf = user.fields().find_from_relation_fields("sites").unwrap();
     ///
     /// let expected = {
-    ///     let compare = Column::from((("test", "User"), "id"))
-    ///         .not_in_selection(Select::from_table("_UserToSites").column("B"));
+    ///     let select = Select::from_table("_UserToSites")
+    ///         .column("B")
+    ///         .so_that(Column::from("B").is_not_null());
+    ///
+    ///     let compare = Column::from((("test", "User"), "id")).not_in_selection(select);
     ///
     ///     ConditionTree::single(compare)
     /// };
@@ -459,29 +686,136 @@ impl AliasedCondition for OneRelationIsNullFilter {
     /// );
     /// ```
     fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
-        let alias = alias.map(|a| a.to_string(None));
+        relation_null_check(&self.field, alias, true)
+    }
+}
 
-        let condition = if self.field.relation_is_inlined_in_parent() {
-            self.field.as_column().opt_table(alias.clone()).is_null()
+impl AliasedCondition for OneRelationIsNotNullFilter {
+    /// Conversion from a `OneRelationIsNotNullFilter` to a query condition
+    /// tree, the inverse of the `OneRelationIsNullFilter`. Aliased when in a
+    /// nested `SELECT`, with an alias of its own for the relation table.
+    ///
+    #[cfg_attr(feature = "internals", doc = "```rust")]
+    #[cfg_attr(not(feature = "internals"), doc = "```ignore")]
+    /// # use sql_connector::{*, internals::*};
+    /// # use prisma_models::*;
+    /// # use connector::*;
+    /// # use prisma_query::ast::*;
+    /// # use serde_json;
+    /// # use std::{fs::File, sync::Arc};
+    /// #
+    /// # let template: SchemaTemplate = serde_json::from_reader(File::open("./test_schema.json").unwrap()).unwrap();
+    /// let schema = template.build(String::from("test"));
+    /// let site = schema.find_model("Site").unwrap();
+    /// let rf = site.fields().find_from_relation_fields("user").unwrap();
+    ///
+    /// let expected = {
+    ///     let select = Select::from_table(Table::from("_UserToSites").alias("t1"))
+    ///         .column(("t1", "A"))
+    ///         .so_that(("t1", "A").is_not_null());
+    ///
+    ///     ConditionTree::single(Column::from(("t0", "id")).in_selection(select))
+    /// };
+    ///
+    /// let cond = rf.one_relation_is_not_null().aliased_cond(Some(Alias::default()));
+    /// assert_eq!(expected, cond);
+    ///
+    /// // Rendered, the `NULL` columns are skipped.
+    /// let sql = SqlDialect::Sqlite.render(Select::from_table(site.table().alias("t0")).so_that(cond)).sql;
+    /// assert!(sql.contains("IN (SELECT"));
+    /// assert!(sql.contains("IS NOT NULL"));
+    /// ```
+    fn aliased_cond(self, alias: Option<Alias>) -> ConditionTree {
+        relation_null_check(&self.field, alias, false)
+    }
+}
+
+/// Checks whether the record is connected through the to-one relation
+/// `field`. The sub-select skips the `NULL` columns of records connected to
+/// nothing, a single `NULL` would make `NOT IN` unknown for every record.
+fn relation_null_check(field: &RelationFieldRef, alias: Option<Alias>, is_null: bool) -> ConditionTree {
+    // The relation table of an inline self-relation is the table of the
+    // enclosing row, so the sub-select gets an alias of its own.
+    let sub_alias = alias.map(|a| a.inc(AliasMode::Table).to_string(None));
+    let alias = alias.map(|a| a.to_string(None));
+
+    let condition = if field.relation_is_inlined_in_parent() {
+        let column = field.as_column().opt_table(alias.clone());
+
+        if is_null {
+            column.is_null()
         } else {
-            let relation = self.field.relation();
+            column.is_not_null()
+        }
+    } else {
+        let relation = field.relation();
+
+        let column = relation
+            .column_for_relation_side(field.relation_side)
+            .opt_table(sub_alias.clone());
 
-            let column = relation
-                .column_for_relation_side(self.field.relation_side)
-                .opt_table(alias.clone());
+        let table = Table::from(relation.relation_table());
+        let relation_table = match sub_alias {
+            Some(ref alias) => table.alias(alias.to_string()),
+            None => table,
+        };
 
-            let table = Table::from(relation.relation_table());
-            let relation_table = match alias {
-                Some(ref alias) => table.alias(alias.to_string()),
-                None => table,
-            };
+        let select = Select::from_table(relation_table)
+            .column(column.clone())
+            .so_that(column.is_not_null());
 
-            let select = Select::from_table(relation_table).column(column);
-            let id_column = self.field.model().id_column().opt_table(alias.clone());
+        let id_column = field.model().id_column().opt_table(alias.clone());
 
+        if is_null {
             id_column.not_in_selection(select)
-        };
+        } else {
+            id_column.in_selection(select)
+        }
+    };
 
-        ConditionTree::single(condition)
+    ConditionTree::single(condition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_like_escapes_wildcards_and_backslashes() {
+        assert_eq!(r"100\%", escape_like(PrismaValue::from("100%")));
+        assert_eq!(r"a\_b", escape_like(PrismaValue::from("a_b")));
+        assert_eq!(r"a\\b", escape_like(PrismaValue::from(r"a\b")));
+        assert_eq!(r"\%\_\\", escape_like(PrismaValue::from(r"%_\")));
+    }
+
+    #[test]
+    fn with_like_escapes_escapes_every_like() {
+        let sql = String::from(r#"SELECT "id" FROM "User" WHERE ("name" LIKE ? AND "name" NOT LIKE ?)"#);
+
+        assert_eq!(
+            r#"SELECT "id" FROM "User" WHERE ("name" LIKE ? ESCAPE '\' AND "name" NOT LIKE ? ESCAPE '\')"#,
+            with_like_escapes(sql)
+        );
+    }
+
+    #[test]
+    fn with_like_escapes_keeps_existing_clauses() {
+        let escaped = String::from(r#"SELECT 1 WHERE "name" LIKE ? ESCAPE '!'"#);
+        let numbered = String::from(r#"SELECT 1 WHERE "name" LIKE ?1"#);
+        let no_like = String::from(r#"SELECT 1 WHERE "name" = ?"#);
+
+        assert_eq!(escaped.clone(), with_like_escapes(escaped));
+        assert_eq!(numbered.clone(), with_like_escapes(numbered));
+        assert_eq!(no_like.clone(), with_like_escapes(no_like));
+    }
+
+    #[test]
+    fn escape_like_keeps_other_characters() {
+        assert_eq!("", escape_like(PrismaValue::from("")));
+        assert_eq!(
+            "Ärger 'quoted' [x]",
+            escape_like(PrismaValue::from("Ärger 'quoted' [x]"))
+        );
+        assert_eq!("42", escape_like(PrismaValue::Int(42)));
     }
 }
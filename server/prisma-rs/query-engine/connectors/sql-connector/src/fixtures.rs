@@ -0,0 +1,188 @@
+use crate::{database::SqlDatabase, mutaction::MutationBuilder, Transaction, Transactional};
+use connector::{error::ConnectorError, ConnectorResult};
+use prisma_models::prelude::*;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// Records to load into an empty database, e.g. for tests or demos. Records
+/// refer to each other by their keys, so the fixture does not depend on the
+/// generated ids.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixture {
+    /// Removes the data of all models before loading the records.
+    #[serde(default)]
+    pub truncate: bool,
+    pub records: Vec<FixtureRecord>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureRecord {
+    /// The name other records of the fixture use for this one.
+    pub key: String,
+    pub model: String,
+    /// Values of the scalar fields, a `List` for list fields.
+    #[serde(default)]
+    pub values: BTreeMap<String, PrismaValue>,
+    /// The keys of the records connected through each relation field. The
+    /// connected records are created first, so a relation needs to be given
+    /// on one side only.
+    #[serde(default)]
+    pub relations: BTreeMap<String, Vec<String>>,
+}
+
+impl<T> SqlDatabase<T>
+where
+    T: Transactional,
+{
+    /// Loads the records of the fixture in one transaction, the ones a record
+    /// is connected to before the record itself, and returns the ids of the
+    /// records by their keys. The order only depends on the fixture, so the
+    /// records get the same auto-increment ids every time. Nothing is loaded
+    /// if any record fails.
+    pub fn load_fixture(
+        &self,
+        db_name: &str,
+        project: ProjectRef,
+        fixture: &Fixture,
+    ) -> ConnectorResult<HashMap<String, GraphqlId>> {
+        let records = dependency_order(&fixture.records)?;
+
+        self.executor.with_transaction(db_name, |conn| {
+            if fixture.truncate {
+                conn.truncate(Arc::clone(&project))?;
+            }
+
+            let mut ids = HashMap::with_capacity(records.len());
+
+            for record in records {
+                let model = project.schema().find_model(&record.model)?;
+                let id = self.create_fixture_record(conn, Arc::clone(&model), record)?;
+
+                for (field_name, keys) in record.relations.iter() {
+                    let field = model.fields().find_from_relation_fields(field_name)?;
+
+                    for key in keys {
+                        // UNWRAP: The dependency order checked the keys.
+                        let other_id = ids.get(key).unwrap();
+                        conn.write(MutationBuilder::create_relation(Arc::clone(&field), &id, other_id))?;
+                    }
+                }
+
+                ids.insert(record.key.clone(), id);
+            }
+
+            Ok(ids)
+        })
+    }
+
+    fn create_fixture_record(
+        &self,
+        conn: &mut Transaction,
+        model: ModelRef,
+        record: &FixtureRecord,
+    ) -> ConnectorResult<GraphqlId> {
+        let mut non_list_args = PrismaArgs::new();
+        let mut list_args = Vec::new();
+
+        for (name, value) in record.values.iter() {
+            match (model.fields().find_from_scalar(name)?.is_list, value) {
+                (true, PrismaValue::List(list)) => list_args.push((name.clone(), list.clone())),
+                (true, PrismaValue::Null) => list_args.push((name.clone(), None)),
+                (true, value) => list_args.push((name.clone(), Some(vec![value.clone()]))),
+                (false, value) => non_list_args.insert(name.as_str(), value.clone()),
+            }
+        }
+
+        self.type_registry
+            .write_args(&model, &mut non_list_args, &mut list_args)?;
+
+        let (insert, returned_id) = MutationBuilder::create_node(Arc::clone(&model), non_list_args)?;
+        let last_id = conn.insert(insert)?;
+
+        let id = match returned_id {
+            Some(id) => id,
+            None => GraphqlId::Int(last_id),
+        };
+
+        for (name, list_value) in list_args {
+            let table = model.fields().find_from_scalar(&name)?.scalar_list_table();
+
+            if let Some(insert) = MutationBuilder::create_scalar_list_value(table.table(), &list_value, &id) {
+                conn.insert(insert)?;
+            }
+        }
+
+        Ok(id)
+    }
+}
+
+/// Orders the records so every record comes after the ones in its relations,
+/// otherwise keeping the order of the fixture.
+fn dependency_order(records: &[FixtureRecord]) -> ConnectorResult<Vec<&FixtureRecord>> {
+    let mut keys = HashMap::with_capacity(records.len());
+
+    for (index, record) in records.iter().enumerate() {
+        if keys.insert(record.key.as_str(), index).is_some() {
+            return Err(fixture_error(format!(
+                "The key `{}` is used by more than one record.",
+                record.key
+            )));
+        }
+    }
+
+    for record in records {
+        for key in record.relations.values().flatten() {
+            if !keys.contains_key(key.as_str()) {
+                return Err(fixture_error(format!(
+                    "The record `{}` is connected to the unknown record `{}`.",
+                    record.key, key
+                )));
+            }
+        }
+    }
+
+    let mut loaded = vec![false; records.len()];
+    let mut ordered = Vec::with_capacity(records.len());
+
+    while ordered.len() < records.len() {
+        let next = records.iter().enumerate().position(|(index, record)| {
+            !loaded[index]
+                && record
+                    .relations
+                    .values()
+                    .flatten()
+                    .all(|key| loaded[keys[key.as_str()]])
+        });
+
+        match next {
+            Some(index) => {
+                loaded[index] = true;
+                ordered.push(&records[index]);
+            }
+            None => {
+                let waiting: Vec<&str> = records
+                    .iter()
+                    .zip(loaded.iter())
+                    .filter(|(_, loaded)| !**loaded)
+                    .map(|(record, _)| record.key.as_str())
+                    .collect();
+
+                return Err(fixture_error(format!(
+                    "The records {} are connected in a cycle, give the relations on one side only.",
+                    waiting.join(", ")
+                )));
+            }
+        }
+    }
+
+    Ok(ordered)
+}
+
+fn fixture_error(message: String) -> ConnectorError {
+    ConnectorError::QueryError(failure::err_msg(message))
+}
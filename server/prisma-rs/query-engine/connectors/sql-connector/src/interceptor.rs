@@ -0,0 +1,20 @@
+use connector::{error::ConnectorError, ConnectorResult};
+use prisma_query::ast::ParameterizedValue;
+
+/// A hook around every statement the connector executes. Interceptors are
+/// called in the order they were added.
+pub trait QueryInterceptor: Send + Sync {
+    /// Called with the final statement and its parameters before execution.
+    /// The returned pair is executed instead, an error aborts the query.
+    fn before(
+        &self,
+        sql: String,
+        params: Vec<ParameterizedValue>,
+    ) -> ConnectorResult<(String, Vec<ParameterizedValue>)> {
+        Ok((sql, params))
+    }
+
+    /// Called after execution with the number of rows returned or changed,
+    /// or the error of the statement.
+    fn after(&self, _sql: &str, _result: Result<usize, &ConnectorError>) {}
+}
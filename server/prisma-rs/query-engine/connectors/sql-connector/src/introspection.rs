@@ -0,0 +1,186 @@
+//! Reading the current schema of a database, normalized over the different
+//! databases, e.g. for detecting drift from the data model.
+
+use connector::ConnectorResult;
+use serde::Serialize;
+
+#[cfg(feature = "sqlite")]
+use crate::quote_identifier;
+#[cfg(feature = "sqlite")]
+use rusqlite::{Connection, NO_PARAMS};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseSchema {
+    pub tables: Vec<Table>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Table {
+    pub name: String,
+    /// Views are listed with their columns, but have no keys or indexes.
+    pub is_view: bool,
+    pub columns: Vec<Column>,
+    /// The columns of the primary key in order, empty if there is none.
+    pub primary_key: Vec<String>,
+    pub indexes: Vec<Index>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Column {
+    pub name: String,
+    /// The type as declared, e.g. `TEXT` or `DECIMAL(65,30)`.
+    pub data_type: String,
+    pub nullable: bool,
+    /// The default as an SQL expression.
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Index {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignKey {
+    pub columns: Vec<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    pub on_delete: String,
+}
+
+impl DatabaseSchema {
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.iter().find(|table| table.name == name)
+    }
+}
+
+/// Reads the tables and views of the attached database `schema` from
+/// `sqlite_master` and the table pragmas. SQLite's internal tables are
+/// skipped.
+#[cfg(feature = "sqlite")]
+pub fn introspect_sqlite(conn: &Connection, schema: &str) -> ConnectorResult<DatabaseSchema> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT name, type = 'view' FROM {}.sqlite_master \
+         WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        quote_identifier(schema)
+    ))?;
+
+    let names = stmt
+        .query_map(NO_PARAMS, |row| (row.get(0), row.get(1)))?
+        .collect::<Result<Vec<(String, bool)>, _>>()?;
+
+    let mut tables = Vec::with_capacity(names.len());
+
+    for (name, is_view) in names {
+        tables.push(sqlite_table(conn, schema, name, is_view)?);
+    }
+
+    Ok(DatabaseSchema { tables })
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_table(conn: &Connection, schema: &str, name: String, is_view: bool) -> ConnectorResult<Table> {
+    let pragma = |pragma: &str, argument: &str| {
+        format!(
+            "PRAGMA {}.{}({})",
+            quote_identifier(schema),
+            pragma,
+            quote_identifier(argument)
+        )
+    };
+
+    let mut stmt = conn.prepare(&pragma("table_info", &name))?;
+
+    // The position in the primary key, zero if not a part of it.
+    let rows = stmt
+        .query_map(NO_PARAMS, |row| {
+            let column = Column {
+                name: row.get(1),
+                data_type: row.get(2),
+                nullable: row.get::<_, i64>(3) == 0,
+                default: row.get(4),
+            };
+
+            (column, row.get::<_, i64>(5))
+        })?
+        .collect::<Result<Vec<(Column, i64)>, _>>()?;
+
+    let mut primary_key: Vec<(i64, String)> = rows
+        .iter()
+        .filter(|(_, position)| *position > 0)
+        .map(|(column, position)| (*position, column.name.clone()))
+        .collect();
+
+    primary_key.sort();
+
+    let index_names = conn
+        .prepare(&pragma("index_list", &name))?
+        .query_map(NO_PARAMS, |row| (row.get(1), row.get::<_, i64>(2) == 1))?
+        .collect::<Result<Vec<(String, bool)>, _>>()?;
+
+    let mut indexes = Vec::with_capacity(index_names.len());
+
+    for (index_name, unique) in index_names {
+        let columns = conn
+            .prepare(&pragma("index_info", &index_name))?
+            .query_map(NO_PARAMS, |row| row.get(2))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        indexes.push(Index {
+            name: index_name,
+            columns,
+            unique,
+        });
+    }
+
+    // One row per column, the columns of a key sharing the id.
+    let foreign_key_rows = conn
+        .prepare(&pragma("foreign_key_list", &name))?
+        .query_map(NO_PARAMS, |row| {
+            let id: i64 = row.get(0);
+            let referenced_table: String = row.get(2);
+            let column: String = row.get(3);
+            let referenced_column: String = row.get(4);
+            let on_delete: String = row.get(6);
+
+            (id, referenced_table, column, referenced_column, on_delete)
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut foreign_keys: Vec<(i64, ForeignKey)> = Vec::new();
+
+    for (id, referenced_table, column, referenced_column, on_delete) in foreign_key_rows {
+        match foreign_keys.iter_mut().find(|(other, _)| *other == id) {
+            Some((_, foreign_key)) => {
+                foreign_key.columns.push(column);
+                foreign_key.referenced_columns.push(referenced_column);
+            }
+            None => foreign_keys.push((
+                id,
+                ForeignKey {
+                    columns: vec![column],
+                    referenced_table,
+                    referenced_columns: vec![referenced_column],
+                    on_delete,
+                },
+            )),
+        }
+    }
+
+    Ok(Table {
+        name,
+        is_view,
+        columns: rows.into_iter().map(|(column, _)| column).collect(),
+        primary_key: primary_key.into_iter().map(|(_, column)| column).collect(),
+        indexes,
+        foreign_keys: foreign_keys.into_iter().map(|(_, foreign_key)| foreign_key).collect(),
+    })
+}
@@ -5,19 +5,90 @@
 //! - [DataResolver](../connector/trait.DataResolver.html) to fetch data.
 //! - [DatabaseMutactionExecutor](../connector/trait.DatabaseMutactionExecutor.html) to write
 //!   data.
+//!
+//! Both are implemented by `SqlDatabase`, the handle of a connected database,
+//! created e.g. from a `SqliteBuilder`. The traits, query arguments, results
+//! and errors are re-exported, all of the `connector` crate under
+//! `connector`, so the crate can be used as a library on its own.
+//!
+//! The SQLite backend is compiled with the default `sqlite` feature. Without
+//! it, only the database independent parts are built, e.g. for implementing
+//! `Transactional` for another database.
+//!
+//! The query builders are internal and change without notice. With the
+//! `internals` feature they are available from the `internals` module, e.g.
+//! for benchmarks, and their examples only run as doctests with
+//! `cargo test --features internals`. `SqlCapture` shows the SQL they
+//! generate.
 
+mod audit;
+mod change_events;
+mod conformance;
 mod cursor_condition;
+mod encryption;
 mod filter_conversion;
+mod fixtures;
+mod interceptor;
+mod metrics;
+mod mock;
 mod mutaction;
 mod ordering;
+mod outbox;
+mod policy;
 mod query_builder;
+mod query_cache;
+mod query_log;
+mod recording;
+mod redaction;
 mod row;
+mod schema_drift;
+mod shard_router;
+mod single_flight;
+mod sql_capture;
+mod strict_sql;
+mod tenant_router;
 mod transactional;
+mod type_mapping;
+mod value_tables;
 
 pub mod database;
+pub mod introspection;
 
-pub use filter_conversion::*;
-pub use mutaction::*;
-pub use query_builder::SelectDefinition;
+pub use audit::*;
+pub use change_events::*;
+pub use conformance::*;
+pub use encryption::*;
+pub use fixtures::*;
+pub use interceptor::*;
+pub use metrics::*;
+pub use mock::*;
+pub use outbox::*;
+pub use policy::*;
+pub use query_cache::*;
+pub use query_log::*;
+pub use recording::*;
+pub use redaction::*;
 pub use row::*;
+pub use schema_drift::*;
+pub use shard_router::*;
+pub use single_flight::*;
+pub use sql_capture::*;
+pub use strict_sql::*;
+pub use tenant_router::*;
 pub use transactional::*;
+pub use type_mapping::*;
+pub use value_tables::*;
+
+pub use connector::{
+    self, error::ConnectorError, ConnectorResult, DataResolver, DatabaseMutactionExecutor, QueryArguments,
+};
+
+pub(crate) use filter_conversion::*;
+pub(crate) use mutaction::*;
+pub(crate) use query_builder::SelectDefinition;
+
+/// The query builders of the connector, without any stability guarantees.
+#[cfg(feature = "internals")]
+pub mod internals {
+    pub use crate::{filter_conversion::*, mutaction::*, query_builder::SelectDefinition};
+}
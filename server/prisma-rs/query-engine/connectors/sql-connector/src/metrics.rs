@@ -0,0 +1,148 @@
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+/// Receives measurements from the connector. Implement to export them to a
+/// monitoring system, or use `PrometheusMetrics`.
+pub trait Metrics: Send + Sync {
+    /// A statement was executed, `operation` being e.g. `write` or `filter`.
+    fn record_query(&self, operation: &'static str, duration: Duration);
+
+    /// A transaction failed, with the stable code of the error.
+    fn record_error(&self, code: &'static str);
+
+    /// A transaction was committed or rolled back.
+    fn record_transaction(&self, duration: Duration);
+
+    /// The state of the connection pool when taking a connection.
+    fn record_pool(&self, connections: u32, idle_connections: u32, max_size: u32);
+}
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const BUCKETS: [f64; 11] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+#[derive(Default, Clone)]
+struct Histogram {
+    buckets: [u64; 11],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0;
+
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let separator = if labels.is_empty() { "" } else { "," };
+
+        for (bucket, bound) in self.buckets.iter().zip(BUCKETS.iter()) {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}{}le=\"{}\"}} {}",
+                name, labels, separator, bound, bucket
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}{}le=\"+Inf\"}} {}",
+            name, labels, separator, self.count
+        );
+
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", labels)
+        };
+
+        let _ = writeln!(out, "{}_sum{} {}", name, labels, self.sum);
+        let _ = writeln!(out, "{}_count{} {}", name, labels, self.count);
+    }
+}
+
+#[derive(Default)]
+struct Measurements {
+    queries: BTreeMap<&'static str, Histogram>,
+    errors: BTreeMap<&'static str, u64>,
+    transactions: Histogram,
+    pool_connections: u32,
+    pool_idle_connections: u32,
+    pool_max_size: u32,
+}
+
+/// Collects the measurements in memory, rendering them in the Prometheus
+/// text format for scraping.
+#[derive(Default)]
+pub struct PrometheusMetrics {
+    measurements: Mutex<Measurements>,
+}
+
+impl PrometheusMetrics {
+    /// The current values in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let m = self.measurements.lock();
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# TYPE prisma_sql_query_duration_seconds histogram");
+
+        for (operation, histogram) in m.queries.iter() {
+            let labels = format!("operation=\"{}\"", operation);
+            histogram.render(&mut out, "prisma_sql_query_duration_seconds", &labels);
+        }
+
+        let _ = writeln!(out, "# TYPE prisma_sql_errors_total counter");
+
+        for (code, count) in m.errors.iter() {
+            let _ = writeln!(out, "prisma_sql_errors_total{{code=\"{}\"}} {}", code, count);
+        }
+
+        let _ = writeln!(out, "# TYPE prisma_sql_transaction_duration_seconds histogram");
+        m.transactions
+            .render(&mut out, "prisma_sql_transaction_duration_seconds", "");
+
+        let _ = writeln!(out, "# TYPE prisma_sql_pool_connections gauge");
+        let _ = writeln!(out, "prisma_sql_pool_connections {}", m.pool_connections);
+        let _ = writeln!(out, "# TYPE prisma_sql_pool_idle_connections gauge");
+        let _ = writeln!(out, "prisma_sql_pool_idle_connections {}", m.pool_idle_connections);
+        let _ = writeln!(out, "# TYPE prisma_sql_pool_max_connections gauge");
+        let _ = writeln!(out, "prisma_sql_pool_max_connections {}", m.pool_max_size);
+
+        out
+    }
+}
+
+impl Metrics for PrometheusMetrics {
+    fn record_query(&self, operation: &'static str, duration: Duration) {
+        let mut m = self.measurements.lock();
+        m.queries
+            .entry(operation)
+            .or_insert_with(Histogram::default)
+            .observe(duration);
+    }
+
+    fn record_error(&self, code: &'static str) {
+        let mut m = self.measurements.lock();
+        *m.errors.entry(code).or_insert(0) += 1;
+    }
+
+    fn record_transaction(&self, duration: Duration) {
+        self.measurements.lock().transactions.observe(duration);
+    }
+
+    fn record_pool(&self, connections: u32, idle_connections: u32, max_size: u32) {
+        let mut m = self.measurements.lock();
+
+        m.pool_connections = connections;
+        m.pool_idle_connections = idle_connections;
+        m.pool_max_size = max_size;
+    }
+}
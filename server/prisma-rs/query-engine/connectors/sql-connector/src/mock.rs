@@ -0,0 +1,203 @@
+use crate::{
+    filter_conversion::render_for_sqlite, introspection::DatabaseSchema, HealthStatus, PrismaRow, ReadOperations,
+    Transaction, Transactional, WriteItems, WriteOperations,
+};
+use connector::{error::ConnectorError, Capabilities, ConnectorResult, DatabaseVersion};
+use parking_lot::Mutex;
+use prisma_models::{ProjectRef, TypeIdentifier};
+use prisma_query::{
+    ast::{ParameterizedValue, Query, Select},
+    visitor::{self, Visitor},
+};
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// What the next statement sent to a `MockTransactional` returns.
+#[derive(Debug)]
+pub enum MockResponse {
+    Rows(Vec<PrismaRow>),
+    Written { count: usize, last_id: usize },
+    Error(ConnectorError),
+}
+
+/// A `Transactional` without a database, for unit testing code on top of the
+/// connector. It records every executed query and answers with the canned
+/// responses in the order they were pushed. Without responses left, reads
+/// return no rows and writes change nothing.
+pub struct MockTransactional {
+    capabilities: Capabilities,
+    schema: DatabaseSchema,
+    responses: Mutex<VecDeque<MockResponse>>,
+    queries: Mutex<Vec<Query>>,
+    committed: AtomicUsize,
+    rolled_back: AtomicUsize,
+}
+
+impl Default for MockTransactional {
+    /// Mocks a database with all features of a recent SQLite.
+    fn default() -> Self {
+        Self::new(Capabilities {
+            version: DatabaseVersion::new(3, 38, 0),
+            returning: true,
+            window_functions: true,
+            json: true,
+            native_upsert: true,
+            transactional_ddl: true,
+            max_bind_parameters: 32766,
+        })
+    }
+}
+
+impl MockTransactional {
+    pub fn new(capabilities: Capabilities) -> Self {
+        Self {
+            capabilities,
+            schema: DatabaseSchema { tables: Vec::new() },
+            responses: Mutex::new(VecDeque::new()),
+            queries: Mutex::new(Vec::new()),
+            committed: AtomicUsize::new(0),
+            rolled_back: AtomicUsize::new(0),
+        }
+    }
+
+    /// The schema returned by `introspect`, empty by default.
+    pub fn set_schema(&mut self, schema: DatabaseSchema) {
+        self.schema = schema;
+    }
+
+    pub fn push_response(&self, response: MockResponse) {
+        self.responses.lock().push_back(response);
+    }
+
+    pub fn push_rows(&self, rows: Vec<PrismaRow>) {
+        self.push_response(MockResponse::Rows(rows));
+    }
+
+    pub fn push_written(&self, count: usize, last_id: usize) {
+        self.push_response(MockResponse::Written { count, last_id });
+    }
+
+    pub fn push_error(&self, error: ConnectorError) {
+        self.push_response(MockResponse::Error(error));
+    }
+
+    /// The queries executed so far, including the ones of transactions that
+    /// were rolled back.
+    pub fn queries(&self) -> Vec<Query> {
+        self.queries.lock().clone()
+    }
+
+    /// The executed queries as the SQL and parameters SQLite would get.
+    pub fn sql(&self) -> Vec<(String, Vec<ParameterizedValue>)> {
+        self.queries()
+            .into_iter()
+            .map(render_for_sqlite::<visitor::Sqlite, _>)
+            .collect()
+    }
+
+    /// Forgets the executed queries and the responses not used yet.
+    pub fn reset(&self) {
+        self.queries.lock().clear();
+        self.responses.lock().clear();
+    }
+
+    pub fn committed(&self) -> usize {
+        self.committed.load(Ordering::SeqCst)
+    }
+
+    pub fn rolled_back(&self) -> usize {
+        self.rolled_back.load(Ordering::SeqCst)
+    }
+
+    fn execute(&self, query: Query) -> Option<MockResponse> {
+        self.queries.lock().push(query);
+        self.responses.lock().pop_front()
+    }
+}
+
+impl Transactional for MockTransactional {
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    fn health_check(&self, _db: &str, _timeout: Duration) -> HealthStatus {
+        HealthStatus {
+            healthy: true,
+            duration_ms: 0,
+            error_code: None,
+            error: None,
+            connections: 1,
+            idle_connections: 1,
+        }
+    }
+
+    fn introspect(&self, _db: &str) -> ConnectorResult<DatabaseSchema> {
+        Ok(self.schema.clone())
+    }
+
+    fn invalidate_caches(&self, _db: &str) {}
+
+    fn with_transaction<F, T>(&self, _db: &str, f: F) -> ConnectorResult<T>
+    where
+        F: FnOnce(&mut Transaction) -> ConnectorResult<T>,
+    {
+        let result = f(&mut MockTransaction { mock: self });
+
+        if result.is_ok() {
+            self.committed.fetch_add(1, Ordering::SeqCst);
+        } else {
+            self.rolled_back.fetch_add(1, Ordering::SeqCst);
+        }
+
+        result
+    }
+}
+
+struct MockTransaction<'a> {
+    mock: &'a MockTransactional,
+}
+
+impl<'a> WriteOperations for MockTransaction<'a> {
+    /// Truncating is not recorded as a query.
+    fn truncate(&mut self, _project: ProjectRef) -> ConnectorResult<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, q: Query) -> ConnectorResult<WriteItems> {
+        match self.mock.execute(q) {
+            Some(MockResponse::Written { count, last_id }) => Ok(WriteItems { count, last_id }),
+            Some(MockResponse::Error(e)) => Err(e),
+            Some(response) => Err(unexpected("write", response)),
+            None => Ok(WriteItems { count: 0, last_id: 0 }),
+        }
+    }
+}
+
+impl<'a> ReadOperations for MockTransaction<'a> {
+    fn filter(&mut self, q: Select, _idents: &[TypeIdentifier]) -> ConnectorResult<Vec<PrismaRow>> {
+        match self.mock.execute(q.into()) {
+            Some(MockResponse::Rows(rows)) => Ok(rows),
+            Some(MockResponse::Error(e)) => Err(e),
+            Some(response) => Err(unexpected("read", response)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The plan of the mock is the SQL of the query.
+    fn explain(&mut self, q: Query) -> ConnectorResult<String> {
+        let (sql, _) = visitor::Sqlite::build(q.clone());
+        self.mock.queries.lock().push(q);
+
+        Ok(sql)
+    }
+}
+
+fn unexpected(operation: &str, response: MockResponse) -> ConnectorError {
+    ConnectorError::QueryError(failure::err_msg(format!(
+        "The mock was asked to {}, but the next response is {:?}.",
+        operation, response
+    )))
+}
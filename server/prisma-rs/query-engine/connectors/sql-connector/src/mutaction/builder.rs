@@ -8,7 +8,7 @@ pub struct MutationBuilder;
 impl MutationBuilder {
     const PARAMETER_LIMIT: usize = 10000;
 
-    pub fn create_node(model: ModelRef, mut args: PrismaArgs) -> (Insert, Option<GraphqlId>) {
+    pub fn create_node(model: ModelRef, mut args: PrismaArgs) -> ConnectorResult<(Insert, Option<GraphqlId>)> {
         let model_id = model.fields().id();
 
         let return_id = match args.get_field_value(&model_id.name) {
@@ -29,6 +29,12 @@ impl MutationBuilder {
             .filter(|field| args.has_arg_for(&field.name()))
             .collect();
 
+        for field in fields.iter() {
+            if let (Field::Scalar(sf), Some(value)) = (field, args.get_field_value(field.name())) {
+                Self::check_length(sf, value)?;
+            }
+        }
+
         let fields = fields
             .iter()
             .map(|field| (field.name(), args.take_field_value(field.name()).unwrap()));
@@ -39,7 +45,7 @@ impl MutationBuilder {
             .into_iter()
             .fold(base, |acc, (name, value)| acc.value(name, value));
 
-        (insert.into(), return_id)
+        Ok((insert.into(), return_id))
     }
 
     pub fn create_relation(field: RelationFieldRef, parent_id: &GraphqlId, child_id: &GraphqlId) -> Query {
@@ -135,6 +141,8 @@ impl MutationBuilder {
                 });
             }
 
+            Self::check_length(&field, value)?;
+
             query = query.set(field.db_name(), value.clone());
         }
 
@@ -199,8 +207,11 @@ impl MutationBuilder {
         })
     }
 
+    /// Deletes the data of all tables. Models backed by views are skipped, the
+    /// data is removed from the tables the views read from.
     pub fn truncate_tables(project: ProjectRef) -> Vec<Delete> {
-        let models = project.schema().models();
+        let schema = project.schema();
+        let models: Vec<&ModelRef> = schema.models().iter().filter(|m| !m.is_view()).collect();
         let mut deletes = Vec::new();
 
         deletes = project
@@ -238,6 +249,22 @@ impl MutationBuilder {
         deletes
     }
 
+    /// Databases either reject or silently truncate values longer than the
+    /// column allows, so we check the length before writing.
+    fn check_length(field: &ScalarField, value: &PrismaValue) -> ConnectorResult<()> {
+        match (field.max_length, value) {
+            (Some(max_length), PrismaValue::String(s)) | (Some(max_length), PrismaValue::Enum(s))
+                if s.chars().count() > max_length =>
+            {
+                Err(ConnectorError::ValueTooLong {
+                    column: field.db_name().to_string(),
+                    max_length,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn delete_in_chunks<F>(table: Table, ids: &[&GraphqlId], conditions: F) -> Vec<Delete>
     where
         F: Fn(&[&GraphqlId]) -> Compare,
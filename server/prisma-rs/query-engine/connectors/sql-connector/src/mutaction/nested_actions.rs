@@ -53,6 +53,15 @@ pub trait NestedActions {
         }
     }
 
+    /// A foreign key failing when writing through the relation means the
+    /// relation was violated. Other errors are returned as-is.
+    fn relation_violation_for(&self, error: ConnectorError) -> ConnectorError {
+        match error {
+            ConnectorError::ForeignKeyViolation { .. } => self.relation_violation(),
+            error => error,
+        }
+    }
+
     fn nodes_not_connected(&self, parent_id: Option<GraphqlId>, child_id: Option<GraphqlId>) -> ConnectorError {
         let rf = self.relation_field();
 
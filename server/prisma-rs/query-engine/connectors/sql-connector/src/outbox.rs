@@ -0,0 +1,135 @@
+use crate::{ChangeEvent, Transaction};
+use chrono::Utc;
+use connector::{error::ConnectorError, ConnectorResult};
+use prisma_models::prelude::*;
+use prisma_query::ast::*;
+use std::time::Duration;
+
+/// A table the changes of every mutation are written to in the transaction of
+/// the mutation, so that events are published if and only if the mutation
+/// committed. Consumers claim the events, publish them and complete them
+/// afterwards. The table must exist in the database of the data model:
+///
+/// ```sql
+/// CREATE TABLE "Outbox" (
+///     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+///     "event" TEXT NOT NULL,
+///     "claimedBy" TEXT,
+///     "claimedAt" INTEGER
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    table: String,
+}
+
+/// An event of the outbox, the id telling the order of the events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub event: ChangeEvent,
+}
+
+impl Outbox {
+    pub const ID_COLUMN: &'static str = "id";
+    pub const EVENT_COLUMN: &'static str = "event";
+    pub const CLAIMED_BY_COLUMN: &'static str = "claimedBy";
+    /// Milliseconds since the epoch.
+    pub const CLAIMED_AT_COLUMN: &'static str = "claimedAt";
+
+    pub fn new<S>(table: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { table: table.into() }
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table
+    }
+
+    fn table(&self, db_name: &str) -> Table {
+        (db_name, self.table.as_str()).into()
+    }
+
+    /// Adds the `changes` to the outbox, in the transaction writing them.
+    pub(crate) fn write(&self, conn: &mut Transaction, db_name: &str, changes: &[ChangeEvent]) -> ConnectorResult<()> {
+        for change in changes {
+            let event = PrismaValue::String(serde_json::to_string(change)?);
+            conn.insert(Insert::single_into(self.table(db_name)).value(Self::EVENT_COLUMN, event))?;
+        }
+
+        Ok(())
+    }
+
+    /// Claims the `limit` oldest events for `consumer`, that are not claimed
+    /// or whose claim is older than `lease`, e.g. of a consumer that crashed.
+    pub(crate) fn claim(
+        &self,
+        conn: &mut Transaction,
+        db_name: &str,
+        consumer: &str,
+        limit: usize,
+        lease: Duration,
+    ) -> ConnectorResult<Vec<OutboxEvent>> {
+        let now = Utc::now().timestamp_millis();
+        let lease_ms = lease.as_secs() as i64 * 1000 + i64::from(lease.subsec_millis());
+
+        let claimable = ConditionTree::or(
+            Column::from(Self::CLAIMED_BY_COLUMN).is_null().into(),
+            Column::from(Self::CLAIMED_AT_COLUMN)
+                .less_than(PrismaValue::Int(now - lease_ms))
+                .into(),
+        );
+
+        let select = Select::from_table(self.table(db_name))
+            .column(Column::from(Self::ID_COLUMN))
+            .column(Column::from(Self::EVENT_COLUMN))
+            .so_that(claimable)
+            .order_by(Column::from(Self::ID_COLUMN).ascend())
+            .limit(limit);
+
+        let rows = conn.filter(select, &[TypeIdentifier::Int, TypeIdentifier::String])?;
+        let mut events = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let mut values = row.values.into_iter();
+
+            match (values.next(), values.next()) {
+                (Some(PrismaValue::Int(id)), Some(PrismaValue::String(event))) => events.push(OutboxEvent {
+                    id,
+                    event: serde_json::from_str(&event)?,
+                }),
+                _ => return Err(ConnectorError::ColumnDoesNotExist),
+            }
+        }
+
+        if events.is_empty() {
+            return Ok(events);
+        }
+
+        let ids: Vec<PrismaValue> = events.iter().map(|event| PrismaValue::Int(event.id)).collect();
+
+        let claim = Update::table(self.table(db_name))
+            .set(Self::CLAIMED_BY_COLUMN, PrismaValue::String(consumer.to_string()))
+            .set(Self::CLAIMED_AT_COLUMN, PrismaValue::Int(now))
+            .so_that(Column::from(Self::ID_COLUMN).in_selection(ids));
+
+        conn.update(claim)?;
+
+        Ok(events)
+    }
+
+    /// Removes published events from the outbox, returning how many were
+    /// removed.
+    pub(crate) fn complete(&self, conn: &mut Transaction, db_name: &str, ids: &[i64]) -> ConnectorResult<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let ids: Vec<PrismaValue> = ids.iter().map(|id| PrismaValue::Int(*id)).collect();
+        let delete = Delete::from_table(self.table(db_name)).so_that(Column::from(Self::ID_COLUMN).in_selection(ids));
+
+        conn.delete(delete)
+    }
+}
@@ -0,0 +1,184 @@
+use connector::{filter::Filter, mutaction::*};
+use prisma_models::prelude::*;
+use std::sync::Arc;
+
+/// The operations an `OperationPolicy` decides on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperationKind {
+    Read,
+    Count,
+    Create,
+    Update,
+    Upsert,
+    Delete,
+    UpdateMany,
+    DeleteMany,
+    ResetData,
+    Raw,
+}
+
+/// An operation about to be executed, with the writes nested in a mutation
+/// checked one by one after the mutation itself.
+#[derive(Debug, Clone)]
+pub struct PolicyRequest<'a> {
+    pub kind: OperationKind,
+    /// Empty for resetting the data and raw queries.
+    pub model: Option<ModelRef>,
+    /// The filter of reads and of writes to many records.
+    pub filter: Option<&'a Filter>,
+    /// True for writes nested in a mutation, which only change records
+    /// related to the record of the mutation.
+    pub nested: bool,
+}
+
+impl<'a> PolicyRequest<'a> {
+    pub fn new(kind: OperationKind, model: Option<ModelRef>, filter: Option<&'a Filter>) -> Self {
+        Self {
+            kind,
+            model,
+            filter,
+            nested: false,
+        }
+    }
+
+    /// True if the filter of the operation matches all records, e.g. a
+    /// `deleteMany` without a `where`.
+    pub fn is_unfiltered(&self) -> bool {
+        self.filter.map(matches_all).unwrap_or(true)
+    }
+
+    /// The mutation followed by the writes nested in it.
+    pub(crate) fn for_mutaction(mutaction: &'a TopLevelDatabaseMutaction) -> Vec<Self> {
+        let mut requests = Vec::new();
+
+        match mutaction {
+            TopLevelDatabaseMutaction::CreateNode(cn) => {
+                requests.push(Self::new(OperationKind::Create, Some(Arc::clone(&cn.model)), None));
+                Self::nested(&cn.nested_mutactions, &mut requests);
+            }
+            TopLevelDatabaseMutaction::UpdateNode(un) => {
+                requests.push(Self::new(OperationKind::Update, Some(un.where_.field.model()), None));
+                Self::nested(&un.nested_mutactions, &mut requests);
+            }
+            TopLevelDatabaseMutaction::UpsertNode(ups) => {
+                requests.push(Self::new(OperationKind::Upsert, Some(ups.where_.field.model()), None));
+                Self::nested(&ups.create.nested_mutactions, &mut requests);
+                Self::nested(&ups.update.nested_mutactions, &mut requests);
+            }
+            TopLevelDatabaseMutaction::DeleteNode(dn) => {
+                requests.push(Self::new(OperationKind::Delete, Some(dn.where_.field.model()), None));
+            }
+            TopLevelDatabaseMutaction::UpdateNodes(uns) => requests.push(Self::new(
+                OperationKind::UpdateMany,
+                Some(Arc::clone(&uns.model)),
+                Some(&uns.filter),
+            )),
+            TopLevelDatabaseMutaction::DeleteNodes(dns) => requests.push(Self::new(
+                OperationKind::DeleteMany,
+                Some(Arc::clone(&dns.model)),
+                Some(&dns.filter),
+            )),
+            TopLevelDatabaseMutaction::ResetData(_) => requests.push(Self::new(OperationKind::ResetData, None, None)),
+        }
+
+        requests
+    }
+
+    fn nested(mutactions: &'a NestedMutactions, requests: &mut Vec<Self>) {
+        let mut push = |kind, field: &RelationField, filter| {
+            requests.push(Self {
+                nested: true,
+                ..Self::new(kind, Some(field.related_model()), filter)
+            })
+        };
+
+        for create in mutactions.creates.iter() {
+            push(OperationKind::Create, &create.relation_field, None);
+        }
+
+        for update in mutactions.updates.iter() {
+            push(OperationKind::Update, &update.relation_field, None);
+        }
+
+        for upsert in mutactions.upserts.iter() {
+            push(OperationKind::Upsert, &upsert.relation_field, None);
+        }
+
+        for delete in mutactions.deletes.iter() {
+            push(OperationKind::Delete, &delete.relation_field, None);
+        }
+
+        for update_many in mutactions.update_manys.iter() {
+            push(
+                OperationKind::UpdateMany,
+                &update_many.relation_field,
+                update_many.filter.as_ref(),
+            );
+        }
+
+        for delete_many in mutactions.delete_manys.iter() {
+            push(
+                OperationKind::DeleteMany,
+                &delete_many.relation_field,
+                delete_many.filter.as_ref(),
+            );
+        }
+
+        for create in mutactions.creates.iter() {
+            Self::nested(&create.nested_mutactions, requests);
+        }
+
+        for update in mutactions.updates.iter() {
+            Self::nested(&update.nested_mutactions, requests);
+        }
+
+        for upsert in mutactions.upserts.iter() {
+            Self::nested(&upsert.create.nested_mutactions, requests);
+            Self::nested(&upsert.update.nested_mutactions, requests);
+        }
+    }
+}
+
+/// Decides if an operation may be executed. Policies are consulted before
+/// the transaction of the operation starts, on the thread of the request, so
+/// they can read its tenant, database role, session settings and query tags.
+pub trait OperationPolicy: Send + Sync {
+    /// The reason for denying the operation, if it is denied.
+    fn check(&self, request: &PolicyRequest) -> Result<(), String>;
+}
+
+impl<F> OperationPolicy for F
+where
+    F: Fn(&PolicyRequest) -> Result<(), String> + Send + Sync,
+{
+    fn check(&self, request: &PolicyRequest) -> Result<(), String> {
+        self(request)
+    }
+}
+
+/// Denies top-level writes to many records with a filter matching all of
+/// them, as well as resetting the data.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyUnfilteredWrites;
+
+impl OperationPolicy for DenyUnfilteredWrites {
+    fn check(&self, request: &PolicyRequest) -> Result<(), String> {
+        let model = request.model.as_ref().map(|model| model.name.as_str()).unwrap_or("");
+
+        match request.kind {
+            OperationKind::UpdateMany | OperationKind::DeleteMany if !request.nested && request.is_unfiltered() => {
+                Err(format!("Writing to all records of {} needs a filter.", model))
+            }
+            OperationKind::ResetData => Err(String::from("Resetting the data is not allowed.")),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn matches_all(filter: &Filter) -> bool {
+    match filter {
+        Filter::And(filters) => filters.iter().all(|filter| matches_all(filter)),
+        Filter::BoolFilter(b) => *b,
+        _ => false,
+    }
+}
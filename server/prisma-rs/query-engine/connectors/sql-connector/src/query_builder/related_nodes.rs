@@ -2,7 +2,7 @@ use crate::{cursor_condition::CursorCondition, filter_conversion::AliasedConditi
 use connector::QueryArguments;
 use prisma_models::prelude::*;
 use prisma_query::ast::{
-    row_number, Aliasable, Column, Comparable, ConditionTree, Conjuctive, Function, Joinable, Select, Table,
+    row_number, Aliasable, Column, Comparable, ConditionTree, Conjuctive, Function, Joinable, Orderable, Select, Table,
 };
 use std::sync::Arc;
 
@@ -91,9 +91,23 @@ impl<'a> RelatedNodesQueryBuilder<'a> {
             .value(Table::from(Self::BASE_TABLE_ALIAS).asterisk())
             .value(row_number_part.alias(Self::ROW_NUMBER_ALIAS));
 
+        // The rows keep the order of their row numbers, reversed for `last`
+        // like all other reads. Reversing them again keeps the records of a
+        // parent together, with the parents in ascending order.
+        let parent_id = Column::from((Self::ROW_NUMBER_TABLE_ALIAS, SelectedFields::PARENT_MODEL_ALIAS));
+        let row_number = Column::from((Self::ROW_NUMBER_TABLE_ALIAS, Self::ROW_NUMBER_ALIAS));
+
+        let parent_order = if self.reverse_order {
+            parent_id.descend()
+        } else {
+            parent_id.ascend()
+        };
+
         Select::from_table(Table::from(with_row_numbers).alias(Self::ROW_NUMBER_TABLE_ALIAS))
             .value(Table::from(Self::ROW_NUMBER_TABLE_ALIAS).asterisk())
             .so_that(Self::ROW_NUMBER_ALIAS.between(self.window_limits.0 as i64, self.window_limits.1 as i64))
+            .order_by(parent_order)
+            .order_by(row_number.ascend())
     }
 
     pub fn without_pagination(self) -> Select {
@@ -121,14 +135,28 @@ impl<'a> RelatedNodesQueryBuilder<'a> {
     fn base_query(&self) -> Select {
         let select = Select::from_table(self.from_field.related_model().table());
 
-        self.selected_fields
+        let select = self
+            .selected_fields
             .columns()
             .into_iter()
-            .fold(select, |acc, col| acc.column(col.clone()))
-            .inner_join(
+            .fold(select, |acc, col| acc.column(col.clone()));
+
+        // An inline relation with the foreign key in the related table needs
+        // no join, the parent ids are in the related rows. Otherwise the
+        // relation columns are always read through `Relation::TABLE_ALIAS`, so
+        // a self-relation joining the model table to itself stays unambiguous.
+        if self.is_inlined_in_related_model() {
+            select
+        } else {
+            select.inner_join(
                 self.relation_table()
                     .on(self.id_column().equals(self.opposite_relation_side_column())),
             )
+        }
+    }
+
+    fn is_inlined_in_related_model(&self) -> bool {
+        self.from_field.related_field().relation_is_inlined_in_parent()
     }
 
     fn id_column(&self) -> Column {
@@ -138,13 +166,13 @@ impl<'a> RelatedNodesQueryBuilder<'a> {
     fn relation_side_column(&self) -> Column {
         self.relation
             .column_for_relation_side(self.from_field.relation_side)
-            .table(Relation::TABLE_ALIAS)
+            .table(self.from_field.relation_columns_table())
     }
 
     fn opposite_relation_side_column(&self) -> Column {
         self.relation
             .column_for_relation_side(self.from_field.relation_side.opposite())
-            .table(Relation::TABLE_ALIAS)
+            .table(self.from_field.relation_columns_table())
     }
 
     fn relation_table(&self) -> Table {
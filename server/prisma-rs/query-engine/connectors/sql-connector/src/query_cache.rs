@@ -0,0 +1,132 @@
+use crate::PrismaRow;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Identifies the result of a read query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryCacheKey {
+    pub database: String,
+    /// The tenant of the transaction, if the tenant databases are attached
+    /// to the same connections in turn.
+    pub tenant: Option<String>,
+    /// Changes with every migration of the database, so results are never
+    /// read from an older schema.
+    pub schema_version: i64,
+    /// The session settings of the request, sorted by name, and its database
+    /// role. Views and policies can return different rows for them.
+    pub settings: Vec<(String, String)>,
+    pub role: Option<String>,
+    pub sql: String,
+    pub parameters: Vec<String>,
+}
+
+/// A cache for the results of read queries, consulted before executing a
+/// query. All results of a database must be dropped in `invalidate`, which
+/// is called on every write to it and again after the writing transaction
+/// commits.
+pub trait QueryCache: Send + Sync {
+    fn get(&self, key: &QueryCacheKey) -> Option<Vec<PrismaRow>>;
+
+    /// Changes with every `invalidate` of the database. Taken before a read
+    /// and passed to `put` with its rows.
+    fn generation(&self, database: &str) -> u64;
+
+    /// Must not cache the rows if the database was invalidated since
+    /// `generation`, as they might have been read before a commit of
+    /// another connection.
+    fn put(&self, key: QueryCacheKey, rows: Vec<PrismaRow>, generation: u64);
+
+    fn invalidate(&self, database: &str);
+}
+
+#[derive(Default)]
+struct Entries {
+    rows: HashMap<QueryCacheKey, Vec<PrismaRow>>,
+    generations: HashMap<String, u64>,
+}
+
+/// Keeps the results in memory until the database is written to. Once
+/// `capacity` results are stored, new results are not cached.
+pub struct MemoryQueryCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+impl MemoryQueryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+}
+
+impl QueryCache for MemoryQueryCache {
+    fn get(&self, key: &QueryCacheKey) -> Option<Vec<PrismaRow>> {
+        self.entries.lock().rows.get(key).cloned()
+    }
+
+    fn generation(&self, database: &str) -> u64 {
+        self.entries.lock().generations.get(database).cloned().unwrap_or(0)
+    }
+
+    fn put(&self, key: QueryCacheKey, rows: Vec<PrismaRow>, generation: u64) {
+        let mut entries = self.entries.lock();
+        let current = entries.generations.get(&key.database).cloned().unwrap_or(0);
+
+        if current == generation && entries.rows.len() < self.capacity {
+            entries.rows.insert(key, rows);
+        }
+    }
+
+    fn invalidate(&self, database: &str) {
+        let mut entries = self.entries.lock();
+
+        entries.rows.retain(|key, _| key.database != database);
+        *entries.generations.entry(database.to_string()).or_insert(0) += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(database: &str) -> QueryCacheKey {
+        QueryCacheKey {
+            database: database.to_string(),
+            tenant: None,
+            schema_version: 1,
+            settings: Vec::new(),
+            role: None,
+            sql: String::from("SELECT \"id\" FROM \"Item\""),
+            parameters: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reads_started_before_an_invalidation_are_not_cached() {
+        let cache = MemoryQueryCache::new(16);
+        let generation = cache.generation("db");
+
+        // Another connection commits while the rows are read.
+        cache.invalidate("db");
+        cache.put(key("db"), vec![PrismaRow::default()], generation);
+
+        assert!(cache.get(&key("db")).is_none());
+
+        cache.put(key("db"), vec![PrismaRow::default()], cache.generation("db"));
+
+        assert!(cache.get(&key("db")).is_some());
+    }
+
+    #[test]
+    fn invalidations_of_other_databases_keep_the_reads() {
+        let cache = MemoryQueryCache::new(16);
+        let generation = cache.generation("db");
+
+        cache.invalidate("other");
+        cache.put(key("db"), vec![PrismaRow::default()], generation);
+
+        assert!(cache.get(&key("db")).is_some());
+    }
+}
@@ -0,0 +1,153 @@
+use connector::{error::ConnectorError, ConnectorResult};
+use log::{log, Level};
+use prisma_models::ScalarField;
+use prisma_query::ast::ParameterizedValue;
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Settings for logging the executed queries through the `log` crate, with
+/// the targets `sql_connector::query` and `sql_connector::slow_query`.
+#[derive(Debug, Clone, Default)]
+pub struct QueryLog {
+    /// The level queries are logged at, `None` disables the log.
+    pub level: Option<Level>,
+    /// Log `?` instead of the parameter values.
+    pub redact_parameters: bool,
+    /// Statements taking longer are logged as a warning, even if the log is
+    /// otherwise disabled.
+    pub slow_query_threshold: Option<Duration>,
+    /// Statements with these columns are logged with `?` for all parameters.
+    sensitive_columns: Arc<HashSet<String>>,
+}
+
+/// A query being executed, logged when finished.
+pub struct LoggedQuery {
+    level: Option<Level>,
+    slow_query_threshold: Option<Duration>,
+    transaction_id: usize,
+    statement: String,
+    start: Instant,
+}
+
+impl QueryLog {
+    /// Parses the level from the config, e.g. `debug` or `info`.
+    pub fn new(
+        level: Option<&str>,
+        redact_parameters: bool,
+        slow_query_threshold_ms: Option<u64>,
+    ) -> ConnectorResult<Self> {
+        let level = match level {
+            Some(level) => Some(Level::from_str(level).map_err(|_| ConnectorError::InvalidConnectionArguments)?),
+            None => None,
+        };
+
+        Ok(Self {
+            level,
+            redact_parameters,
+            slow_query_threshold: slow_query_threshold_ms.map(Duration::from_millis),
+            sensitive_columns: Arc::new(HashSet::new()),
+        })
+    }
+
+    /// Never logs the values of the field, see `scrub_column`.
+    pub fn scrub_field(&mut self, field: &ScalarField) {
+        self.scrub_column(field.db_name());
+    }
+
+    /// Never logs the values of the column, nor attaches them to errors. The
+    /// parameters can't be told apart in the SQL, so all parameters of the
+    /// statements having the column are hidden, in every table with a
+    /// column of that name.
+    pub fn scrub_column<S>(&mut self, column: S)
+    where
+        S: Into<String>,
+    {
+        Arc::make_mut(&mut self.sensitive_columns).insert(column.into());
+    }
+
+    /// True if the parameters of the statement are logged as `?`.
+    ///
+    /// ```rust
+    /// # use sql_connector::*;
+    /// let mut query_log = QueryLog::default();
+    /// query_log.scrub_column("email");
+    ///
+    /// assert!(query_log.hides_parameters(r#"SELECT "id" FROM "User" WHERE "email" = ?"#));
+    /// assert!(!query_log.hides_parameters(r#"SELECT "id" FROM "User" WHERE "emailConfirmed" = ?"#));
+    /// ```
+    pub fn hides_parameters(&self, sql: &str) -> bool {
+        self.redact_parameters
+            || self
+                .sensitive_columns
+                .iter()
+                .any(|column| references_column(sql, column))
+    }
+
+    /// Starts timing a statement, if either of the logs is enabled. The
+    /// parameters are formatted before executing, so they don't need to
+    /// outlive the query.
+    pub fn start(&self, transaction_id: usize, sql: &str, params: &[ParameterizedValue]) -> Option<LoggedQuery> {
+        if self.level.is_none() && self.slow_query_threshold.is_none() {
+            return None;
+        }
+
+        let params: Vec<String> = if self.hides_parameters(sql) {
+            params.iter().map(|_| String::from("?")).collect()
+        } else {
+            params.iter().map(|param| format!("{:?}", param)).collect()
+        };
+
+        Some(LoggedQuery {
+            level: self.level,
+            slow_query_threshold: self.slow_query_threshold,
+            transaction_id,
+            statement: format!("{} [{}]", sql, params.join(", ")),
+            start: Instant::now(),
+        })
+    }
+}
+
+impl LoggedQuery {
+    /// Logs the statement with the time it took and the number of rows it
+    /// returned or changed.
+    pub fn finish(self, rows: usize) {
+        let duration = self.start.elapsed();
+        let millis = duration.as_secs() * 1000 + u64::from(duration.subsec_millis());
+
+        if let Some(level) = self.level {
+            log!(
+                target: "sql_connector::query",
+                level,
+                "{} ({}ms, {} rows, transaction {})",
+                self.statement,
+                millis,
+                rows,
+                self.transaction_id
+            );
+        }
+
+        match self.slow_query_threshold {
+            Some(threshold) if duration > threshold => log!(
+                target: "sql_connector::slow_query",
+                Level::Warn,
+                "Slow query: {} ({}ms, {} rows, transaction {})",
+                self.statement,
+                millis,
+                rows,
+                self.transaction_id
+            ),
+            _ => (),
+        }
+    }
+}
+
+/// True if the column appears quoted in the statement.
+fn references_column(sql: &str, column: &str) -> bool {
+    [('"', '"'), ('`', '`'), ('[', ']')]
+        .iter()
+        .any(|(open, close)| sql.contains(&format!("{}{}{}", open, column, close)))
+}
@@ -0,0 +1,328 @@
+use crate::{
+    introspection::DatabaseSchema, HealthStatus, PrismaRow, ReadOperations, Transaction, Transactional, WriteItems,
+    WriteOperations,
+};
+use connector::{error::ConnectorError, Capabilities, ConnectorResult};
+use parking_lot::Mutex;
+use prisma_models::{PrismaValue, ProjectRef, TypeIdentifier};
+use prisma_query::{
+    ast::{Query, Select},
+    visitor::{self, Visitor},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// A query sent to the database and what it returned, one JSON line in a
+/// recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedQuery {
+    /// The SQL as SQLite gets it.
+    pub sql: String,
+    /// The parameters in their debug format.
+    pub params: Vec<String>,
+    pub result: RecordedResult,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordedResult {
+    Rows(Vec<Vec<PrismaValue>>),
+    Written {
+        count: usize,
+        last_id: usize,
+    },
+    Plan(String),
+    /// The message of the error, replayed as a `QueryError`.
+    Error(String),
+}
+
+impl RecordedQuery {
+    fn new(query: Query, result: RecordedResult) -> Self {
+        let (sql, params) = visitor::Sqlite::build(query);
+        let params = params.iter().map(|param| format!("{:?}", param)).collect();
+
+        Self { sql, params, result }
+    }
+}
+
+/// Executes the transactions with `T`, appending every query with its result
+/// to a file to be replayed later by a `ReplayTransactional`. Queries of
+/// transactions running at the same time are written in the order they
+/// finished, so recordings are meant for a single thread.
+pub struct RecordingTransactional<T>
+where
+    T: Transactional,
+{
+    inner: T,
+    file: Mutex<File>,
+}
+
+impl<T> RecordingTransactional<T>
+where
+    T: Transactional,
+{
+    /// Appends to the recording at `path`, created if it doesn't exist.
+    pub fn new<P>(inner: T, path: P) -> ConnectorResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| ConnectorError::QueryError(e.into()))?;
+
+        Ok(Self {
+            inner,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn record(&self, query: Query, result: RecordedResult) -> ConnectorResult<()> {
+        let line = serde_json::to_string(&RecordedQuery::new(query, result))?;
+
+        writeln!(self.file.lock(), "{}", line).map_err(|e| ConnectorError::QueryError(e.into()))
+    }
+}
+
+impl<T> Transactional for RecordingTransactional<T>
+where
+    T: Transactional,
+{
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    fn health_check(&self, db: &str, timeout: Duration) -> HealthStatus {
+        self.inner.health_check(db, timeout)
+    }
+
+    fn introspect(&self, db: &str) -> ConnectorResult<DatabaseSchema> {
+        self.inner.introspect(db)
+    }
+
+    fn invalidate_caches(&self, db: &str) {
+        self.inner.invalidate_caches(db)
+    }
+
+    fn with_transaction<F, R>(&self, db: &str, f: F) -> ConnectorResult<R>
+    where
+        F: FnOnce(&mut Transaction) -> ConnectorResult<R>,
+    {
+        self.inner
+            .with_transaction(db, |inner| f(&mut RecordingTransaction { inner, recording: self }))
+    }
+}
+
+struct RecordingTransaction<'a, T>
+where
+    T: Transactional,
+{
+    inner: &'a mut Transaction,
+    recording: &'a RecordingTransactional<T>,
+}
+
+impl<'a, T> WriteOperations for RecordingTransaction<'a, T>
+where
+    T: Transactional,
+{
+    /// Truncating is not recorded, it is not replayed either.
+    fn truncate(&mut self, project: ProjectRef) -> ConnectorResult<()> {
+        self.inner.truncate(project)
+    }
+
+    fn write(&mut self, q: Query) -> ConnectorResult<WriteItems> {
+        let result = self.inner.write(q.clone());
+
+        let recorded = match result {
+            Ok(ref items) => RecordedResult::Written {
+                count: items.count,
+                last_id: items.last_id,
+            },
+            Err(ref e) => RecordedResult::Error(e.to_string()),
+        };
+
+        self.recording.record(q, recorded)?;
+
+        result
+    }
+}
+
+impl<'a, T> ReadOperations for RecordingTransaction<'a, T>
+where
+    T: Transactional,
+{
+    fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> ConnectorResult<Vec<PrismaRow>> {
+        let result = self.inner.filter(q.clone(), idents);
+
+        let recorded = match result {
+            Ok(ref rows) => RecordedResult::Rows(rows.iter().map(|row| row.values.clone()).collect()),
+            Err(ref e) => RecordedResult::Error(e.to_string()),
+        };
+
+        self.recording.record(q.into(), recorded)?;
+
+        result
+    }
+
+    fn explain(&mut self, q: Query) -> ConnectorResult<String> {
+        let result = self.inner.explain(q.clone());
+
+        let recorded = match result {
+            Ok(ref plan) => RecordedResult::Plan(plan.clone()),
+            Err(ref e) => RecordedResult::Error(e.to_string()),
+        };
+
+        self.recording.record(q, recorded)?;
+
+        result
+    }
+}
+
+/// Answers the queries of the engine from a recording, without a database.
+/// Every query must be the next one of the recording, with the same SQL and
+/// parameters, so a test fails on the first query the engine builds
+/// differently than when the recording was made.
+pub struct ReplayTransactional {
+    capabilities: Capabilities,
+    queries: Mutex<VecDeque<RecordedQuery>>,
+}
+
+impl ReplayTransactional {
+    /// Replays the recording at `path`, answering with the `capabilities` of
+    /// the recorded database.
+    pub fn open<P>(path: P, capabilities: Capabilities) -> ConnectorResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path).map_err(|e| ConnectorError::QueryError(e.into()))?;
+        let mut queries = Vec::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| ConnectorError::QueryError(e.into()))?;
+
+            if !line.trim().is_empty() {
+                queries.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        Ok(Self::new(queries, capabilities))
+    }
+
+    pub fn new(queries: Vec<RecordedQuery>, capabilities: Capabilities) -> Self {
+        Self {
+            capabilities,
+            queries: Mutex::new(queries.into_iter().collect()),
+        }
+    }
+
+    /// The recorded queries not replayed yet, none after a complete replay.
+    pub fn remaining(&self) -> usize {
+        self.queries.lock().len()
+    }
+
+    fn replay(&self, query: Query) -> ConnectorResult<RecordedResult> {
+        let (sql, params) = visitor::Sqlite::build(query);
+        let params: Vec<String> = params.iter().map(|param| format!("{:?}", param)).collect();
+
+        let next = self.queries.lock().pop_front().ok_or_else(|| {
+            replay_error(format!(
+                "The recording has no more queries, but got `{}` with {:?}.",
+                sql, params
+            ))
+        })?;
+
+        if next.sql != sql || next.params != params {
+            return Err(replay_error(format!(
+                "Expected `{}` with {:?} from the recording, but got `{}` with {:?}.",
+                next.sql, next.params, sql, params
+            )));
+        }
+
+        match next.result {
+            RecordedResult::Error(message) => Err(ConnectorError::QueryError(failure::err_msg(message))),
+            result => Ok(result),
+        }
+    }
+}
+
+impl Transactional for ReplayTransactional {
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    fn health_check(&self, _db: &str, _timeout: Duration) -> HealthStatus {
+        HealthStatus {
+            healthy: true,
+            duration_ms: 0,
+            error_code: None,
+            error: None,
+            connections: 1,
+            idle_connections: 1,
+        }
+    }
+
+    fn introspect(&self, _db: &str) -> ConnectorResult<DatabaseSchema> {
+        Ok(DatabaseSchema { tables: Vec::new() })
+    }
+
+    fn invalidate_caches(&self, _db: &str) {}
+
+    fn with_transaction<F, R>(&self, _db: &str, f: F) -> ConnectorResult<R>
+    where
+        F: FnOnce(&mut Transaction) -> ConnectorResult<R>,
+    {
+        f(&mut ReplayTransaction { replay: self })
+    }
+}
+
+struct ReplayTransaction<'a> {
+    replay: &'a ReplayTransactional,
+}
+
+impl<'a> WriteOperations for ReplayTransaction<'a> {
+    fn truncate(&mut self, _project: ProjectRef) -> ConnectorResult<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, q: Query) -> ConnectorResult<WriteItems> {
+        match self.replay.replay(q)? {
+            RecordedResult::Written { count, last_id } => Ok(WriteItems { count, last_id }),
+            result => Err(unexpected("write", result)),
+        }
+    }
+}
+
+impl<'a> ReadOperations for ReplayTransaction<'a> {
+    fn filter(&mut self, q: Select, _idents: &[TypeIdentifier]) -> ConnectorResult<Vec<PrismaRow>> {
+        match self.replay.replay(q.into())? {
+            RecordedResult::Rows(rows) => Ok(rows.into_iter().map(|values| PrismaRow { values }).collect()),
+            result => Err(unexpected("read", result)),
+        }
+    }
+
+    fn explain(&mut self, q: Query) -> ConnectorResult<String> {
+        match self.replay.replay(q)? {
+            RecordedResult::Plan(plan) => Ok(plan),
+            result => Err(unexpected("explain", result)),
+        }
+    }
+}
+
+fn unexpected(operation: &str, result: RecordedResult) -> ConnectorError {
+    replay_error(format!(
+        "The replay was asked to {}, but recorded {:?}.",
+        operation, result
+    ))
+}
+
+fn replay_error(message: String) -> ConnectorError {
+    ConnectorError::QueryError(failure::err_msg(message))
+}
@@ -0,0 +1,139 @@
+use crate::PrismaRow;
+use connector::database_role;
+use prisma_models::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
+
+/// What a redacted field is read as.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Redaction {
+    /// The value is replaced with this one, e.g. `"***"`. Lists get it for
+    /// every element.
+    Mask(PrismaValue),
+    /// The value is read as `Null`.
+    Omit,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    redaction: Redaction,
+    exempt_roles: HashSet<String>,
+}
+
+/// Fields whose values never leave the connector, replaced when the rows are
+/// read. The values can still be filtered and ordered by, so fields that must
+/// not be guessed need a policy denying such reads as well. Ids can't be
+/// redacted, the engine needs them to assemble the results.
+#[derive(Debug, Default, Clone)]
+pub struct RedactionRules {
+    /// Keyed by the names of the model and the field.
+    rules: HashMap<(String, String), Rule>,
+}
+
+impl RedactionRules {
+    /// Redacts the field of the model for all operations, except those with
+    /// one of the `exempt_roles` set with `with_database_role`.
+    ///
+    /// The role is trusted as set, so it must come from an authenticated
+    /// source. The server only takes it from the `prisma-role` header with
+    /// `trustedGateway` configured, applications embedding the connector
+    /// must not pass on roles claimed by their clients either.
+    pub fn redact<M, F, I>(&mut self, model: M, field: F, redaction: Redaction, exempt_roles: I)
+    where
+        M: Into<String>,
+        F: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let rule = Rule {
+            redaction,
+            exempt_roles: exempt_roles.into_iter().map(Into::into).collect(),
+        };
+
+        self.rules.insert((model.into(), field.into()), rule);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Redacts the scalar values of rows selected with `selected_fields`.
+    pub fn read_rows(&self, selected_fields: &SelectedFields, rows: &mut [PrismaRow]) {
+        if self.is_empty() {
+            return;
+        }
+
+        let redactions: Vec<Option<&Redaction>> = selected_fields
+            .scalar_non_list()
+            .iter()
+            .map(|field| self.find(field))
+            .collect();
+
+        if redactions.iter().all(Option::is_none) {
+            return;
+        }
+
+        for row in rows.iter_mut() {
+            Self::apply(&redactions, &mut row.values);
+        }
+    }
+
+    /// Redacts a record returned by a mutation, e.g. the deleted one.
+    pub fn read_node(&self, model: &Model, node: &mut SingleNode) {
+        if self.is_empty() {
+            return;
+        }
+
+        let fields = model.fields();
+
+        let redactions: Vec<Option<&Redaction>> = node
+            .field_names
+            .iter()
+            .map(|name| fields.find_from_scalar(name).ok().and_then(|field| self.find(&field)))
+            .collect();
+
+        Self::apply(&redactions, &mut node.node.values);
+    }
+
+    /// Redacts a single value read from the field.
+    pub fn read_value(&self, field: &ScalarField, value: PrismaValue) -> PrismaValue {
+        match self.find(field) {
+            Some(redaction) => Self::redacted(redaction, value),
+            None => value,
+        }
+    }
+
+    fn find(&self, field: &ScalarField) -> Option<&Redaction> {
+        if field.is_id() {
+            return None;
+        }
+
+        let rule = self.rules.get(&(field.model().name.clone(), field.name.clone()))?;
+
+        match database_role() {
+            Some(ref role) if rule.exempt_roles.contains(role) => None,
+            _ => Some(&rule.redaction),
+        }
+    }
+
+    fn apply(redactions: &[Option<&Redaction>], values: &mut [PrismaValue]) {
+        for (value, redaction) in values.iter_mut().zip(redactions.iter()) {
+            if let Some(redaction) = redaction {
+                let read = mem::replace(value, PrismaValue::Null);
+                *value = Self::redacted(redaction, read);
+            }
+        }
+    }
+
+    fn redacted(redaction: &Redaction, value: PrismaValue) -> PrismaValue {
+        match (redaction, value) {
+            (Redaction::Omit, _) => PrismaValue::Null,
+            (Redaction::Mask(mask), PrismaValue::List(Some(values))) => {
+                PrismaValue::List(Some(values.iter().map(|_| mask.clone()).collect()))
+            }
+            (Redaction::Mask(mask), _) => mask.clone(),
+        }
+    }
+}
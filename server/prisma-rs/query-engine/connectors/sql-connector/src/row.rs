@@ -1,11 +1,14 @@
 use chrono::{DateTime, Utc};
-use connector::ConnectorResult;
+use connector::{error::ConnectorError, ConnectorResult};
 use prisma_models::{Node, PrismaValue, TypeIdentifier};
 use serde_json;
 use uuid::Uuid;
 
 #[cfg(feature = "sqlite")]
-use rusqlite::{types::Type as SqliteType, Error as SqliteError, Row as SqliteRow};
+use rusqlite::{
+    types::{Type as SqliteType, Value as SqliteValue},
+    Error as SqliteError, Row as SqliteRow,
+};
 
 /// An allocated representation of a `Row` returned from the database.
 #[derive(Debug, Clone, Default)]
@@ -49,40 +52,125 @@ impl<'a, 'stmt> ToPrismaRow for SqliteRow<'a, 'stmt> {
                         SqliteError::FromSqlConversionFailure(i as usize, SqliteType::Text, Box::new(err))
                     })
                 }),
-                TypeIdentifier::UUID => {
-                    let result: Result<String, _> = row.get_checked(i);
-
-                    if let Ok(val) = result {
-                        let uuid = Uuid::parse_str(val.as_ref())?;
-
-                        Ok(PrismaValue::Uuid(uuid))
-                    } else {
-                        result.map(|s| PrismaValue::String(s))
-                    }
-                }
-                TypeIdentifier::DateTime => row.get_checked(i).map(|ts: i64| {
-                    let nsecs = ((ts % 1000) * 1_000_000) as u32;
-                    let secs = (ts / 1000) as i64;
-                    let naive = chrono::NaiveDateTime::from_timestamp(secs, nsecs);
-                    let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
+                TypeIdentifier::UUID => row.get_checked(i).and_then(|val: SqliteValue| match val {
+                    SqliteValue::Text(s) => Uuid::parse_str(&s).map(|u| PrismaValue::Uuid(u)).map_err(|err| {
+                        SqliteError::FromSqlConversionFailure(i as usize, SqliteType::Text, Box::new(err))
+                    }),
+                    SqliteValue::Blob(bytes) => Uuid::from_slice(&bytes).map(|u| PrismaValue::Uuid(u)).map_err(|err| {
+                        SqliteError::FromSqlConversionFailure(i as usize, SqliteType::Blob, Box::new(err))
+                    }),
+                    SqliteValue::Null => Ok(PrismaValue::Null),
+                    SqliteValue::Integer(_) => Err(SqliteError::InvalidColumnType(i, SqliteType::Integer)),
+                    SqliteValue::Real(_) => Err(SqliteError::InvalidColumnType(i, SqliteType::Real)),
+                }),
+                TypeIdentifier::DateTime => row.get_checked(i).and_then(|ts: i64| {
+                    // Timestamps before the epoch have a negative remainder,
+                    // the nanoseconds must always be positive.
+                    let (secs, millis) = match (ts / 1000, ts % 1000) {
+                        (secs, millis) if millis < 0 => (secs - 1, millis + 1000),
+                        (secs, millis) => (secs, millis),
+                    };
 
-                    PrismaValue::DateTime(datetime)
+                    chrono::NaiveDateTime::from_timestamp_opt(secs, (millis * 1_000_000) as u32)
+                        .map(|naive| {
+                            let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
+                            PrismaValue::DateTime(datetime)
+                        })
+                        .ok_or_else(|| SqliteError::IntegralValueOutOfRange(i, ts))
                 }),
             };
 
+            let conversion_failure =
+                |index, actual: SqliteType, reason: String| ConnectorError::ColumnConversionFailure {
+                    index,
+                    expected: format!("{:?}", typid),
+                    actual: actual.to_string(),
+                    reason,
+                };
+
             match result {
                 Ok(pv) => Ok(pv),
-                Err(rusqlite::Error::InvalidColumnType(_, rusqlite::types::Type::Null)) => Ok(PrismaValue::Null),
+                Err(SqliteError::InvalidColumnType(_, SqliteType::Null)) => Ok(PrismaValue::Null),
+                Err(SqliteError::InvalidColumnType(index, actual)) => {
+                    Err(conversion_failure(index, actual, String::from("invalid column type")))
+                }
+                Err(SqliteError::FromSqlConversionFailure(index, actual, err)) => {
+                    Err(conversion_failure(index, actual, err.to_string()))
+                }
+                Err(SqliteError::IntegralValueOutOfRange(index, value)) => Err(conversion_failure(
+                    index,
+                    SqliteType::Integer,
+                    format!("value {} out of range", value),
+                )),
                 Err(e) => Err(e.into()),
             }
         }
 
-        let mut row = PrismaRow::default();
+        let idents = idents.into_iter();
+        let mut values = Vec::with_capacity(idents.size_hint().0);
 
-        for (i, typid) in idents.into_iter().enumerate() {
-            row.values.push(convert(self, i, typid)?);
+        for (i, typid) in idents.enumerate() {
+            values.push(convert(self, i, typid)?);
         }
 
-        Ok(row)
+        Ok(PrismaRow { values })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use rusqlite::{Connection, NO_PARAMS};
+
+    fn read(sql: &str, ident: TypeIdentifier) -> ConnectorResult<PrismaValue> {
+        let conn = Connection::open_in_memory().unwrap();
+
+        conn.query_row(sql, NO_PARAMS, |row| row.to_prisma_row(&[ident]))
+            .unwrap()
+            .map(|row| row.values.into_iter().next().unwrap())
+    }
+
+    fn read_uuid(sql: &str) -> PrismaValue {
+        read(sql, TypeIdentifier::UUID).unwrap()
+    }
+
+    #[test]
+    fn uuid_stored_as_text() {
+        let expected = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let value = read_uuid("SELECT '67e55044-10b1-426f-9247-bb680e5fe0c8'");
+
+        assert_eq!(PrismaValue::Uuid(expected), value);
+    }
+
+    #[test]
+    fn uuid_stored_as_binary() {
+        let expected = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let value = read_uuid("SELECT X'67e5504410b1426f9247bb680e5fe0c8'");
+
+        assert_eq!(PrismaValue::Uuid(expected), value);
+    }
+
+    #[test]
+    fn uuid_null() {
+        assert_eq!(PrismaValue::Null, read_uuid("SELECT NULL"));
+    }
+
+    #[test]
+    fn datetime_before_epoch() {
+        let expected = chrono::NaiveDateTime::from_timestamp(-2, 500_000_000);
+        let value = read("SELECT -1500", TypeIdentifier::DateTime).unwrap();
+
+        assert_eq!(PrismaValue::DateTime(DateTime::from_utc(expected, Utc)), value);
+    }
+
+    #[test]
+    fn wrong_column_type() {
+        match read("SELECT 'foo'", TypeIdentifier::Int) {
+            Err(ConnectorError::ColumnConversionFailure { index, actual, .. }) => {
+                assert_eq!(0, index);
+                assert_eq!("Text", actual);
+            }
+            res => panic!("Expected a conversion failure, got {:?}", res),
+        }
     }
 }
@@ -0,0 +1,278 @@
+use crate::{
+    introspection::{DatabaseSchema, Table},
+    Transactional,
+};
+use connector::{error::ConnectorError, ConnectorResult};
+use prisma_models::prelude::*;
+use std::fmt;
+
+/// A difference between the data model and the tables in the database, that
+/// makes queries against the model fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaDrift {
+    MissingTable {
+        model: String,
+        table: String,
+    },
+    /// The model is backed by a view, but the database has a table of the
+    /// name, or the other way around.
+    WrongTableKind {
+        model: String,
+        table: String,
+        is_view: bool,
+    },
+    MissingRelationTable {
+        relation: String,
+        table: String,
+    },
+    MissingColumn {
+        table: String,
+        column: String,
+    },
+    WrongColumnType {
+        table: String,
+        column: String,
+        field_type: TypeIdentifier,
+        data_type: String,
+    },
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaDrift::MissingTable { model, table } => write!(
+                f,
+                "The table `{}` of the model `{}` does not exist, create it or apply the migrations.",
+                table, model
+            ),
+            SchemaDrift::WrongTableKind { model, table, is_view } => {
+                let (expected, actual) = if *is_view { ("view", "table") } else { ("table", "view") };
+
+                write!(
+                    f,
+                    "The model `{}` is read from a {}, but `{}` is a {}.",
+                    model, expected, table, actual
+                )
+            }
+            SchemaDrift::MissingRelationTable { relation, table } => write!(
+                f,
+                "The table `{}` of the relation `{}` does not exist, create it or apply the migrations.",
+                table, relation
+            ),
+            SchemaDrift::MissingColumn { table, column } => {
+                write!(f, "The column `{}` is missing in the table `{}`.", column, table)
+            }
+            SchemaDrift::WrongColumnType {
+                table,
+                column,
+                field_type,
+                data_type,
+            } => write!(
+                f,
+                "The column `{}` of the table `{}` has the type `{}`, which can't store values of a {} field.",
+                column,
+                table,
+                data_type,
+                field_type.user_friendly_type_name()
+            ),
+        }
+    }
+}
+
+/// Fails with a description of every table and column of the data model
+/// missing or of the wrong type in the database of the `executor`, e.g. for
+/// each shard or tenant database. See `SqlDatabase::validate_schema`.
+pub fn validate_schema<T>(executor: &T, db_name: &str, schema: &Schema) -> ConnectorResult<()>
+where
+    T: Transactional,
+{
+    let drift = schema_drift(schema, &executor.introspect(db_name)?);
+
+    if drift.is_empty() {
+        return Ok(());
+    }
+
+    let report: Vec<String> = drift.iter().map(|d| format!("- {}", d)).collect();
+
+    Err(ConnectorError::SchemaDrift {
+        problems: drift.len(),
+        report: report.join("\n"),
+    })
+}
+
+/// Compares the tables the `schema` is read from and written to with the
+/// introspected `database`. Columns and tables not in the data model are
+/// ignored.
+pub fn schema_drift(schema: &Schema, database: &DatabaseSchema) -> Vec<SchemaDrift> {
+    let mut drift = Vec::new();
+
+    for model in schema.models().iter().filter(|model| !model.is_embedded) {
+        match database.table(&model.table_name()) {
+            Some(table) => {
+                if table.is_view != model.is_view() {
+                    drift.push(SchemaDrift::WrongTableKind {
+                        model: model.name.clone(),
+                        table: table.name.clone(),
+                        is_view: model.is_view(),
+                    });
+                }
+
+                for field in model.fields().scalar_non_list() {
+                    check_column(&mut drift, table, field.db_name(), Some(field.as_ref()));
+                }
+            }
+            None => drift.push(SchemaDrift::MissingTable {
+                model: model.name.clone(),
+                table: model.table_name(),
+            }),
+        }
+
+        for field in model.fields().scalar_list() {
+            let table_name = field.scalar_list_table().table_name().to_string();
+
+            match database.table(&table_name) {
+                Some(table) => {
+                    for column in &[
+                        ScalarListTable::NODE_ID_FIELD_NAME,
+                        ScalarListTable::POSITION_FIELD_NAME,
+                        ScalarListTable::VALUE_FIELD_NAME,
+                    ] {
+                        check_column(&mut drift, table, column, None);
+                    }
+                }
+                None => drift.push(SchemaDrift::MissingTable {
+                    model: model.name.clone(),
+                    table: table_name,
+                }),
+            }
+        }
+    }
+
+    for relation in schema.relations() {
+        use RelationLinkManifestation::*;
+
+        let columns = match relation.manifestation {
+            Some(Inline(ref m)) => match schema.find_model(&m.in_table_of_model_name) {
+                // A missing model table is already reported above.
+                Ok(_) => vec![m.referencing_column.clone()],
+                Err(_) => continue,
+            },
+            Some(RelationTable(ref m)) => {
+                let mut columns = vec![m.model_a_column.clone(), m.model_b_column.clone()];
+                columns.extend(m.id_column.clone());
+
+                columns
+            }
+            None => vec![
+                Relation::MODEL_A_DEFAULT_COLUMN.to_string(),
+                Relation::MODEL_B_DEFAULT_COLUMN.to_string(),
+                String::from("id"),
+            ],
+        };
+
+        let table_name = relation.relation_table_name();
+
+        match database.table(&table_name) {
+            Some(table) => {
+                for column in columns {
+                    check_column(&mut drift, table, &column, None);
+                }
+            }
+            None if relation.is_inline_relation() => (),
+            None => drift.push(SchemaDrift::MissingRelationTable {
+                relation: relation.name.clone(),
+                table: table_name,
+            }),
+        }
+    }
+
+    drift
+}
+
+fn check_column(drift: &mut Vec<SchemaDrift>, table: &Table, column: &str, field: Option<&ScalarField>) {
+    match table.columns.iter().find(|c| c.name == column) {
+        Some(c) => {
+            if let Some(field) = field {
+                if !can_store(field, &c.data_type) {
+                    drift.push(SchemaDrift::WrongColumnType {
+                        table: table.name.clone(),
+                        column: c.name.clone(),
+                        field_type: field.type_identifier,
+                        data_type: c.data_type.clone(),
+                    });
+                }
+            }
+        }
+        None => drift.push(SchemaDrift::MissingColumn {
+            table: table.name.clone(),
+            column: column.to_string(),
+        }),
+    }
+}
+
+/// SQLite stores any value in any column, but converts it according to the
+/// affinity of the declared type. A type is only rejected if the values of
+/// the field would be converted to something we can't read back.
+fn can_store(field: &ScalarField, data_type: &str) -> bool {
+    let affinity = Affinity::of(data_type);
+
+    match field.type_identifier {
+        TypeIdentifier::String | TypeIdentifier::Enum | TypeIdentifier::Json => affinity == Affinity::Text,
+        TypeIdentifier::UUID => match field.uuid_storage() {
+            Some(UuidStorage::Binary) => affinity == Affinity::Blob,
+            _ => affinity == Affinity::Text,
+        },
+        TypeIdentifier::GraphQLID => affinity == Affinity::Text || affinity == Affinity::Integer,
+        TypeIdentifier::Int | TypeIdentifier::Float | TypeIdentifier::Boolean => {
+            affinity != Affinity::Text && affinity != Affinity::Blob
+        }
+        TypeIdentifier::DateTime => affinity != Affinity::Blob,
+        TypeIdentifier::Relation => true,
+    }
+}
+
+/// The type affinities of SQLite, see https://www.sqlite.org/datatype3.html.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Affinity {
+    Integer,
+    Text,
+    Blob,
+    Real,
+    Numeric,
+}
+
+impl Affinity {
+    fn of(data_type: &str) -> Affinity {
+        let data_type = data_type.to_uppercase();
+
+        if data_type.contains("INT") {
+            Affinity::Integer
+        } else if data_type.contains("CHAR") || data_type.contains("CLOB") || data_type.contains("TEXT") {
+            Affinity::Text
+        } else if data_type.is_empty() || data_type.contains("BLOB") {
+            Affinity::Blob
+        } else if data_type.contains("REAL") || data_type.contains("FLOA") || data_type.contains("DOUB") {
+            Affinity::Real
+        } else {
+            Affinity::Numeric
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockTransactional;
+    use std::fs::File;
+
+    #[test]
+    fn executors_missing_the_tables_fail_validation() {
+        let template: SchemaTemplate = serde_json::from_reader(File::open("test_schema.json").unwrap()).unwrap();
+        let schema = template.build(String::from("test"));
+
+        match validate_schema(&MockTransactional::default(), "test", &schema) {
+            Err(ConnectorError::SchemaDrift { problems, .. }) => assert!(problems > 0),
+            result => panic!("Expected the schema drift, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,963 @@
+use connector::{error::ConnectorError, filter::*, mutaction::*, *};
+use prisma_models::*;
+use serde_json::Value;
+use std::{cmp::Ordering, collections::HashMap, sync::Arc};
+
+/// Spreads the records of sharded models over several databases by the value
+/// of their shard key, see `Model::shard_key`. Records are written to the
+/// shard owning their key. Reads filtering the key by equality go to that
+/// shard only, all other reads are sent to every shard one after another and
+/// the results merged in the order and window of the query.
+///
+/// Models without a shard key live on the first shard. Relations are resolved
+/// on every shard, so related records created in nested mutations end up on
+/// the shard of their parent.
+pub struct ShardRouter<D>
+where
+    D: DataResolver + DatabaseMutactionExecutor,
+{
+    shards: Vec<D>,
+}
+
+impl<D> ShardRouter<D>
+where
+    D: DataResolver + DatabaseMutactionExecutor,
+{
+    /// The shards in a fixed order. Adding or reordering shards changes the
+    /// shard of most keys, the records must be moved accordingly.
+    pub fn new(shards: Vec<D>) -> Self {
+        assert!(!shards.is_empty(), "Sharding needs at least one database.");
+
+        Self { shards }
+    }
+
+    pub fn shards(&self) -> &[D] {
+        &self.shards
+    }
+
+    /// The position of the shard owning the records with the shard key
+    /// `value`, from the 64 bit FNV-1a hash of the value.
+    pub fn shard_for(&self, value: &PrismaValue) -> usize {
+        let hash = value.to_string().bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+
+        (hash % self.shards.len() as u64) as usize
+    }
+
+    /// The shards that can have records of `model` matching `filter`.
+    fn targets(&self, model: &Model, filter: Option<&Filter>) -> Vec<usize> {
+        match model.shard_key_field() {
+            None => vec![0],
+            Some(key) => match filter.and_then(|filter| pinned_value(filter, &key)) {
+                Some(value) => vec![self.shard_for(value)],
+                None => (0..self.shards.len()).collect(),
+            },
+        }
+    }
+
+    fn selector_targets(&self, record_selector: &RecordSelector) -> Vec<usize> {
+        let model = record_selector.model();
+
+        let key = match model.shard_key_field() {
+            Some(key) => key,
+            None => return vec![0],
+        };
+
+        let selectors = match record_selector {
+            RecordSelector::Field(node_selector) => std::slice::from_ref(node_selector),
+            RecordSelector::Compound(node_selectors) => node_selectors.as_slice(),
+        };
+
+        match selectors.iter().find(|selector| selector.field.name == key.name) {
+            Some(selector) => vec![self.shard_for(&selector.value)],
+            None => (0..self.shards.len()).collect(),
+        }
+    }
+
+    fn find(
+        &self,
+        record_selector: &RecordSelector,
+        selected_fields: &SelectedFields,
+    ) -> ConnectorResult<Option<(usize, SingleNode)>> {
+        for shard in self.selector_targets(record_selector) {
+            if let Some(node) = self.shards[shard].get_node_by_where(record_selector, selected_fields)? {
+                return Ok(Some((shard, node)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The shard of the record selected by `where_`, `None` if it doesn't
+    /// exist.
+    fn owner(&self, where_: &NodeSelector) -> ConnectorResult<Option<usize>> {
+        let record_selector = RecordSelector::from(where_.clone());
+
+        match self.selector_targets(&record_selector).as_slice() {
+            [shard] => Ok(Some(*shard)),
+            _ => {
+                let selected_fields = SelectedFields::id(where_.field.model());
+                let found = self.find(&record_selector, &selected_fields)?;
+
+                Ok(found.map(|(shard, _)| shard))
+            }
+        }
+    }
+
+    /// The shard of a new record, generating its id first if the model is
+    /// sharded by its id.
+    fn creation_shard(&self, create: &mut CreateNode) -> ConnectorResult<usize> {
+        let key = match create.model.shard_key_field() {
+            Some(key) => key,
+            None => return Ok(0),
+        };
+
+        if key.is_id() && !create.non_list_args.has_arg_for(&key.name) {
+            let id = create.model.generate_id();
+            create.non_list_args.insert(key.name.clone(), id);
+        }
+
+        match create.non_list_args.get_field_value(&key.name) {
+            Some(value) if !value.is_null() => Ok(self.shard_for(value)),
+            _ => Err(ConnectorError::ShardKeyRequired {
+                model: create.model.name.clone(),
+                field: key.name.clone(),
+            }),
+        }
+    }
+
+    /// Records are not moved between shards, so their key stays as created.
+    fn check_key_unchanged(model: &Model, args: &PrismaArgs) -> ConnectorResult<()> {
+        match model.shard_key_field() {
+            Some(key) if args.has_arg_for(&key.name) => Err(ConnectorError::ShardKeyChanged {
+                model: model.name.clone(),
+                field: key.name.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replaces the cursors with filters on the values of the cursor records,
+    /// as the cursor records are only on one of the shards. Tokens are
+    /// decoded like in `CursorCondition`, forged ones fail with
+    /// `InvalidCursor`.
+    fn resolve_cursors(
+        &self,
+        model: &ModelRef,
+        mut query_arguments: QueryArguments,
+    ) -> ConnectorResult<QueryArguments> {
+        let cursors: Vec<(GraphqlId, bool)> = query_arguments
+            .after
+            .take()
+            .map(|id| (id, true))
+            .into_iter()
+            .chain(query_arguments.before.take().map(|id| (id, false)))
+            .collect();
+
+        if cursors.is_empty() {
+            return Ok(query_arguments);
+        }
+
+        let mut filters: Vec<Filter> = query_arguments.filter.take().into_iter().collect();
+
+        for (cursor, is_after) in cursors {
+            let (id, order_value) = match cursor {
+                GraphqlId::String(ref s) if CursorToken::is_token(s) => {
+                    let token = CursorToken::decode(s)?;
+                    (token.id, token.order_values.into_iter().next())
+                }
+                id => (id, None),
+            };
+
+            let order_by = query_arguments.order_by.as_ref();
+            filters.push(self.cursor_filter(model, order_by, id, order_value, is_after)?);
+        }
+
+        query_arguments.filter = Some(Filter::and(filters));
+
+        Ok(query_arguments)
+    }
+
+    /// The records after or before the cursor record in the order of the
+    /// query, see `CursorCondition`. The value of the cursor record is read,
+    /// unless the token of the cursor has it.
+    fn cursor_filter(
+        &self,
+        model: &ModelRef,
+        order_by: Option<&OrderBy>,
+        id: GraphqlId,
+        order_value: Option<PrismaValue>,
+        is_after: bool,
+    ) -> ConnectorResult<Filter> {
+        let id_field = model.fields().id();
+
+        let (field, sort_order) = match order_by {
+            Some(order_by) if order_by.field.name != id_field.name => {
+                (Arc::clone(&order_by.field), order_by.sort_order)
+            }
+            Some(order_by) => return Ok(beyond_cursor(&id_field, id.into(), is_after, order_by.sort_order)),
+            None => return Ok(beyond_cursor(&id_field, id.into(), is_after, SortOrder::Ascending)),
+        };
+
+        // Records with the same value are ordered by id.
+        let id_condition = beyond_cursor(&id_field, id.clone().into(), is_after, SortOrder::Ascending);
+
+        let value = match order_value {
+            Some(value) => value,
+            None => {
+                let mut selected_fields = SelectedFields::id(Arc::clone(model));
+                selected_fields.add_scalar(Arc::clone(&field), true);
+
+                let record_selector = RecordSelector::from(NodeSelector::new(Arc::clone(&id_field), id));
+
+                // Like in SQL, a cursor that doesn't exist matches nothing.
+                match self.find(&record_selector, &selected_fields)? {
+                    Some((_, node)) => node.get_field_value(field.db_name())?.clone(),
+                    None => return Ok(Filter::from(false)),
+                }
+            }
+        };
+
+        let beyond = beyond_cursor(&field, value.clone(), is_after, sort_order);
+
+        Ok(Filter::or(vec![
+            Filter::and(vec![field.equals(value), id_condition]),
+            beyond,
+        ]))
+    }
+}
+
+impl<D> DataResolver for ShardRouter<D>
+where
+    D: DataResolver + DatabaseMutactionExecutor,
+{
+    fn capabilities(&self) -> Capabilities {
+        self.shards[0].capabilities()
+    }
+
+    fn invalidate_caches(&self, database: &str) {
+        for shard in self.shards.iter() {
+            shard.invalidate_caches(database);
+        }
+    }
+
+    fn get_node_by_where(
+        &self,
+        record_selector: &RecordSelector,
+        selected_fields: &SelectedFields,
+    ) -> ConnectorResult<Option<SingleNode>> {
+        Ok(self.find(record_selector, selected_fields)?.map(|(_, node)| node))
+    }
+
+    fn get_nodes(
+        &self,
+        model: ModelRef,
+        query_arguments: QueryArguments,
+        selected_fields: &SelectedFields,
+    ) -> ConnectorResult<ManyNodes> {
+        let targets = self.targets(&model, query_arguments.filter.as_ref());
+
+        if let [shard] = targets.as_slice() {
+            return self.shards[*shard].get_nodes(model, query_arguments, selected_fields);
+        }
+
+        let query_arguments = self.resolve_cursors(&model, query_arguments)?;
+        let window = Window::new(&query_arguments, true);
+        let sorting = Sorting::new(&model, &query_arguments, selected_fields);
+
+        let mut nodes = Vec::new();
+
+        for shard in targets {
+            let shard_arguments = window.shard_arguments(query_arguments.clone());
+            let result = self.shards[shard].get_nodes(Arc::clone(&model), shard_arguments, &sorting.selected_fields)?;
+
+            nodes.extend(result.nodes);
+        }
+
+        let nodes = window.apply(sorting.sort(nodes));
+
+        Ok(sorting.project(nodes, selected_fields))
+    }
+
+    fn get_related_nodes(
+        &self,
+        from_field: RelationFieldRef,
+        from_node_ids: &[GraphqlId],
+        query_arguments: QueryArguments,
+        selected_fields: &SelectedFields,
+    ) -> ConnectorResult<ManyNodes> {
+        let model = from_field.related_model();
+        let is_sharded = from_field.model().shard_key.is_some() || model.shard_key.is_some();
+
+        if !is_sharded {
+            return self.shards[0].get_related_nodes(from_field, from_node_ids, query_arguments, selected_fields);
+        }
+
+        let query_arguments = self.resolve_cursors(&model, query_arguments)?;
+        let window = Window::new(&query_arguments, false);
+        let sorting = Sorting::new(&model, &query_arguments, selected_fields);
+
+        let mut nodes = Vec::new();
+
+        for shard in self.shards.iter() {
+            let shard_arguments = window.shard_arguments(query_arguments.clone());
+            let result = shard.get_related_nodes(
+                Arc::clone(&from_field),
+                from_node_ids,
+                shard_arguments,
+                &sorting.selected_fields,
+            )?;
+
+            nodes.extend(result.nodes);
+        }
+
+        // The window applies to the related records of every parent.
+        let mut parents: Vec<Vec<Node>> = Vec::new();
+        let mut positions: HashMap<Option<GraphqlId>, usize> = HashMap::new();
+
+        for node in nodes {
+            let position = *positions.entry(node.parent_id.clone()).or_insert_with(|| {
+                parents.push(Vec::new());
+                parents.len() - 1
+            });
+
+            parents[position].push(node);
+        }
+
+        let nodes = parents
+            .into_iter()
+            .flat_map(|nodes| window.apply(sorting.sort(nodes)))
+            .collect();
+
+        Ok(sorting.project(nodes, selected_fields))
+    }
+
+    fn get_scalar_list_values_by_node_ids(
+        &self,
+        list_field: ScalarFieldRef,
+        node_ids: Vec<GraphqlId>,
+    ) -> ConnectorResult<Vec<ScalarListValues>> {
+        let mut values = Vec::new();
+
+        for shard in self.targets(&list_field.model(), None) {
+            values.extend(
+                self.shards[shard].get_scalar_list_values_by_node_ids(Arc::clone(&list_field), node_ids.clone())?,
+            );
+        }
+
+        Ok(values)
+    }
+
+    fn count_by_model(&self, model: ModelRef, query_arguments: QueryArguments) -> ConnectorResult<usize> {
+        let targets = self.targets(&model, query_arguments.filter.as_ref());
+
+        if let [shard] = targets.as_slice() {
+            return self.shards[*shard].count_by_model(model, query_arguments);
+        }
+
+        let query_arguments = self.resolve_cursors(&model, query_arguments)?;
+        let window = Window::new(&query_arguments, true);
+        let mut count = 0;
+
+        for shard in targets {
+            let shard_arguments = window.shard_arguments(query_arguments.clone());
+            count += self.shards[shard].count_by_model(Arc::clone(&model), shard_arguments)?;
+        }
+
+        Ok(window.apply_to_count(count))
+    }
+
+    fn count_by_table(&self, database: &str, table: &str) -> ConnectorResult<usize> {
+        let mut count = 0;
+
+        for shard in self.shards.iter() {
+            count += shard.count_by_table(database, table)?;
+        }
+
+        Ok(count)
+    }
+}
+
+impl<D> DatabaseMutactionExecutor for ShardRouter<D>
+where
+    D: DataResolver + DatabaseMutactionExecutor,
+{
+    /// Raw queries are run on the first shard.
+    fn execute_raw(&self, query: String) -> ConnectorResult<Value> {
+        self.shards[0].execute_raw(query)
+    }
+
+    fn execute(
+        &self,
+        db_name: String,
+        mutaction: TopLevelDatabaseMutaction,
+    ) -> ConnectorResult<DatabaseMutactionResult> {
+        match mutaction {
+            TopLevelDatabaseMutaction::CreateNode(mut create) => {
+                let shard = self.creation_shard(&mut create)?;
+                let mutaction = TopLevelDatabaseMutaction::CreateNode(create);
+
+                self.shards[shard].execute(db_name, mutaction)
+            }
+            TopLevelDatabaseMutaction::UpdateNode(ref update) => {
+                Self::check_key_unchanged(&update.where_.field.model(), &update.non_list_args)?;
+
+                // A missing record fails on any shard.
+                let shard = self.owner(&update.where_)?.unwrap_or(0);
+                self.shards[shard].execute(db_name, mutaction)
+            }
+            TopLevelDatabaseMutaction::DeleteNode(ref delete) => {
+                let shard = self.owner(&delete.where_)?.unwrap_or(0);
+                self.shards[shard].execute(db_name, mutaction)
+            }
+            TopLevelDatabaseMutaction::UpsertNode(mut upsert) => {
+                Self::check_key_unchanged(&upsert.where_.field.model(), &upsert.update.non_list_args)?;
+
+                let shard = match self.owner(&upsert.where_)? {
+                    Some(shard) => shard,
+                    None => self.creation_shard(&mut upsert.create)?,
+                };
+
+                self.shards[shard].execute(db_name, TopLevelDatabaseMutaction::UpsertNode(upsert))
+            }
+            TopLevelDatabaseMutaction::UpdateNodes(ref update) => {
+                Self::check_key_unchanged(&update.model, &update.non_list_args)?;
+
+                let targets = self.targets(&update.model, Some(&update.filter));
+                self.execute_many(&targets, db_name, mutaction)
+            }
+            TopLevelDatabaseMutaction::DeleteNodes(ref delete) => {
+                let targets = self.targets(&delete.model, Some(&delete.filter));
+                self.execute_many(&targets, db_name, mutaction)
+            }
+            TopLevelDatabaseMutaction::ResetData(_) => {
+                let targets: Vec<usize> = (0..self.shards.len()).collect();
+                self.execute_many(&targets, db_name, mutaction)
+            }
+        }
+    }
+}
+
+impl<D> ShardRouter<D>
+where
+    D: DataResolver + DatabaseMutactionExecutor,
+{
+    /// Runs the mutaction on every shard of `targets`, summing up the
+    /// affected records. The shards are written one after another, a failing
+    /// shard keeps the changes of the shards before it.
+    fn execute_many(
+        &self,
+        targets: &[usize],
+        db_name: String,
+        mutaction: TopLevelDatabaseMutaction,
+    ) -> ConnectorResult<DatabaseMutactionResult> {
+        let mut count = 0;
+        let mut last_result = None;
+
+        for shard in targets {
+            let result = self.shards[*shard].execute(db_name.clone(), mutaction.clone())?;
+
+            if let Identifier::Count(affected) = result.identifier {
+                count += affected;
+            }
+
+            last_result = Some(result);
+        }
+
+        match last_result {
+            Some(DatabaseMutactionResult {
+                identifier: Identifier::Count(_),
+                typ,
+            }) => Ok(DatabaseMutactionResult {
+                identifier: Identifier::Count(count),
+                typ,
+            }),
+            Some(result) => Ok(result),
+            None => Err(ConnectorError::NodeDoesNotExist),
+        }
+    }
+}
+
+/// The value the shard key must be equal to for records to match `filter`.
+fn pinned_value<'a>(filter: &'a Filter, key: &ScalarField) -> Option<&'a PrismaValue> {
+    match filter {
+        Filter::Scalar(ScalarFilter {
+            field,
+            condition: ScalarCondition::Equals(value),
+        }) if field.name == key.name => Some(value),
+        Filter::And(filters) => filters.iter().find_map(|filter| pinned_value(filter, key)),
+        _ => None,
+    }
+}
+
+/// The records with a `field` after or before the cursor `value`, in the
+/// direction of the sort order.
+fn beyond_cursor(field: &ScalarFieldRef, value: PrismaValue, is_after: bool, sort_order: SortOrder) -> Filter {
+    match (is_after, sort_order) {
+        (true, SortOrder::Ascending) | (false, SortOrder::Descending) => field.greater_than(value),
+        (true, SortOrder::Descending) | (false, SortOrder::Ascending) => field.less_than(value),
+    }
+}
+
+/// The skip and count of a query, applied to the merged records of the
+/// shards.
+struct Window {
+    skip: usize,
+    count: Option<usize>,
+    /// Queries for pages read one more record, to tell if there's another
+    /// page.
+    one_more: bool,
+}
+
+impl Window {
+    fn new(query_arguments: &QueryArguments, one_more: bool) -> Self {
+        Self {
+            skip: query_arguments.skip.unwrap_or(0) as usize,
+            count: query_arguments
+                .last
+                .or(query_arguments.first)
+                .map(|count| count as usize),
+            one_more,
+        }
+    }
+
+    /// Every shard reads the records of the whole window, as it can't know
+    /// how many of the skipped ones are on the other shards.
+    fn shard_arguments(&self, mut query_arguments: QueryArguments) -> QueryArguments {
+        let skip = query_arguments.skip.take().unwrap_or(0);
+
+        if query_arguments.last.is_some() {
+            query_arguments.last = query_arguments.last.map(|last| last + skip);
+        } else {
+            query_arguments.first = query_arguments.first.map(|first| first + skip);
+        }
+
+        query_arguments
+    }
+
+    fn limit(&self) -> Option<usize> {
+        self.count.map(|count| if self.one_more { count + 1 } else { count })
+    }
+
+    fn apply(&self, nodes: Vec<Node>) -> Vec<Node> {
+        let nodes = nodes.into_iter().skip(self.skip);
+
+        match self.limit() {
+            Some(limit) => nodes.take(limit).collect(),
+            None => nodes.collect(),
+        }
+    }
+
+    fn apply_to_count(&self, count: usize) -> usize {
+        let count = count.saturating_sub(self.skip);
+
+        match self.limit() {
+            Some(limit) => count.min(limit),
+            None => count,
+        }
+    }
+}
+
+/// Sorts the records of several shards like the database sorts them, by the
+/// field of the query and then by id, reversed for the last records.
+struct Sorting {
+    /// The selected fields including the fields to sort by.
+    selected_fields: SelectedFields,
+    field_names: Vec<String>,
+    field: Option<usize>,
+    id: usize,
+    descending: bool,
+    reverse: bool,
+}
+
+impl Sorting {
+    fn new(model: &ModelRef, query_arguments: &QueryArguments, selected_fields: &SelectedFields) -> Self {
+        let order_by = query_arguments.order_by.as_ref();
+        let id_field = model.fields().id();
+        let mut extended = selected_fields.clone();
+
+        for field in order_by
+            .map(|order_by| &order_by.field)
+            .into_iter()
+            .chain(Some(&id_field))
+        {
+            if !extended.scalar.iter().any(|selected| selected.field.name == field.name) {
+                extended.add_scalar(Arc::clone(field), true);
+            }
+        }
+
+        let field_names = extended.names();
+        let position = |field: &ScalarField| {
+            field_names
+                .iter()
+                .position(|name| name == field.db_name())
+                .expect("Fields to sort by are selected.")
+        };
+
+        Self {
+            field: order_by.map(|order_by| position(&order_by.field)),
+            id: position(&id_field),
+            descending: order_by
+                .map(|order_by| order_by.sort_order == SortOrder::Descending)
+                .unwrap_or(false),
+            reverse: query_arguments.last.is_some(),
+            field_names,
+            selected_fields: extended,
+        }
+    }
+
+    fn sort(&self, mut nodes: Vec<Node>) -> Vec<Node> {
+        nodes.sort_by(|a, b| {
+            let by_field = match self.field {
+                Some(field) => compare_values(&a.values[field], &b.values[field]),
+                None => Ordering::Equal,
+            };
+
+            let by_field = if self.descending { by_field.reverse() } else { by_field };
+            let ordering = by_field.then_with(|| compare_values(&a.values[self.id], &b.values[self.id]));
+
+            if self.reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        nodes
+    }
+
+    /// Drops the values of the fields only selected for sorting.
+    fn project(&self, nodes: Vec<Node>, selected_fields: &SelectedFields) -> ManyNodes {
+        let field_names = selected_fields.names();
+
+        if field_names == self.field_names {
+            return ManyNodes { nodes, field_names };
+        }
+
+        let positions: Vec<usize> = field_names
+            .iter()
+            .filter_map(|name| self.field_names.iter().position(|other| other == name))
+            .collect();
+
+        let nodes = nodes
+            .into_iter()
+            .map(|node| Node {
+                values: positions
+                    .iter()
+                    .filter_map(|position| node.values.get(*position).cloned())
+                    .collect(),
+                parent_id: node.parent_id,
+            })
+            .collect();
+
+        ManyNodes { nodes, field_names }
+    }
+}
+
+/// Compares values like SQLite, nulls first and numbers by value.
+fn compare_values(a: &PrismaValue, b: &PrismaValue) -> Ordering {
+    match (a, b) {
+        (PrismaValue::Null, PrismaValue::Null) => Ordering::Equal,
+        (PrismaValue::Null, _) => Ordering::Less,
+        (_, PrismaValue::Null) => Ordering::Greater,
+        (PrismaValue::Int(a), PrismaValue::Int(b)) => a.cmp(b),
+        (PrismaValue::Float(a), PrismaValue::Float(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (PrismaValue::Int(a), PrismaValue::Float(b)) => (*a as f64).partial_cmp(b).unwrap_or(Ordering::Equal),
+        (PrismaValue::Float(a), PrismaValue::Int(b)) => a.partial_cmp(&(*b as f64)).unwrap_or(Ordering::Equal),
+        (PrismaValue::Boolean(a), PrismaValue::Boolean(b)) => a.cmp(b),
+        (PrismaValue::DateTime(a), PrismaValue::DateTime(b)) => a.cmp(b),
+        (PrismaValue::Relation(a), PrismaValue::Relation(b)) => a.cmp(b),
+        (PrismaValue::Uuid(a), PrismaValue::Uuid(b)) => a.cmp(b),
+        (PrismaValue::GraphqlId(GraphqlId::Int(a)), PrismaValue::GraphqlId(GraphqlId::Int(b))) => a.cmp(b),
+        (PrismaValue::GraphqlId(GraphqlId::UUID(a)), PrismaValue::GraphqlId(GraphqlId::UUID(b))) => a.cmp(b),
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[cfg(feature = "sqlite")]
+    use crate::database::{SqlDatabase, Sqlite};
+
+    /// The model `User`, with the schema keeping it alive.
+    fn model() -> (SchemaRef, ModelRef) {
+        let template: SchemaTemplate = serde_json::from_reader(File::open("test_schema.json").unwrap()).unwrap();
+        let schema = template.build(String::from("test"));
+        let model = schema.find_model("User").unwrap();
+
+        (schema, model)
+    }
+
+    fn selected_fields(model: &ModelRef) -> SelectedFields {
+        let mut selected_fields = SelectedFields::id(Arc::clone(model));
+        selected_fields.add_scalar(model.fields().find_from_scalar("name").unwrap(), false);
+        selected_fields
+    }
+
+    fn order_by_name(model: &ModelRef, sort_order: SortOrder) -> Option<OrderBy> {
+        Some(OrderBy {
+            field: model.fields().find_from_scalar("name").unwrap(),
+            sort_order,
+        })
+    }
+
+    /// Records with the ids and names, as read from the shards.
+    fn nodes(records: &[(usize, &str)]) -> Vec<Node> {
+        records
+            .iter()
+            .map(|(id, name)| Node {
+                values: vec![
+                    PrismaValue::GraphqlId(GraphqlId::Int(*id)),
+                    PrismaValue::String(name.to_string()),
+                ],
+                parent_id: None,
+            })
+            .collect()
+    }
+
+    fn ids(nodes: &[Node]) -> Vec<usize> {
+        nodes
+            .iter()
+            .map(|node| match node.values[0] {
+                PrismaValue::GraphqlId(GraphqlId::Int(id)) => id,
+                ref value => panic!("Expected an int id, got {:?}", value),
+            })
+            .collect()
+    }
+
+    fn condition(filter: Filter) -> ScalarCondition {
+        match filter {
+            Filter::Scalar(ScalarFilter { condition, .. }) => condition,
+            filter => panic!("Expected a scalar filter, got {:?}", filter),
+        }
+    }
+
+    #[test]
+    fn sorting_descending_orders_ties_by_id() {
+        let (_schema, model) = model();
+        let query_arguments = QueryArguments {
+            order_by: order_by_name(&model, SortOrder::Descending),
+            ..Default::default()
+        };
+
+        let sorting = Sorting::new(&model, &query_arguments, &selected_fields(&model));
+        let sorted = sorting.sort(nodes(&[(3, "b"), (2, "a"), (1, "b"), (4, "c")]));
+
+        assert_eq!(vec![4, 1, 3, 2], ids(&sorted));
+    }
+
+    #[test]
+    fn sorting_without_order_by_orders_by_id() {
+        let (_schema, model) = model();
+        let sorting = Sorting::new(&model, &QueryArguments::default(), &selected_fields(&model));
+        let sorted = sorting.sort(nodes(&[(2, "a"), (3, "c"), (1, "b")]));
+
+        assert_eq!(vec![1, 2, 3], ids(&sorted));
+    }
+
+    #[test]
+    fn last_with_skip_takes_the_window_from_the_end() {
+        let (_schema, model) = model();
+        let query_arguments = QueryArguments {
+            skip: Some(1),
+            last: Some(2),
+            order_by: order_by_name(&model, SortOrder::Ascending),
+            ..Default::default()
+        };
+
+        let window = Window::new(&query_arguments, true);
+        let shard_arguments = window.shard_arguments(query_arguments.clone());
+
+        // Every shard reads the skipped records too.
+        assert_eq!(None, shard_arguments.skip);
+        assert_eq!(Some(3), shard_arguments.last);
+        assert_eq!(None, shard_arguments.first);
+
+        let sorting = Sorting::new(&model, &query_arguments, &selected_fields(&model));
+        let first_shard = nodes(&[(1, "a"), (4, "d"), (5, "e")]);
+        let second_shard = nodes(&[(2, "b"), (3, "c")]);
+
+        let merged = window.apply(sorting.sort(first_shard.into_iter().chain(second_shard).collect()));
+
+        // Reversed, with one more record telling there's a previous page.
+        assert_eq!(vec![4, 3, 2], ids(&merged));
+    }
+
+    #[test]
+    fn descending_first_with_skip() {
+        let (_schema, model) = model();
+        let query_arguments = QueryArguments {
+            skip: Some(2),
+            first: Some(2),
+            order_by: order_by_name(&model, SortOrder::Descending),
+            ..Default::default()
+        };
+
+        let window = Window::new(&query_arguments, false);
+        assert_eq!(Some(4), window.shard_arguments(query_arguments.clone()).first);
+
+        let sorting = Sorting::new(&model, &query_arguments, &selected_fields(&model));
+        let merged = window.apply(sorting.sort(nodes(&[(1, "a"), (2, "e"), (3, "c"), (4, "d"), (5, "b")])));
+
+        assert_eq!(vec![3, 5], ids(&merged));
+        assert_eq!(2, window.apply_to_count(5));
+        assert_eq!(0, window.apply_to_count(1));
+    }
+
+    #[test]
+    fn cursors_follow_the_sort_order() {
+        let (_schema, model) = model();
+        let id_field = model.fields().id();
+        let cursor = || PrismaValue::GraphqlId(GraphqlId::Int(3));
+
+        let cases = vec![
+            (true, SortOrder::Ascending, true),
+            (false, SortOrder::Ascending, false),
+            (true, SortOrder::Descending, false),
+            (false, SortOrder::Descending, true),
+        ];
+
+        for (is_after, sort_order, greater) in cases {
+            match condition(beyond_cursor(&id_field, cursor(), is_after, sort_order)) {
+                ScalarCondition::GreaterThan(value) if greater => assert_eq!(cursor(), value),
+                ScalarCondition::LessThan(value) if !greater => assert_eq!(cursor(), value),
+                condition => panic!(
+                    "Unexpected condition {:?} for {} the cursor in {:?} order",
+                    condition,
+                    if is_after { "after" } else { "before" },
+                    sort_order
+                ),
+            }
+        }
+    }
+
+    /// Two SQLite shards with the users spread over them by name.
+    #[cfg(feature = "sqlite")]
+    fn sharded_users(name: &str, users: &[(&str, &str)]) -> ShardRouter<SqlDatabase<Sqlite>> {
+        use crate::Transactional;
+        use prisma_query::ast::{Insert, Query};
+        use std::{env, fs};
+
+        let shards = (0..2)
+            .map(|shard| {
+                let folder = env::temp_dir().join(format!("prisma-shards-{}-{}-{}", name, shard, std::process::id()));
+                fs::create_dir_all(&folder).unwrap();
+                let _ = fs::remove_file(folder.join("test.db"));
+
+                SqlDatabase::new(Sqlite::new(folder.to_string_lossy().into_owned(), 1, false).unwrap())
+            })
+            .collect();
+
+        let router = ShardRouter::new(shards);
+
+        for shard in router.shards() {
+            shard
+                .executor
+                .with_transaction("test", |tx| {
+                    tx.write(Query::from(
+                        r#"CREATE TABLE "test"."User" ("id" TEXT PRIMARY KEY, "name" TEXT NOT NULL)"#,
+                    ))
+                    .map(|_| ())
+                })
+                .unwrap();
+        }
+
+        for (id, name) in users {
+            let name = PrismaValue::String(name.to_string());
+            let insert = Insert::single_into(("test", "User"))
+                .value("id", PrismaValue::String(id.to_string()))
+                .value("name", name.clone());
+
+            router.shards()[router.shard_for(&name)]
+                .executor
+                .with_transaction("test", |tx| tx.insert(insert).map(|_| ()))
+                .unwrap();
+        }
+
+        router
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn sharded_reads_page_with_cursor_tokens() {
+        let template: SchemaTemplate = serde_json::from_reader(File::open("test_schema.json").unwrap()).unwrap();
+        let template = SchemaTemplate {
+            models: template
+                .models
+                .into_iter()
+                .map(|model| ModelTemplate {
+                    shard_key: Some(String::from("name")),
+                    ..model
+                })
+                .collect(),
+            ..template
+        };
+
+        let schema = template.build(String::from("test"));
+        let model = schema.find_model("User").unwrap();
+        let users = [
+            ("1", "alice"),
+            ("2", "bob"),
+            ("3", "carol"),
+            ("4", "dave"),
+            ("5", "eve"),
+        ];
+        let router = sharded_users("cursor-tokens", &users);
+
+        let page = |after: Option<GraphqlId>| {
+            let query_arguments = QueryArguments {
+                after,
+                first: Some(2),
+                order_by: order_by_name(&model, SortOrder::Ascending),
+                ..Default::default()
+            };
+
+            let result = router
+                .get_nodes(Arc::clone(&model), query_arguments, &selected_fields(&model))
+                .unwrap();
+
+            result
+                .nodes
+                .into_iter()
+                .map(|node| match node.values[1] {
+                    PrismaValue::String(ref name) => name.clone(),
+                    ref value => panic!("Expected a name, got {:?}", value),
+                })
+                .collect::<Vec<String>>()
+        };
+
+        let token = |id: &str, name: &str| {
+            let token = CursorToken::new(
+                vec![PrismaValue::String(name.to_string())],
+                GraphqlId::String(id.to_string()),
+            );
+
+            Some(GraphqlId::String(token.encode()))
+        };
+
+        // With one more record telling there's a next page.
+        assert_eq!(vec!["alice", "bob", "carol"], page(None));
+        assert_eq!(vec!["carol", "dave", "eve"], page(token("2", "bob")));
+        assert_eq!(vec!["eve"], page(token("4", "dave")));
+
+        let forged = token("2", "bob").map(|cursor| match cursor {
+            GraphqlId::String(s) => GraphqlId::String(s.replace("cursor:", "cursor:x")),
+            id => id,
+        });
+
+        let query_arguments = QueryArguments {
+            after: forged,
+            order_by: order_by_name(&model, SortOrder::Ascending),
+            ..Default::default()
+        };
+
+        match router.get_nodes(Arc::clone(&model), query_arguments, &selected_fields(&model)) {
+            Err(ConnectorError::InvalidCursor) => (),
+            res => panic!("Expected an invalid cursor, got {:?}", res.map(|result| result.nodes)),
+        }
+    }
+}
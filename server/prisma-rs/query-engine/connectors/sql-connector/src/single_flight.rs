@@ -0,0 +1,339 @@
+use crate::{PrismaRow, QueryCacheKey};
+use connector::ConnectorResult;
+use parking_lot::{Condvar, Mutex};
+use std::{collections::HashMap, sync::Arc};
+
+/// A read query being executed, with the readers waiting for its rows.
+#[derive(Default)]
+struct Flight {
+    /// `None` while running, then the rows or `None` if the query failed.
+    landed: Mutex<Option<Option<Vec<PrismaRow>>>>,
+    done: Condvar,
+}
+
+/// Executes identical read queries running at the same time only once,
+/// sharing the rows with all of them. Queries are identical with the same
+/// SQL and parameters in the same database, tenant and schema version, and
+/// with the same session settings and role, see `QueryCacheKey`.
+///
+/// Only the reader executing the query logs it and passes it through the
+/// interceptors. If it fails, the waiting readers execute the query on their
+/// own, with their own errors.
+#[derive(Default)]
+pub struct SingleFlight {
+    in_flight: Mutex<HashMap<QueryCacheKey, Arc<Flight>>>,
+}
+
+/// Lands the flight when the query finished or panicked, so waiting readers
+/// never wait forever.
+struct Landing<'a> {
+    single_flight: &'a SingleFlight,
+    key: &'a QueryCacheKey,
+    flight: &'a Arc<Flight>,
+    rows: Option<Vec<PrismaRow>>,
+}
+
+impl<'a> Drop for Landing<'a> {
+    fn drop(&mut self) {
+        {
+            let mut in_flight = self.single_flight.in_flight.lock();
+
+            // A write might have replaced the flight with a newer one.
+            let is_current = in_flight
+                .get(self.key)
+                .map(|flight| Arc::ptr_eq(flight, self.flight))
+                .unwrap_or(false);
+
+            if is_current {
+                in_flight.remove(self.key);
+            }
+        }
+
+        *self.flight.landed.lock() = Some(self.rows.take());
+        self.flight.done.notify_all();
+    }
+}
+
+impl SingleFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `query`, or waits for the identical query running already and
+    /// takes its rows.
+    pub fn run<F>(&self, key: QueryCacheKey, query: F) -> ConnectorResult<Vec<PrismaRow>>
+    where
+        F: FnOnce() -> ConnectorResult<Vec<PrismaRow>>,
+    {
+        let (flight, running) = {
+            let mut in_flight = self.in_flight.lock();
+
+            match in_flight.get(&key) {
+                Some(flight) => (Arc::clone(flight), true),
+                None => {
+                    let flight = Arc::new(Flight::default());
+                    in_flight.insert(key.clone(), Arc::clone(&flight));
+
+                    (flight, false)
+                }
+            }
+        };
+
+        if running {
+            let rows = {
+                let mut landed = flight.landed.lock();
+
+                while landed.is_none() {
+                    flight.done.wait(&mut landed);
+                }
+
+                landed.clone()
+            };
+
+            return match rows {
+                Some(Some(rows)) => Ok(rows),
+                _ => query(),
+            };
+        }
+
+        let mut landing = Landing {
+            single_flight: self,
+            key: &key,
+            flight: &flight,
+            rows: None,
+        };
+
+        let result = query();
+
+        if let Ok(ref rows) = result {
+            landing.rows = Some(rows.clone());
+        }
+
+        result
+    }
+
+    /// Readers starting after a write to the database don't wait for queries
+    /// started before it, which might not see the write.
+    pub fn invalidate(&self, database: &str) {
+        self.in_flight.lock().retain(|key, _| key.database != database);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use connector::error::ConnectorError;
+    use prisma_models::PrismaValue;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            mpsc,
+        },
+        thread,
+    };
+
+    fn key(database: &str) -> QueryCacheKey {
+        QueryCacheKey {
+            database: database.to_string(),
+            tenant: None,
+            schema_version: 1,
+            settings: Vec::new(),
+            role: None,
+            sql: String::from("SELECT \"id\" FROM \"Item\" WHERE \"id\" = ?"),
+            parameters: vec![String::from("1")],
+        }
+    }
+
+    fn key_of_user(database: &str, user: &str) -> QueryCacheKey {
+        QueryCacheKey {
+            settings: vec![(String::from("app.current_user"), user.to_string())],
+            ..key(database)
+        }
+    }
+
+    fn rows() -> Vec<PrismaRow> {
+        vec![PrismaRow {
+            values: vec![PrismaValue::Int(1)],
+        }]
+    }
+
+    fn values(rows: Vec<PrismaRow>) -> Vec<Vec<PrismaValue>> {
+        rows.into_iter().map(|row| row.values).collect()
+    }
+
+    /// Waits until the flight of `key` is running with `waiters` readers
+    /// waiting for it. The map and the leader hold the flight, plus every
+    /// waiter.
+    fn wait_for_waiters(single_flight: &SingleFlight, key: &QueryCacheKey, waiters: usize) {
+        while single_flight.in_flight.lock().get(key).map(Arc::strong_count) != Some(2 + waiters) {
+            thread::yield_now();
+        }
+    }
+
+    /// Runs the query of `key` on a new thread until `release` is sent to.
+    fn spawn_leader<F>(
+        single_flight: &Arc<SingleFlight>,
+        key: QueryCacheKey,
+        executions: &Arc<AtomicUsize>,
+        query: F,
+    ) -> (mpsc::Sender<()>, thread::JoinHandle<ConnectorResult<Vec<PrismaRow>>>)
+    where
+        F: FnOnce() -> ConnectorResult<Vec<PrismaRow>> + Send + 'static,
+    {
+        let (release, released) = mpsc::channel();
+        let single_flight = Arc::clone(single_flight);
+        let executions = Arc::clone(executions);
+
+        let leader = thread::spawn(move || {
+            single_flight.run(key, move || {
+                executions.fetch_add(1, Ordering::SeqCst);
+                released.recv().unwrap();
+                query()
+            })
+        });
+
+        (release, leader)
+    }
+
+    fn spawn_waiter(
+        single_flight: &Arc<SingleFlight>,
+        key: QueryCacheKey,
+        executions: &Arc<AtomicUsize>,
+    ) -> thread::JoinHandle<ConnectorResult<Vec<PrismaRow>>> {
+        let single_flight = Arc::clone(single_flight);
+        let executions = Arc::clone(executions);
+
+        thread::spawn(move || {
+            single_flight.run(key, move || {
+                executions.fetch_add(1, Ordering::SeqCst);
+                Ok(rows())
+            })
+        })
+    }
+
+    #[test]
+    fn identical_queries_run_once() {
+        let single_flight = Arc::new(SingleFlight::new());
+        let executions = Arc::new(AtomicUsize::new(0));
+
+        let (release, leader) = spawn_leader(&single_flight, key("db"), &executions, || Ok(rows()));
+        wait_for_waiters(&single_flight, &key("db"), 0);
+
+        let waiters: Vec<_> = (0..2)
+            .map(|_| spawn_waiter(&single_flight, key("db"), &executions))
+            .collect();
+        wait_for_waiters(&single_flight, &key("db"), 2);
+
+        release.send(()).unwrap();
+
+        assert_eq!(values(rows()), values(leader.join().unwrap().unwrap()));
+
+        for waiter in waiters {
+            assert_eq!(values(rows()), values(waiter.join().unwrap().unwrap()));
+        }
+
+        assert_eq!(1, executions.load(Ordering::SeqCst));
+        assert!(single_flight.in_flight.lock().is_empty());
+    }
+
+    #[test]
+    fn queries_with_other_settings_run_on_their_own() {
+        let single_flight = Arc::new(SingleFlight::new());
+        let executions = Arc::new(AtomicUsize::new(0));
+
+        let (release_alice, alice) =
+            spawn_leader(&single_flight, key_of_user("db", "alice"), &executions, || Ok(rows()));
+        wait_for_waiters(&single_flight, &key_of_user("db", "alice"), 0);
+
+        let bob_rows = vec![PrismaRow {
+            values: vec![PrismaValue::Int(2)],
+        }];
+
+        let (release_bob, bob) = spawn_leader(&single_flight, key_of_user("db", "bob"), &executions, move || {
+            Ok(bob_rows)
+        });
+        wait_for_waiters(&single_flight, &key_of_user("db", "bob"), 0);
+
+        // Both are running, neither waits for the other.
+        release_bob.send(()).unwrap();
+        assert_eq!(vec![vec![PrismaValue::Int(2)]], values(bob.join().unwrap().unwrap()));
+
+        release_alice.send(()).unwrap();
+        assert_eq!(values(rows()), values(alice.join().unwrap().unwrap()));
+
+        assert_eq!(2, executions.load(Ordering::SeqCst));
+        assert!(single_flight.in_flight.lock().is_empty());
+    }
+
+    #[test]
+    fn waiters_run_the_query_when_the_leader_fails() {
+        let single_flight = Arc::new(SingleFlight::new());
+        let executions = Arc::new(AtomicUsize::new(0));
+
+        let (release, leader) = spawn_leader(&single_flight, key("db"), &executions, || {
+            Err(ConnectorError::QueryCancelled)
+        });
+        wait_for_waiters(&single_flight, &key("db"), 0);
+
+        let waiter = spawn_waiter(&single_flight, key("db"), &executions);
+        wait_for_waiters(&single_flight, &key("db"), 1);
+
+        release.send(()).unwrap();
+
+        assert!(leader.join().unwrap().is_err());
+        assert_eq!(values(rows()), values(waiter.join().unwrap().unwrap()));
+        assert_eq!(2, executions.load(Ordering::SeqCst));
+        assert!(single_flight.in_flight.lock().is_empty());
+    }
+
+    #[test]
+    fn waiters_run_the_query_when_the_leader_panics() {
+        let single_flight = Arc::new(SingleFlight::new());
+        let executions = Arc::new(AtomicUsize::new(0));
+
+        let (release, leader) = spawn_leader(&single_flight, key("db"), &executions, || panic!("query panicked"));
+        wait_for_waiters(&single_flight, &key("db"), 0);
+
+        let waiter = spawn_waiter(&single_flight, key("db"), &executions);
+        wait_for_waiters(&single_flight, &key("db"), 1);
+
+        release.send(()).unwrap();
+
+        assert!(leader.join().is_err());
+        assert_eq!(values(rows()), values(waiter.join().unwrap().unwrap()));
+        assert_eq!(2, executions.load(Ordering::SeqCst));
+        assert!(single_flight.in_flight.lock().is_empty());
+    }
+
+    #[test]
+    fn readers_after_invalidate_run_their_own_query() {
+        let single_flight = Arc::new(SingleFlight::new());
+        let executions = Arc::new(AtomicUsize::new(0));
+
+        let (release_first, first) = spawn_leader(&single_flight, key("db"), &executions, || Ok(rows()));
+        let (release_other, other) = spawn_leader(&single_flight, key("other"), &executions, || Ok(rows()));
+        wait_for_waiters(&single_flight, &key("db"), 0);
+        wait_for_waiters(&single_flight, &key("other"), 0);
+
+        single_flight.invalidate("db");
+
+        assert!(!single_flight.in_flight.lock().contains_key(&key("db")));
+        assert!(single_flight.in_flight.lock().contains_key(&key("other")));
+
+        let (release_second, second) = spawn_leader(&single_flight, key("db"), &executions, || Ok(rows()));
+        wait_for_waiters(&single_flight, &key("db"), 0);
+
+        // The first flight landing must not remove the second one.
+        release_first.send(()).unwrap();
+        assert_eq!(values(rows()), values(first.join().unwrap().unwrap()));
+        assert!(single_flight.in_flight.lock().contains_key(&key("db")));
+
+        release_second.send(()).unwrap();
+        release_other.send(()).unwrap();
+
+        assert_eq!(values(rows()), values(second.join().unwrap().unwrap()));
+        assert_eq!(values(rows()), values(other.join().unwrap().unwrap()));
+        assert_eq!(3, executions.load(Ordering::SeqCst));
+        assert!(single_flight.in_flight.lock().is_empty());
+    }
+}
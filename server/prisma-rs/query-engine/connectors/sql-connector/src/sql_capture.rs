@@ -0,0 +1,132 @@
+use crate::{
+    filter_conversion::render_for_sqlite, mutaction::MutationBuilder, query_builder::QueryBuilder, SelectDefinition,
+};
+use connector::{filter::Filter, ConnectorResult, QueryArguments};
+use prisma_models::prelude::*;
+use prisma_query::{
+    ast::{ParameterizedValue, Query},
+    visitor::{self, Visitor},
+};
+
+/// The databases the queries can be rendered for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqlDialect {
+    Sqlite,
+    /// The `build` of a custom visitor, see `SqlDialect::custom`.
+    Custom(fn(Query) -> (String, Vec<ParameterizedValue>)),
+}
+
+/// A statement as the database would get it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedSql {
+    pub sql: String,
+    pub params: Vec<ParameterizedValue>,
+}
+
+impl SqlDialect {
+    /// Renders with the visitor `V`, e.g. the one given to a `Sqlite`.
+    pub fn custom<V>() -> Self
+    where
+        V: Visitor,
+    {
+        SqlDialect::Custom(V::build::<Query>)
+    }
+
+    pub fn render<Q>(self, query: Q) -> CapturedSql
+    where
+        Q: Into<Query>,
+    {
+        let (sql, params) = match self {
+            SqlDialect::Sqlite => render_for_sqlite::<visitor::Sqlite, _>(query),
+            SqlDialect::Custom(build) => build(query.into()),
+        };
+
+        CapturedSql { sql, params }
+    }
+}
+
+/// Runs the query builders of the connector without executing anything, so
+/// tests can assert on the SQL generated for reads and writes.
+#[derive(Debug, Clone, Copy)]
+pub struct SqlCapture {
+    dialect: SqlDialect,
+}
+
+impl SqlCapture {
+    pub fn new(dialect: SqlDialect) -> Self {
+        Self { dialect }
+    }
+
+    /// The records of `model` matching the arguments, as read by `get_nodes`.
+    pub fn get_nodes(
+        &self,
+        model: ModelRef,
+        selected_fields: &SelectedFields,
+        query_arguments: QueryArguments,
+    ) -> CapturedSql {
+        self.dialect
+            .render(QueryBuilder::get_nodes(model, selected_fields, query_arguments))
+    }
+
+    /// The records related to the records `from_node_ids` through
+    /// `from_field`, as read by `get_related_nodes`.
+    pub fn get_related_nodes(
+        &self,
+        from_field: RelationFieldRef,
+        from_node_ids: &[GraphqlId],
+        query_arguments: QueryArguments,
+        selected_fields: &SelectedFields,
+    ) -> CapturedSql {
+        self.dialect.render(QueryBuilder::get_related_nodes(
+            from_field,
+            from_node_ids,
+            query_arguments,
+            selected_fields,
+        ))
+    }
+
+    pub fn get_scalar_list_values(&self, list_field: ScalarFieldRef, node_ids: Vec<GraphqlId>) -> CapturedSql {
+        self.dialect
+            .render(QueryBuilder::get_scalar_list_values_by_node_ids(list_field, node_ids))
+    }
+
+    pub fn count_by_model(&self, model: ModelRef, query_arguments: QueryArguments) -> CapturedSql {
+        self.dialect
+            .render(QueryBuilder::count_by_model(model, query_arguments))
+    }
+
+    /// The condition of the filter, selecting all columns of the records
+    /// matching it.
+    pub fn filter(&self, model: ModelRef, filter: Filter) -> CapturedSql {
+        self.dialect.render(filter.into_select(model))
+    }
+
+    pub fn create_node(&self, model: ModelRef, args: PrismaArgs) -> ConnectorResult<CapturedSql> {
+        let (insert, _) = MutationBuilder::create_node(model, args)?;
+        Ok(self.dialect.render(insert))
+    }
+
+    pub fn create_relation(&self, field: RelationFieldRef, parent_id: &GraphqlId, child_id: &GraphqlId) -> CapturedSql {
+        self.dialect
+            .render(MutationBuilder::create_relation(field, parent_id, child_id))
+    }
+
+    /// One statement per chunk of ids, none without arguments or ids.
+    pub fn update_many(
+        &self,
+        model: ModelRef,
+        ids: &[&GraphqlId],
+        args: &PrismaArgs,
+    ) -> ConnectorResult<Vec<CapturedSql>> {
+        let updates = MutationBuilder::update_many(model, ids, args)?;
+        Ok(updates.into_iter().map(|update| self.dialect.render(update)).collect())
+    }
+
+    /// Deletes the list values of the records before the records themselves.
+    pub fn delete_many(&self, model: ModelRef, ids: &[&GraphqlId]) -> Vec<CapturedSql> {
+        MutationBuilder::delete_many(model, ids)
+            .into_iter()
+            .map(|delete| self.dialect.render(delete))
+            .collect()
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{introspection::DatabaseSchema, HealthStatus, ReadOperations, Transaction, Transactional};
+use connector::{require_tenant, tenant, Capabilities, ConnectorResult};
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Duration};
+
+/// Routes every transaction to the database of the tenant set with
+/// `with_tenant` on the current thread, e.g. for serving each customer from
+/// their own database. The executors of the tenants are connected on first
+/// use. Once `max_tenants` are connected, the least recently used one is
+/// dropped, closing its pool when its running transactions are done.
+pub struct TenantRouter<T>
+where
+    T: Transactional,
+{
+    capabilities: Capabilities,
+    max_tenants: usize,
+    connect: Box<Fn(&str) -> ConnectorResult<T> + Send + Sync>,
+    /// The connected tenants, the most recently used last.
+    tenants: Mutex<Vec<(String, Arc<T>)>>,
+}
+
+impl<T> TenantRouter<T>
+where
+    T: Transactional,
+{
+    /// Connects the executor of a tenant with `connect`. The `capabilities`
+    /// are the features the databases of all tenants have.
+    pub fn new<F>(capabilities: Capabilities, max_tenants: usize, connect: F) -> Self
+    where
+        F: Fn(&str) -> ConnectorResult<T> + Send + Sync + 'static,
+    {
+        Self {
+            capabilities,
+            max_tenants: max_tenants.max(1),
+            connect: Box::new(connect),
+            tenants: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The tenants currently connected.
+    pub fn tenants(&self) -> Vec<String> {
+        self.tenants.lock().iter().map(|(tenant, _)| tenant.clone()).collect()
+    }
+
+    /// The executor of the tenant of the current thread, connected if it
+    /// isn't already.
+    fn route(&self) -> ConnectorResult<Arc<T>> {
+        let tenant = require_tenant()?;
+
+        if let Some(executor) = self.connected(&tenant) {
+            return Ok(executor);
+        }
+
+        // Connecting can take a while, the other tenants are served meanwhile.
+        let executor = Arc::new((self.connect)(&tenant)?);
+        let mut tenants = self.tenants.lock();
+
+        // Another thread connected the tenant at the same time.
+        if let Some(position) = tenants.iter().position(|(other, _)| *other == tenant) {
+            let entry = tenants.remove(position);
+            let executor = Arc::clone(&entry.1);
+            tenants.push(entry);
+
+            return Ok(executor);
+        }
+
+        if tenants.len() >= self.max_tenants {
+            tenants.remove(0);
+        }
+
+        tenants.push((tenant, Arc::clone(&executor)));
+
+        Ok(executor)
+    }
+
+    /// The executor of an already connected tenant, marked as the most
+    /// recently used.
+    fn connected(&self, tenant: &str) -> Option<Arc<T>> {
+        let mut tenants = self.tenants.lock();
+        let position = tenants.iter().position(|(other, _)| other == tenant)?;
+        let entry = tenants.remove(position);
+        let executor = Arc::clone(&entry.1);
+        tenants.push(entry);
+
+        Some(executor)
+    }
+
+    fn executors(&self) -> Vec<Arc<T>> {
+        self.tenants
+            .lock()
+            .iter()
+            .map(|(_, executor)| Arc::clone(executor))
+            .collect()
+    }
+}
+
+impl<T> Transactional for TenantRouter<T>
+where
+    T: Transactional,
+{
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Checks the database of the current tenant. Without a tenant, all
+    /// connected tenants are checked and must be healthy.
+    fn health_check(&self, db: &str, timeout: Duration) -> HealthStatus {
+        if tenant().is_some() {
+            return match self.route() {
+                Ok(executor) => executor.health_check(db, timeout),
+                Err(e) => HealthStatus {
+                    healthy: false,
+                    duration_ms: 0,
+                    error_code: Some(e.code()),
+                    error: Some(e.to_string()),
+                    connections: 0,
+                    idle_connections: 0,
+                },
+            };
+        }
+
+        let initial = HealthStatus {
+            healthy: true,
+            duration_ms: 0,
+            error_code: None,
+            error: None,
+            connections: 0,
+            idle_connections: 0,
+        };
+
+        self.executors().iter().fold(initial, |acc, executor| {
+            let status = executor.health_check(db, timeout);
+
+            HealthStatus {
+                healthy: acc.healthy && status.healthy,
+                duration_ms: acc.duration_ms.max(status.duration_ms),
+                error_code: acc.error_code.or(status.error_code),
+                error: acc.error.or(status.error),
+                connections: acc.connections + status.connections,
+                idle_connections: acc.idle_connections + status.idle_connections,
+            }
+        })
+    }
+
+    fn introspect(&self, db: &str) -> ConnectorResult<DatabaseSchema> {
+        self.route()?.introspect(db)
+    }
+
+    /// Drops the caches of all connected tenants, as they share the data
+    /// model.
+    fn invalidate_caches(&self, db: &str) {
+        for executor in self.executors() {
+            executor.invalidate_caches(db);
+        }
+    }
+
+    fn with_transaction<F, R>(&self, db: &str, f: F) -> ConnectorResult<R>
+    where
+        F: FnOnce(&mut Transaction) -> ConnectorResult<R>,
+    {
+        self.route()?.with_transaction(db, f)
+    }
+
+    fn with_read_transaction<F, R>(&self, db: &str, f: F) -> ConnectorResult<R>
+    where
+        F: FnOnce(&mut ReadOperations) -> ConnectorResult<R>,
+    {
+        self.route()?.with_read_transaction(db, f)
+    }
+}
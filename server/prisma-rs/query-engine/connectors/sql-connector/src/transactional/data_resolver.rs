@@ -1,8 +1,17 @@
-use crate::{database::SqlDatabase, query_builder::QueryBuilder, Transactional};
-use connector::{error::ConnectorError, filter::NodeSelector, *};
+use crate::{
+    cursor_condition::CursorCondition, database::SqlDatabase, query_builder::QueryBuilder, OperationKind,
+    PolicyRequest, PrismaRow, Transactional,
+};
+use connector::{error::ConnectorError, filter::RecordSelector, *};
+use crossbeam_utils::thread;
 use itertools::Itertools;
 use prisma_models::*;
-use std::convert::TryFrom;
+use prisma_query::ast::Select;
+use std::{cmp, convert::TryFrom, slice, sync::Arc};
+
+/// The batches of a read of related records executed at the same time, each
+/// on its own connection.
+const MAX_CONCURRENT_BATCHES: usize = 4;
 
 struct ScalarListElement {
     node_id: GraphqlId,
@@ -11,28 +20,65 @@ struct ScalarListElement {
 
 impl<T> DataResolver for SqlDatabase<T>
 where
-    T: Transactional,
+    T: Transactional + Sync,
 {
+    fn capabilities(&self) -> Capabilities {
+        self.executor.capabilities()
+    }
+
+    fn invalidate_caches(&self, database: &str) {
+        self.executor.invalidate_caches(database)
+    }
+
     fn get_node_by_where(
         &self,
-        node_selector: &NodeSelector,
+        record_selector: &RecordSelector,
         selected_fields: &SelectedFields,
     ) -> ConnectorResult<Option<SingleNode>> {
-        let db_name = &node_selector.field.model().schema().db_name;
-        let query = QueryBuilder::get_nodes(node_selector.field.model(), selected_fields, node_selector);
+        let model = record_selector.model();
+        let db_name = &model.schema().db_name;
+        self.check_policies(&PolicyRequest::new(OperationKind::Read, Some(Arc::clone(&model)), None))?;
+
+        // Records read by their id before in the request are not read again.
+        let cached = match (node_cache(), record_selector) {
+            (Some(cache), RecordSelector::Field(selector)) if selector.field.is_id() => {
+                GraphqlId::try_from(selector.value.clone()).ok().map(|id| (cache, id))
+            }
+            _ => None,
+        };
+
+        if let Some((ref cache, ref id)) = cached {
+            if let Some(node) = cache.get(db_name, &model.name, id, &selected_fields.names()) {
+                return Ok(Some(node));
+            }
+        }
+
+        let mut record_selector = record_selector.clone();
+        self.type_registry.write_record_selector(&mut record_selector)?;
+
+        let query = QueryBuilder::get_nodes(Arc::clone(&model), selected_fields, Filter::from(record_selector));
         let field_names = selected_fields.names();
         let idents = selected_fields.type_identifiers();
 
         let node = self
             .executor
-            .with_transaction(db_name, |conn| match conn.find(query, idents.as_slice()) {
-                Ok(result) => Ok(Some(result)),
+            .with_read_transaction(db_name, |conn| match conn.find(query, idents.as_slice()) {
+                Ok(mut result) => {
+                    self.type_registry
+                        .read_rows(selected_fields, slice::from_mut(&mut result))?;
+                    self.redactions.read_rows(selected_fields, slice::from_mut(&mut result));
+                    Ok(Some(result))
+                }
                 Err(_e @ ConnectorError::NodeNotFoundForWhere(_)) => Ok(None),
                 Err(e) => Err(e),
             })?
             .map(Node::from)
             .map(|node| SingleNode { node, field_names });
 
+        if let (Some((cache, id)), Some(node)) = (cached, &node) {
+            cache.insert(db_name, &model.name, id, node.clone());
+        }
+
         Ok(node)
     }
 
@@ -43,18 +89,30 @@ where
         selected_fields: &SelectedFields,
     ) -> ConnectorResult<ManyNodes> {
         let db_name = &model.schema().db_name;
+        self.check_policies(&PolicyRequest::new(
+            OperationKind::Read,
+            Some(Arc::clone(&model)),
+            query_arguments.filter.as_ref(),
+        ))?;
+
+        CursorCondition::check(&query_arguments)?;
+
         let field_names = selected_fields.names();
         let idents = selected_fields.type_identifiers();
-        let query = QueryBuilder::get_nodes(model, selected_fields, query_arguments);
+        let query_arguments = self.type_registry.write_query_arguments(query_arguments)?;
 
-        let nodes = self
-            .executor
-            .with_transaction(db_name, |conn| conn.filter(query, idents.as_slice()))?
-            .into_iter()
-            .map(Node::from)
-            .collect();
+        let mut rows = self.read_with_value_tables(db_name, query_arguments, |conn, query_arguments| {
+            let query = QueryBuilder::get_nodes(model, selected_fields, query_arguments);
+            conn.filter(query, idents.as_slice())
+        })?;
 
-        Ok(ManyNodes { nodes, field_names })
+        self.type_registry.read_rows(selected_fields, &mut rows)?;
+        self.redactions.read_rows(selected_fields, &mut rows);
+
+        Ok(ManyNodes {
+            nodes: rows.into_iter().map(Node::from).collect(),
+            field_names,
+        })
     }
 
     fn get_related_nodes(
@@ -64,48 +122,101 @@ where
         query_arguments: QueryArguments,
         selected_fields: &SelectedFields,
     ) -> ConnectorResult<ManyNodes> {
+        self.check_policies(&PolicyRequest::new(
+            OperationKind::Read,
+            Some(from_field.related_model()),
+            query_arguments.filter.as_ref(),
+        ))?;
+
+        CursorCondition::check(&query_arguments)?;
+
+        let capabilities = self.capabilities();
+
+        // Pagination per parent is done with `ROW_NUMBER()`.
+        if query_arguments.is_with_pagination() && !capabilities.window_functions {
+            return Err(ConnectorError::UnsupportedByDatabase {
+                feature: String::from("Paginating related records"),
+                version: capabilities.version.to_string(),
+            });
+        }
+
         let db_name = &from_field.model().schema().db_name;
         let idents = selected_fields.type_identifiers();
         let field_names = selected_fields.names();
-        let query = QueryBuilder::get_related_nodes(from_field, from_node_ids, query_arguments, selected_fields);
+        let query_arguments = self.type_registry.write_query_arguments(query_arguments)?;
+        let reverse = query_arguments.last.is_some();
 
-        let nodes: ConnectorResult<Vec<Node>> = self
-            .executor
-            .with_transaction(db_name, |conn| conn.filter(query, idents.as_slice()))?
-            .into_iter()
-            .map(|mut row| {
-                let parent_id = row.values.pop().ok_or(ConnectorError::ColumnDoesNotExist)?;
+        // Half of the parameters are left for the filter and the cursors.
+        let batch_size = cmp::max(capabilities.max_bind_parameters / 2, 1);
+        let from_node_ids: Vec<GraphqlId> = from_node_ids.iter().unique().cloned().collect();
 
-                // Relation id is always the second last value. We don't need it
-                // here and we don't need it in the node.
-                let _ = row.values.pop();
+        let queries: Vec<Select> = from_node_ids
+            .chunks(batch_size)
+            .map(|ids| {
+                QueryBuilder::get_related_nodes(Arc::clone(&from_field), ids, query_arguments.clone(), selected_fields)
+            })
+            .collect();
 
-                let mut node = Node::from(row);
-                node.add_parent_id(GraphqlId::try_from(parent_id)?);
+        let mut batches = self.read_batches(db_name, queries, &idents)?;
+
+        // The batches are reversed with the rows of `last` like the parents
+        // within each batch.
+        if reverse {
+            batches.reverse();
+        }
+
+        let mut rows: Vec<PrismaRow> = batches.into_iter().flatten().collect();
+
+        let mut parent_ids = Vec::with_capacity(rows.len());
+
+        for row in rows.iter_mut() {
+            let parent_id = row.values.pop().ok_or(ConnectorError::ColumnDoesNotExist)?;
+
+            // Relation id is always the second last value. We don't need it
+            // here and we don't need it in the node.
+            let _ = row.values.pop();
 
-                Ok(node)
+            parent_ids.push(GraphqlId::try_from(parent_id)?);
+        }
+
+        self.type_registry.read_rows(selected_fields, &mut rows)?;
+        self.redactions.read_rows(selected_fields, &mut rows);
+
+        let nodes = rows
+            .into_iter()
+            .zip(parent_ids)
+            .map(|(row, parent_id)| {
+                let mut node = Node::from(row);
+                node.add_parent_id(parent_id);
+                node
             })
             .collect();
 
-        Ok(ManyNodes {
-            nodes: nodes?,
-            field_names,
-        })
+        Ok(ManyNodes { nodes, field_names })
     }
 
     fn count_by_model(&self, model: ModelRef, query_arguments: QueryArguments) -> ConnectorResult<usize> {
         let db_name = &model.schema().db_name;
-        let query = QueryBuilder::count_by_model(model, query_arguments);
+        self.check_policies(&PolicyRequest::new(
+            OperationKind::Count,
+            Some(Arc::clone(&model)),
+            query_arguments.filter.as_ref(),
+        ))?;
 
-        self.executor
-            .with_transaction(db_name, |conn| conn.find_int(query))
-            .map(|count| count as usize)
+        CursorCondition::check(&query_arguments)?;
+
+        let query_arguments = self.type_registry.write_query_arguments(query_arguments)?;
+
+        self.read_with_value_tables(db_name, query_arguments, |conn, query_arguments| {
+            conn.find_int(QueryBuilder::count_by_model(model, query_arguments))
+        })
+        .map(|count| count as usize)
     }
 
     fn count_by_table(&self, database: &str, table: &str) -> ConnectorResult<usize> {
         let query = QueryBuilder::count_by_table(database, table);
         self.executor
-            .with_transaction(database, |conn| conn.find_int(query))
+            .with_read_transaction(database, |conn| conn.find_int(query))
             .map(|count| count as usize)
     }
 
@@ -116,9 +227,9 @@ where
     ) -> ConnectorResult<Vec<ScalarListValues>> {
         let db_name = &list_field.model().schema().db_name;
         let type_identifier = list_field.type_identifier;
-        let query = QueryBuilder::get_scalar_list_values_by_node_ids(list_field, node_ids);
+        let query = QueryBuilder::get_scalar_list_values_by_node_ids(Arc::clone(&list_field), node_ids);
 
-        let results: Vec<ScalarListElement> = self.executor.with_transaction(db_name, |conn| {
+        let results: Vec<ScalarListElement> = self.executor.with_read_transaction(db_name, |conn| {
             let rows = conn.filter(query, &[TypeIdentifier::GraphQLID, type_identifier])?;
 
             rows.into_iter()
@@ -130,7 +241,9 @@ where
 
                     Ok(ScalarListElement {
                         node_id: GraphqlId::try_from(node_id)?,
-                        value: value,
+                        value: self
+                            .redactions
+                            .read_value(&list_field, self.type_registry.read_value(&list_field, value)?),
                     })
                 })
                 .collect()
@@ -149,3 +262,61 @@ where
         Ok(list_values)
     }
 }
+
+impl<T> SqlDatabase<T>
+where
+    T: Transactional + Sync,
+{
+    /// Executes the queries in their own read transactions, concurrently if
+    /// there are several. The rows of every query are returned in the order of
+    /// the queries.
+    fn read_batches(
+        &self,
+        db_name: &str,
+        mut queries: Vec<Select>,
+        idents: &[TypeIdentifier],
+    ) -> ConnectorResult<Vec<Vec<PrismaRow>>> {
+        if queries.len() == 1 {
+            // UNWRAP: Checked above.
+            let query = queries.pop().unwrap();
+            let rows = self
+                .executor
+                .with_read_transaction(db_name, |conn| conn.filter(query, idents))?;
+
+            return Ok(vec![rows]);
+        }
+
+        let executor = &self.executor;
+        let context = RequestContext::current();
+        let mut batches = Vec::with_capacity(queries.len());
+        let mut queries = queries.into_iter().peekable();
+
+        while queries.peek().is_some() {
+            let results: Vec<ConnectorResult<Vec<PrismaRow>>> = thread::scope(|scope| {
+                let handles: Vec<_> = queries
+                    .by_ref()
+                    .take(MAX_CONCURRENT_BATCHES)
+                    .map(|query| {
+                        let context = context.clone();
+
+                        scope.spawn(move |_| {
+                            context.run(|| executor.with_read_transaction(db_name, |conn| conn.filter(query, idents)))
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("Related records batch thread panicked"))
+                    .collect()
+            })
+            .expect("Related records batch thread panicked");
+
+            for result in results {
+                batches.push(result?);
+            }
+        }
+
+        Ok(batches)
+    }
+}
@@ -4,26 +4,61 @@ mod mutaction_executor;
 pub use data_resolver::*;
 pub use mutaction_executor::*;
 
-use crate::{query_builder::QueryBuilder, AliasedCondition, PrismaRow};
+use crate::{introspection::DatabaseSchema, query_builder::QueryBuilder, AliasedCondition, PrismaRow};
 use connector::{
     error::*,
     filter::{Filter, NodeSelector},
-    ConnectorResult,
+    Capabilities, ConnectorResult,
 };
 use prisma_models::*;
 use prisma_query::ast::*;
-use std::{convert::TryFrom, sync::Arc};
+use serde::Serialize;
+use std::{convert::TryFrom, sync::Arc, time::Duration};
 
 /// A `Transactional` presents a database able to spawn transactions, execute
 /// queries in the transaction and commit the results to the database or do a
 /// rollback in case of an error.
 pub trait Transactional {
+    /// The features of the database, detected when connecting.
+    fn capabilities(&self) -> Capabilities;
+
+    /// Checks that a connection to `db` can be taken from the pool within
+    /// `timeout` and a trivial query runs on it.
+    fn health_check(&self, db: &str, timeout: Duration) -> HealthStatus;
+
+    /// Reads the tables, columns, indexes and foreign keys currently in `db`.
+    fn introspect(&self, db: &str) -> ConnectorResult<DatabaseSchema>;
+
+    /// Drops the cached results of queries to `db`.
+    fn invalidate_caches(&self, db: &str);
+
     /// Wrap a closure into a transaction. All actions done through the
     /// `Transaction` are commited automatically, or rolled back in case of any
     /// error.
     fn with_transaction<F, T>(&self, db: &str, f: F) -> ConnectorResult<T>
     where
         F: FnOnce(&mut Transaction) -> ConnectorResult<T>;
+
+    /// A transaction for reading only, the closure can't write through it.
+    fn with_read_transaction<F, T>(&self, db: &str, f: F) -> ConnectorResult<T>
+    where
+        F: FnOnce(&mut ReadOperations) -> ConnectorResult<T>,
+    {
+        self.with_transaction(db, |conn| f(&mut ReadOnly(conn)))
+    }
+}
+
+/// The result of a health check, e.g. for the readiness probe of the server.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthStatus {
+    pub healthy: bool,
+    /// Time it took to take a connection and run the query.
+    pub duration_ms: u64,
+    pub error_code: Option<&'static str>,
+    pub error: Option<String>,
+    pub connections: u32,
+    pub idle_connections: u32,
 }
 
 pub struct WriteItems {
@@ -33,17 +68,20 @@ pub struct WriteItems {
 
 /// Abstraction of a database transaction. Start, commit and rollback should be
 /// handled per-database basis, `Transaction` providing a minimal interface over
-/// different databases.
-pub trait Transaction {
+/// different databases. Implemented for everything that can both read and
+/// write.
+pub trait Transaction: ReadOperations + WriteOperations {}
+
+impl<T> Transaction for T where T: ReadOperations + WriteOperations {}
+
+/// The writing half of a transaction.
+pub trait WriteOperations {
     /// Burn them. BURN THEM ALL!
     fn truncate(&mut self, project: ProjectRef) -> ConnectorResult<()>;
 
     /// Write to the database, returning the change count and last id inserted.
     fn write(&mut self, q: Query) -> ConnectorResult<WriteItems>;
 
-    /// Select multiple rows from the database.
-    fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> ConnectorResult<Vec<PrismaRow>>;
-
     /// Insert to the database. On success returns the last insert row id.
     fn insert(&mut self, q: Insert) -> ConnectorResult<usize> {
         Ok(self.write(q.into())?.last_id)
@@ -58,6 +96,17 @@ pub trait Transaction {
     fn delete(&mut self, q: Delete) -> ConnectorResult<usize> {
         Ok(self.write(q.into())?.count)
     }
+}
+
+/// The reading half of a transaction, e.g. for replicas or consumers that
+/// must not write.
+pub trait ReadOperations {
+    /// Select multiple rows from the database.
+    fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> ConnectorResult<Vec<PrismaRow>>;
+
+    /// The plan the database would use for the query, as text. The query
+    /// itself is not executed.
+    fn explain(&mut self, q: Query) -> ConnectorResult<String>;
 
     /// Find one full record selecting all scalar fields.
     fn find_record(&mut self, node_selector: &NodeSelector) -> ConnectorResult<SingleNode> {
@@ -192,3 +241,16 @@ pub trait Transaction {
         self.select_ids(select)
     }
 }
+
+/// Hides the writing half of a transaction.
+pub(crate) struct ReadOnly<'a>(pub(crate) &'a mut Transaction);
+
+impl<'a> ReadOperations for ReadOnly<'a> {
+    fn filter(&mut self, q: Select, idents: &[TypeIdentifier]) -> ConnectorResult<Vec<PrismaRow>> {
+        self.0.filter(q, idents)
+    }
+
+    fn explain(&mut self, q: Query) -> ConnectorResult<String> {
+        self.0.explain(q)
+    }
+}
@@ -16,7 +16,9 @@ pub fn execute<S>(
 where
     S: AsRef<str>,
 {
-    let (insert, returned_id) = MutationBuilder::create_node(Arc::clone(&model), non_list_args.clone());
+    super::check_writable(&model)?;
+
+    let (insert, returned_id) = MutationBuilder::create_node(Arc::clone(&model), non_list_args.clone())?;
     let last_id = conn.insert(insert)?;
 
     let id = match returned_id {
@@ -65,11 +67,13 @@ where
         prisma_args.insert(related_field.name.clone(), parent_id.clone());
 
         execute(conn, relation_field.related_model(), &prisma_args, list_args)
+            .map_err(|e| actions.relation_violation_for(e))
     } else {
         let id = execute(conn, relation_field.related_model(), non_list_args, list_args)?;
         let relation_query = MutationBuilder::create_relation(relation_field, parent_id, &id);
 
-        conn.write(relation_query)?;
+        conn.write(relation_query)
+            .map_err(|e| actions.relation_violation_for(e))?;
 
         Ok(id)
     }
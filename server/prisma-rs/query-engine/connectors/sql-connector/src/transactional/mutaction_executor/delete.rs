@@ -16,6 +16,8 @@ use std::sync::Arc;
 /// Will return the deleted record if the delete was successful.
 pub fn execute(conn: &mut Transaction, node_selector: &NodeSelector) -> ConnectorResult<SingleNode> {
     let model = node_selector.field.model();
+    super::check_writable(&model)?;
+
     let record = conn.find_record(node_selector)?;
     let id = record.get_id_value(Arc::clone(&model)).unwrap();
 
@@ -33,7 +35,7 @@ pub fn execute(conn: &mut Transaction, node_selector: &NodeSelector) -> Connecto
 
 /// A nested delete that removes one item related to the given `parent_id`.
 /// If no `RecordFinder` is given, will delete the first item from the
-/// table.
+/// table. Returns the id of the deleted record.
 ///
 /// Errors thrown from domain violations:
 ///
@@ -46,7 +48,9 @@ pub fn execute_nested(
     actions: &NestedActions,
     node_selector: &Option<NodeSelector>,
     relation_field: RelationFieldRef,
-) -> ConnectorResult<()> {
+) -> ConnectorResult<GraphqlId> {
+    super::check_writable(&relation_field.related_model())?;
+
     if let Some(ref node_selector) = node_selector {
         conn.find_id(node_selector)?;
     };
@@ -91,5 +95,5 @@ pub fn execute_nested(
         conn.delete(delete)?;
     }
 
-    Ok(())
+    Ok(child_id)
 }
@@ -9,37 +9,37 @@ use std::sync::Arc;
 /// A top level delete that removes records matching the `Filter`. Violating
 /// any relations will cause an error.
 ///
-/// Will return the number records deleted.
-pub fn execute(conn: &mut Transaction, model: ModelRef, filter: &Filter) -> ConnectorResult<usize> {
-    let ids = conn.filter_ids(Arc::clone(&model), filter.clone())?;
-    let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
-    let count = ids.len();
+/// Will return the ids of the deleted records.
+pub fn execute(conn: &mut Transaction, model: ModelRef, filter: &Filter) -> ConnectorResult<Vec<GraphqlId>> {
+    super::check_writable(&model)?;
+
+    let deleted_ids = conn.filter_ids(Arc::clone(&model), filter.clone())?;
+    let ids: Vec<&GraphqlId> = deleted_ids.iter().map(|id| &*id).collect();
 
     DeleteActions::check_relation_violations(Arc::clone(&model), ids.as_slice(), |select| {
         let ids = conn.select_ids(select)?;
         Ok(ids.into_iter().next())
     })?;
 
-    for delete in MutationBuilder::delete_many(model, ids.as_slice()) {
-        conn.delete(delete)?;
-    }
+    super::execute_batch(conn, MutationBuilder::delete_many(model, ids.as_slice()))?;
 
-    Ok(count)
+    Ok(deleted_ids)
 }
 
 /// Removes nested items matching to filter, or if no filter is given, all
 /// nested items related to the given `parent_id`. An error will be thrown
-/// if any deleted record is required in a model.
+/// if any deleted record is required in a model. Returns the ids of the
+/// deleted records.
 pub fn execute_nested(
     conn: &mut Transaction,
     parent_id: &GraphqlId,
     filter: &Option<Filter>,
     relation_field: RelationFieldRef,
-) -> ConnectorResult<usize> {
-    let ids = conn.filter_ids_by_parents(Arc::clone(&relation_field), vec![parent_id], filter.clone())?;
-    let count = ids.len();
+) -> ConnectorResult<Vec<GraphqlId>> {
+    super::check_writable(&relation_field.related_model())?;
 
-    let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
+    let deleted_ids = conn.filter_ids_by_parents(Arc::clone(&relation_field), vec![parent_id], filter.clone())?;
+    let ids: Vec<&GraphqlId> = deleted_ids.iter().map(|id| &*id).collect();
     let model = relation_field.model();
 
     DeleteActions::check_relation_violations(model, ids.as_slice(), |select| {
@@ -47,9 +47,8 @@ pub fn execute_nested(
         Ok(ids.into_iter().next())
     })?;
 
-    for delete in MutationBuilder::delete_many(relation_field.related_model(), ids.as_slice()) {
-        conn.delete(delete)?;
-    }
+    let deletes = MutationBuilder::delete_many(relation_field.related_model(), ids.as_slice());
+    super::execute_batch(conn, deletes)?;
 
-    Ok(count)
+    Ok(deleted_ids)
 }
@@ -6,11 +6,55 @@ mod relation;
 mod update;
 mod update_many;
 
-use crate::{database::SqlDatabase, Transaction, Transactional};
+use crate::{
+    database::SqlDatabase, ChangeEvent, ChangeOperation, OperationKind, PolicyRequest, Transaction, Transactional,
+};
 use connector::{error::ConnectorError, mutaction::*, ConnectorResult, DatabaseMutactionExecutor};
+use prisma_models::Model;
+use prisma_query::ast::Query;
 use serde_json::Value;
 use std::sync::Arc;
 
+/// Fails for models backed by views that can't be written to.
+fn check_writable(model: &Model) -> ConnectorResult<()> {
+    if model.is_read_only() {
+        Err(ConnectorError::ReadOnlyModel {
+            model: model.name.clone(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Executes the chunked statements of a write to many records, returning the
+/// number of changed rows. A failure after the first statement is returned
+/// as a `BatchFailure`, telling how far the batch got before the transaction
+/// was rolled back.
+fn execute_batch<Q>(conn: &mut Transaction, queries: Vec<Q>) -> ConnectorResult<usize>
+where
+    Q: Into<Query>,
+{
+    let total = queries.len();
+    let mut rows_written = 0;
+
+    for (index, query) in queries.into_iter().enumerate() {
+        match conn.write(query.into()) {
+            Ok(items) => rows_written += items.count,
+            Err(e) if index == 0 => return Err(e),
+            Err(e) => {
+                return Err(ConnectorError::BatchFailure {
+                    index,
+                    total,
+                    rows_written,
+                    cause: Box::new(e),
+                })
+            }
+        }
+    }
+
+    Ok(rows_written)
+}
+
 impl<T> DatabaseMutactionExecutor for SqlDatabase<T>
 where
     T: Transactional,
@@ -18,12 +62,38 @@ where
     fn execute(
         &self,
         db_name: String,
-        mutaction: TopLevelDatabaseMutaction,
+        mut mutaction: TopLevelDatabaseMutaction,
     ) -> ConnectorResult<DatabaseMutactionResult> {
-        self.executor.with_transaction(&db_name, |conn: &mut Transaction| {
-            fn create(conn: &mut Transaction, cn: &CreateNode) -> ConnectorResult<DatabaseMutactionResult> {
-                let parent_id = create::execute(conn, Arc::clone(&cn.model), &cn.non_list_args, &cn.list_args)?;
-                nested::execute(conn, &cn.nested_mutactions, &parent_id)?;
+        for request in PolicyRequest::for_mutaction(&mutaction) {
+            self.check_policies(&request)?;
+        }
+
+        self.type_registry.write_mutaction(&mut mutaction)?;
+
+        let mut changes = Vec::new();
+
+        let outbox = self.outbox();
+        let audit_log = self.audit_log();
+        let actor = connector::actor();
+
+        let result = self.executor.with_transaction(&db_name, |conn: &mut Transaction| {
+            fn create(
+                conn: &mut Transaction,
+                cn: &CreateNode,
+                changes: &mut Vec<ChangeEvent>,
+            ) -> ConnectorResult<DatabaseMutactionResult> {
+                let parent_id = create::execute(conn, Arc::clone(&cn.model), &cn.non_list_args, &cn.list_args)
+                    .map_err(|e| e.on_model(&cn.model))?;
+
+                changes.push(ChangeEvent::written(
+                    &cn.model,
+                    ChangeOperation::Create,
+                    parent_id.clone(),
+                    &cn.non_list_args,
+                    &cn.list_args,
+                ));
+
+                nested::execute(conn, &cn.nested_mutactions, &parent_id, changes)?;
 
                 Ok(DatabaseMutactionResult {
                     identifier: Identifier::Id(parent_id),
@@ -31,9 +101,23 @@ where
                 })
             }
 
-            fn update(conn: &mut Transaction, un: &UpdateNode) -> ConnectorResult<DatabaseMutactionResult> {
-                let parent_id = update::execute(conn, &un.where_, &un.non_list_args, &un.list_args)?;
-                nested::execute(conn, &un.nested_mutactions, &parent_id)?;
+            fn update(
+                conn: &mut Transaction,
+                un: &UpdateNode,
+                changes: &mut Vec<ChangeEvent>,
+            ) -> ConnectorResult<DatabaseMutactionResult> {
+                let parent_id = update::execute(conn, &un.where_, &un.non_list_args, &un.list_args)
+                    .map_err(|e| e.on_model(&un.where_.field.model()))?;
+
+                changes.push(ChangeEvent::written(
+                    &un.where_.field.model(),
+                    ChangeOperation::Update,
+                    parent_id.clone(),
+                    &un.non_list_args,
+                    &un.list_args,
+                ));
+
+                nested::execute(conn, &un.nested_mutactions, &parent_id, changes)?;
 
                 Ok(DatabaseMutactionResult {
                     identifier: Identifier::Id(parent_id),
@@ -41,22 +125,35 @@ where
                 })
             }
 
-            match mutaction {
-                TopLevelDatabaseMutaction::CreateNode(ref cn) => create(conn, cn),
-                TopLevelDatabaseMutaction::UpdateNode(ref un) => update(conn, un),
+            let result = match mutaction {
+                TopLevelDatabaseMutaction::CreateNode(ref cn) => create(conn, cn, &mut changes),
+                TopLevelDatabaseMutaction::UpdateNode(ref un) => update(conn, un, &mut changes),
                 TopLevelDatabaseMutaction::UpsertNode(ref ups) => match conn.find_id(&ups.where_) {
-                    Err(_e @ ConnectorError::NodeNotFoundForWhere { .. }) => create(conn, &ups.create),
+                    Err(_e @ ConnectorError::NodeNotFoundForWhere { .. }) => create(conn, &ups.create, &mut changes),
                     Err(e) => return Err(e),
-                    Ok(_) => update(conn, &ups.update),
+                    Ok(_) => update(conn, &ups.update, &mut changes),
                 },
                 TopLevelDatabaseMutaction::UpdateNodes(ref uns) => {
-                    let count = update_many::execute(
+                    let ids = update_many::execute(
                         conn,
                         Arc::clone(&uns.model),
                         &uns.filter,
                         &uns.non_list_args,
                         &uns.list_args,
-                    )?;
+                    )
+                    .map_err(|e| e.on_model(&uns.model))?;
+
+                    let count = ids.len();
+
+                    for id in ids {
+                        changes.push(ChangeEvent::written(
+                            &uns.model,
+                            ChangeOperation::Update,
+                            id,
+                            &uns.non_list_args,
+                            &uns.list_args,
+                        ));
+                    }
 
                     Ok(DatabaseMutactionResult {
                         identifier: Identifier::Count(count),
@@ -64,7 +161,16 @@ where
                     })
                 }
                 TopLevelDatabaseMutaction::DeleteNode(ref dn) => {
-                    let node = delete::execute(conn, &dn.where_)?;
+                    let model = dn.where_.field.model();
+                    let mut node = delete::execute(conn, &dn.where_).map_err(|e| e.on_model(&model))?;
+                    let id = node.get_id_value(Arc::clone(&model))?.clone();
+
+                    // Converted like the reads, the redactions see the values
+                    // of the query engine.
+                    self.type_registry.read_node(&model, &mut node)?;
+                    self.redactions.read_node(&model, &mut node);
+
+                    changes.push(ChangeEvent::deleted(&model, id));
 
                     Ok(DatabaseMutactionResult {
                         identifier: Identifier::Node(node),
@@ -72,7 +178,11 @@ where
                     })
                 }
                 TopLevelDatabaseMutaction::DeleteNodes(ref dns) => {
-                    let count = delete_many::execute(conn, Arc::clone(&dns.model), &dns.filter)?;
+                    let ids = delete_many::execute(conn, Arc::clone(&dns.model), &dns.filter)
+                        .map_err(|e| e.on_model(&dns.model))?;
+                    let count = ids.len();
+
+                    changes.extend(ids.into_iter().map(|id| ChangeEvent::deleted(&dns.model, id)));
 
                     Ok(DatabaseMutactionResult {
                         identifier: Identifier::Count(count),
@@ -87,11 +197,41 @@ where
                         typ: DatabaseMutactionResultType::Unit,
                     })
                 }
+            }?;
+
+            if let Some(outbox) = outbox {
+                outbox.write(conn, &db_name, &changes)?;
             }
-        })
+
+            if let Some(audit_log) = audit_log {
+                audit_log.write(conn, &db_name, &changes, actor.as_ref().map(String::as_str))?;
+            }
+
+            Ok(result)
+        })?;
+
+        // Resetting the data has no changes, but removes the cached records
+        // as well.
+        if let Some(cache) = connector::node_cache() {
+            cache.clear();
+        }
+
+        if !changes.is_empty() {
+            for sink in self.change_sinks() {
+                sink.publish(&changes);
+            }
+
+            if let Some(audit_log) = audit_log {
+                audit_log.record(&changes, actor.as_ref().map(String::as_str));
+            }
+        }
+
+        Ok(result)
     }
 
     fn execute_raw(&self, _query: String) -> ConnectorResult<Value> {
+        self.check_policies(&PolicyRequest::new(OperationKind::Raw, None, None))?;
+
         Ok(Value::String("hello world!".to_string()))
     }
 }
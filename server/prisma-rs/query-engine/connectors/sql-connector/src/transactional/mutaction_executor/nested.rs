@@ -1,11 +1,23 @@
 use super::{create, delete, delete_many, relation, update, update_many};
-use crate::Transaction;
+use crate::{ChangeEvent, ChangeOperation, Transaction};
 use connector::{error::ConnectorError, mutaction::*, ConnectorResult};
 use prisma_models::GraphqlId;
 use std::sync::Arc;
 
-pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id: &GraphqlId) -> ConnectorResult<()> {
-    fn create(conn: &mut Transaction, parent_id: &GraphqlId, cn: &NestedCreateNode) -> ConnectorResult<()> {
+/// Executes the nested mutactions of the record `parent_id`, adding the
+/// records written to `changes`.
+pub fn execute(
+    conn: &mut Transaction,
+    mutactions: &NestedMutactions,
+    parent_id: &GraphqlId,
+    changes: &mut Vec<ChangeEvent>,
+) -> ConnectorResult<()> {
+    fn create(
+        conn: &mut Transaction,
+        parent_id: &GraphqlId,
+        cn: &NestedCreateNode,
+        changes: &mut Vec<ChangeEvent>,
+    ) -> ConnectorResult<()> {
         let parent_id = create::execute_nested(
             conn,
             parent_id,
@@ -15,12 +27,25 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             &cn.list_args,
         )?;
 
-        execute(conn, &cn.nested_mutactions, &parent_id)?;
+        changes.push(ChangeEvent::written(
+            &cn.relation_field.related_model(),
+            ChangeOperation::Create,
+            parent_id.clone(),
+            &cn.non_list_args,
+            &cn.list_args,
+        ));
+
+        execute(conn, &cn.nested_mutactions, &parent_id, changes)?;
 
         Ok(())
     }
 
-    fn update(conn: &mut Transaction, parent_id: &GraphqlId, un: &NestedUpdateNode) -> ConnectorResult<()> {
+    fn update(
+        conn: &mut Transaction,
+        parent_id: &GraphqlId,
+        un: &NestedUpdateNode,
+        changes: &mut Vec<ChangeEvent>,
+    ) -> ConnectorResult<()> {
         let parent_id = update::execute_nested(
             conn,
             parent_id,
@@ -30,37 +55,49 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             &un.list_args,
         )?;
 
-        execute(conn, &un.nested_mutactions, &parent_id)?;
+        changes.push(ChangeEvent::written(
+            &un.relation_field.related_model(),
+            ChangeOperation::Update,
+            parent_id.clone(),
+            &un.non_list_args,
+            &un.list_args,
+        ));
+
+        execute(conn, &un.nested_mutactions, &parent_id, changes)?;
 
         Ok(())
     }
 
     for create_node in mutactions.creates.iter() {
-        create(conn, parent_id, create_node)?;
+        create(conn, parent_id, create_node, changes)?;
     }
 
     for update_node in mutactions.updates.iter() {
-        update(conn, parent_id, update_node)?;
+        update(conn, parent_id, update_node, changes)?;
     }
 
     for upsert_node in mutactions.upserts.iter() {
         let id_opt = conn.find_id_by_parent(Arc::clone(&upsert_node.relation_field), parent_id, &upsert_node.where_);
 
         match id_opt {
-            Ok(_) => update(conn, parent_id, &upsert_node.update)?,
-            Err(_e @ ConnectorError::NodesNotConnected { .. }) => create(conn, parent_id, &upsert_node.create)?,
+            Ok(_) => update(conn, parent_id, &upsert_node.update, changes)?,
+            Err(_e @ ConnectorError::NodesNotConnected { .. }) => {
+                create(conn, parent_id, &upsert_node.create, changes)?
+            }
             Err(e) => return Err(e),
         }
     }
 
     for delete_node in mutactions.deletes.iter() {
-        delete::execute_nested(
+        let id = delete::execute_nested(
             conn,
             parent_id,
             delete_node,
             &delete_node.where_,
             Arc::clone(&delete_node.relation_field),
         )?;
+
+        changes.push(ChangeEvent::deleted(&delete_node.relation_field.related_model(), id));
     }
 
     for connect in mutactions.connects.iter() {
@@ -82,7 +119,7 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
     }
 
     for update_many in mutactions.update_manys.iter() {
-        update_many::execute_nested(
+        let ids = update_many::execute_nested(
             conn,
             &parent_id,
             &update_many.filter,
@@ -90,15 +127,30 @@ pub fn execute(conn: &mut Transaction, mutactions: &NestedMutactions, parent_id:
             &update_many.non_list_args,
             &update_many.list_args,
         )?;
+
+        let model = update_many.relation_field.related_model();
+
+        for id in ids {
+            changes.push(ChangeEvent::written(
+                &model,
+                ChangeOperation::Update,
+                id,
+                &update_many.non_list_args,
+                &update_many.list_args,
+            ));
+        }
     }
 
     for delete_many in mutactions.delete_manys.iter() {
-        delete_many::execute_nested(
+        let ids = delete_many::execute_nested(
             conn,
             &parent_id,
             &delete_many.filter,
             Arc::clone(&delete_many.relation_field),
         )?;
+
+        let model = delete_many.relation_field.related_model();
+        changes.extend(ids.into_iter().map(|id| ChangeEvent::deleted(&model, id)));
     }
 
     Ok(())
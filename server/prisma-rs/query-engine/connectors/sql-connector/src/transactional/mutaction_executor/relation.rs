@@ -48,7 +48,8 @@ pub fn connect(
     }
 
     let relation_query = MutationBuilder::create_relation(relation_field, parent_id, &child_id);
-    conn.write(relation_query)?;
+    conn.write(relation_query)
+        .map_err(|e| actions.relation_violation_for(e))?;
 
     Ok(())
 }
@@ -122,7 +123,8 @@ pub fn set(
         }
 
         let relation_query = MutationBuilder::create_relation(Arc::clone(&relation_field), parent_id, &child_id);
-        conn.write(relation_query)?;
+        conn.write(relation_query)
+            .map_err(|e| actions.relation_violation_for(e))?;
     }
 
     Ok(())
@@ -14,6 +14,8 @@ where
     S: AsRef<str>,
 {
     let model = node_selector.field.model();
+    super::check_writable(&model)?;
+
     let id = conn.find_id(node_selector)?;
 
     if let Some(update) = MutationBuilder::update_one(Arc::clone(&model), &id, non_list_args)? {
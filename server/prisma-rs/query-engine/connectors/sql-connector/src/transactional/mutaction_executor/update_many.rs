@@ -7,36 +7,36 @@ use std::sync::Arc;
 /// Updates every record and any associated list records in the database
 /// matching the `Filter`.
 ///
-/// Returns the number of updated items, if successful.
+/// Returns the ids of the updated records, if successful.
 pub fn execute<S>(
     conn: &mut Transaction,
     model: ModelRef,
     filter: &Filter,
     non_list_args: &PrismaArgs,
     list_args: &[(S, PrismaListValue)],
-) -> ConnectorResult<usize>
+) -> ConnectorResult<Vec<GraphqlId>>
 where
     S: AsRef<str>,
 {
+    super::check_writable(&model)?;
+
     let ids = conn.filter_ids(Arc::clone(&model), filter.clone())?;
-    let count = ids.len();
 
     let updates = {
         let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
         MutationBuilder::update_many(Arc::clone(&model), ids.as_slice(), non_list_args)?
     };
 
-    for update in updates {
-        conn.update(update)?;
-    }
+    super::execute_batch(conn, updates)?;
 
     update::update_list_args(conn, ids.as_slice(), Arc::clone(&model), list_args)?;
 
-    Ok(count)
+    Ok(ids)
 }
 
 /// Updates nested items matching to filter, or if no filter is given, all
-/// nested items related to the given `parent_id`.
+/// nested items related to the given `parent_id`, returning the ids of the
+/// updated records.
 pub fn execute_nested<S>(
     conn: &mut Transaction,
     parent_id: &GraphqlId,
@@ -44,23 +44,22 @@ pub fn execute_nested<S>(
     relation_field: RelationFieldRef,
     non_list_args: &PrismaArgs,
     list_args: &[(S, PrismaListValue)],
-) -> ConnectorResult<usize>
+) -> ConnectorResult<Vec<GraphqlId>>
 where
     S: AsRef<str>,
 {
+    super::check_writable(&relation_field.related_model())?;
+
     let ids = conn.filter_ids_by_parents(Arc::clone(&relation_field), vec![parent_id], filter.clone())?;
-    let count = ids.len();
 
     let updates = {
         let ids: Vec<&GraphqlId> = ids.iter().map(|id| &*id).collect();
         MutationBuilder::update_many(relation_field.related_model(), ids.as_slice(), non_list_args)?
     };
 
-    for update in updates {
-        conn.update(update)?;
-    }
+    super::execute_batch(conn, updates)?;
 
     update::update_list_args(conn, ids.as_slice(), relation_field.model(), list_args)?;
 
-    Ok(count)
+    Ok(ids)
 }
@@ -0,0 +1,439 @@
+use crate::PrismaRow;
+use connector::{error::ConnectorError, filter::*, mutaction::*, ConnectorResult, QueryArguments};
+use prisma_models::prelude::*;
+use std::{collections::HashMap, mem, sync::Arc};
+use uuid::Uuid;
+
+/// Marks a UUID parameter to be bound as 16 bytes. prisma-query has no blob
+/// parameters, so the value is passed as text starting with this prefix.
+const BINARY_UUID_PREFIX: &str = "\u{0}binary-uuid:";
+
+/// A conversion between a native column type the connector doesn't know
+/// about, such as `ltree` or `tsvector`, and a `PrismaValue`.
+///
+/// The values passed to the mapping are first converted using the
+/// `TypeIdentifier` of the field, e.g. a `String` field in an `ltree` column
+/// gets the text representation. `Null` values are never passed to the
+/// mapping.
+pub trait TypeMapping: Send + Sync {
+    /// Conversion of a value read from the database to a value for the query
+    /// engine.
+    fn from_database(&self, value: PrismaValue) -> ConnectorResult<PrismaValue>;
+
+    /// Conversion of a value from the query engine to a value bound as a
+    /// parameter.
+    fn to_database(&self, value: PrismaValue) -> ConnectorResult<PrismaValue>;
+
+    /// False if filtering by the condition gives wrong results on the
+    /// converted values, failing such filters with `UnsupportedCondition`.
+    fn supports_condition(&self, _condition: &ScalarCondition) -> bool {
+        true
+    }
+}
+
+/// Registered `TypeMapping`s, keyed by the `native_type` of the field in the
+/// data model, or by the names of the model and the field.
+#[derive(Default, Clone)]
+pub struct TypeRegistry {
+    mappings: HashMap<String, Arc<TypeMapping>>,
+    field_mappings: HashMap<(String, String), Arc<TypeMapping>>,
+}
+
+impl TypeRegistry {
+    /// Use the `mapping` for all fields with the given `native_type`.
+    pub fn register<S, M>(&mut self, native_type: S, mapping: M)
+    where
+        S: Into<String>,
+        M: TypeMapping + 'static,
+    {
+        self.mappings.insert(native_type.into(), Arc::new(mapping));
+    }
+
+    /// Use the `mapping` for the field of the model, regardless of its
+    /// `native_type`, e.g. `Encrypted` for fields with personal data.
+    pub fn register_field<M, F, T>(&mut self, model: M, field: F, mapping: T)
+    where
+        M: Into<String>,
+        F: Into<String>,
+        T: TypeMapping + 'static,
+    {
+        self.field_mappings
+            .insert((model.into(), field.into()), Arc::new(mapping));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty() && self.field_mappings.is_empty()
+    }
+
+    /// The mapping to be used with the field, if registered. Mappings of the
+    /// field go before the mappings of its `native_type`.
+    pub fn find(&self, field: &ScalarField) -> Option<&TypeMapping> {
+        let for_field = || {
+            self.field_mappings
+                .get(&(field.model().name.clone(), field.name.clone()))
+        };
+
+        let for_native_type = || {
+            field
+                .native_type
+                .as_ref()
+                .and_then(|native_type| self.mappings.get(native_type))
+        };
+
+        for_field()
+            .or_else(for_native_type)
+            .map(|mapping| &**mapping)
+            .or_else(|| match field.uuid_storage() {
+                Some(UuidStorage::Binary) => Some(&BinaryUuid as &TypeMapping),
+                _ => None,
+            })
+    }
+
+    /// Converts the scalar values of rows selected with `selected_fields`.
+    /// The mappings are looked up once for all rows.
+    pub fn read_rows(&self, selected_fields: &SelectedFields, rows: &mut [PrismaRow]) -> ConnectorResult<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let mappings: Vec<Option<&TypeMapping>> = selected_fields
+            .scalar_non_list()
+            .iter()
+            .map(|field| self.find(field))
+            .collect();
+
+        if mappings.iter().all(Option::is_none) {
+            return Ok(());
+        }
+
+        for row in rows.iter_mut() {
+            for (value, mapping) in row.values.iter_mut().zip(mappings.iter()) {
+                if let Some(mapping) = mapping {
+                    Self::convert(value, |v| mapping.from_database(v))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts the scalar values of a single record, e.g. a deleted one.
+    pub fn read_node(&self, model: &Model, node: &mut SingleNode) -> ConnectorResult<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let fields = model.fields();
+
+        for (name, value) in node.field_names.iter().zip(node.node.values.iter_mut()) {
+            let mapping = fields.find_from_scalar(name).ok().and_then(|field| self.find(&field));
+
+            if let Some(mapping) = mapping {
+                Self::convert(value, |v| mapping.from_database(v))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts a single value read from the given field.
+    pub fn read_value(&self, field: &ScalarField, value: PrismaValue) -> ConnectorResult<PrismaValue> {
+        match self.find(field) {
+            Some(mapping) if !value.is_null() => mapping.from_database(value),
+            _ => Ok(value),
+        }
+    }
+
+    /// Converts all values in the filters of the arguments.
+    pub fn write_query_arguments(&self, mut query_arguments: QueryArguments) -> ConnectorResult<QueryArguments> {
+        if let Some(ref mut filter) = query_arguments.filter {
+            self.write_filter(filter)?;
+        }
+
+        Ok(query_arguments)
+    }
+
+    /// Converts the value of the selector.
+    pub fn write_node_selector(&self, node_selector: &mut NodeSelector) -> ConnectorResult<()> {
+        if let Some(mapping) = self.find(&node_selector.field) {
+            Self::convert(&mut node_selector.value, |v| mapping.to_database(v))?;
+        }
+
+        Ok(())
+    }
+
+    /// Converts the values of all fields of the selector.
+    pub fn write_record_selector(&self, record_selector: &mut RecordSelector) -> ConnectorResult<()> {
+        match record_selector {
+            RecordSelector::Field(node_selector) => self.write_node_selector(node_selector),
+            RecordSelector::Compound(node_selectors) => node_selectors
+                .iter_mut()
+                .try_for_each(|node_selector| self.write_node_selector(node_selector)),
+        }
+    }
+
+    /// Converts all scalar values in the filter tree.
+    pub fn write_filter(&self, filter: &mut Filter) -> ConnectorResult<()> {
+        match filter {
+            Filter::And(ref mut filters) | Filter::Or(ref mut filters) | Filter::Not(ref mut filters) => {
+                for filter in filters.iter_mut() {
+                    self.write_filter(filter)?;
+                }
+            }
+            Filter::Scalar(ref mut sf) => {
+                if let Some(mapping) = self.find(&sf.field) {
+                    if !mapping.supports_condition(&sf.condition) {
+                        return Err(ConnectorError::UnsupportedCondition {
+                            model: sf.field.model().name.clone(),
+                            field: sf.field.name.clone(),
+                        });
+                    }
+
+                    let to_database = |v| mapping.to_database(v);
+
+                    match sf.condition {
+                        ScalarCondition::Equals(ref mut v)
+                        | ScalarCondition::NotEquals(ref mut v)
+                        | ScalarCondition::Contains(ref mut v)
+                        | ScalarCondition::NotContains(ref mut v)
+                        | ScalarCondition::StartsWith(ref mut v)
+                        | ScalarCondition::NotStartsWith(ref mut v)
+                        | ScalarCondition::EndsWith(ref mut v)
+                        | ScalarCondition::NotEndsWith(ref mut v)
+                        | ScalarCondition::Pattern(ref mut v)
+                        | ScalarCondition::NotPattern(ref mut v)
+                        | ScalarCondition::LessThan(ref mut v)
+                        | ScalarCondition::LessThanOrEquals(ref mut v)
+                        | ScalarCondition::GreaterThan(ref mut v)
+                        | ScalarCondition::GreaterThanOrEquals(ref mut v) => Self::convert(v, to_database)?,
+                        ScalarCondition::In(Some(ref mut values)) | ScalarCondition::NotIn(Some(ref mut values)) => {
+                            for v in values.iter_mut() {
+                                Self::convert(v, to_database)?;
+                            }
+                        }
+                        ScalarCondition::In(None)
+                        | ScalarCondition::NotIn(None)
+                        | ScalarCondition::InTemporaryTable(_)
+                        | ScalarCondition::NotInTemporaryTable(_) => (),
+                    }
+                }
+            }
+            Filter::Relation(ref mut rf) => self.write_filter(&mut rf.nested_filter)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Converts all values written or used for finding records in the
+    /// mutaction, including the nested mutactions.
+    pub fn write_mutaction(&self, mutaction: &mut TopLevelDatabaseMutaction) -> ConnectorResult<()> {
+        match mutaction {
+            TopLevelDatabaseMutaction::CreateNode(ref mut cn) => self.write_create(cn),
+            TopLevelDatabaseMutaction::UpdateNode(ref mut un) => self.write_update(un),
+            TopLevelDatabaseMutaction::UpsertNode(ref mut ups) => {
+                self.write_node_selector(&mut ups.where_)?;
+                self.write_create(&mut ups.create)?;
+                self.write_update(&mut ups.update)
+            }
+            TopLevelDatabaseMutaction::UpdateNodes(ref mut uns) => {
+                self.write_filter(&mut uns.filter)?;
+                self.write_args(&uns.model, &mut uns.non_list_args, &mut uns.list_args)
+            }
+            TopLevelDatabaseMutaction::DeleteNode(ref mut dn) => self.write_node_selector(&mut dn.where_),
+            TopLevelDatabaseMutaction::DeleteNodes(ref mut dns) => self.write_filter(&mut dns.filter),
+            TopLevelDatabaseMutaction::ResetData(_) => Ok(()),
+        }
+    }
+
+    fn write_create(&self, cn: &mut CreateNode) -> ConnectorResult<()> {
+        self.write_args(&cn.model, &mut cn.non_list_args, &mut cn.list_args)?;
+        self.write_nested(&mut cn.nested_mutactions)
+    }
+
+    fn write_update(&self, un: &mut UpdateNode) -> ConnectorResult<()> {
+        let model = un.where_.field.model();
+
+        self.write_node_selector(&mut un.where_)?;
+        self.write_args(&model, &mut un.non_list_args, &mut un.list_args)?;
+        self.write_nested(&mut un.nested_mutactions)
+    }
+
+    fn write_nested_create(&self, cn: &mut NestedCreateNode) -> ConnectorResult<()> {
+        let model = cn.relation_field.related_model();
+
+        self.write_args(&model, &mut cn.non_list_args, &mut cn.list_args)?;
+        self.write_nested(&mut cn.nested_mutactions)
+    }
+
+    fn write_nested_update(&self, un: &mut NestedUpdateNode) -> ConnectorResult<()> {
+        let model = un.relation_field.related_model();
+
+        self.write_opt_node_selector(&mut un.where_)?;
+        self.write_args(&model, &mut un.non_list_args, &mut un.list_args)?;
+        self.write_nested(&mut un.nested_mutactions)
+    }
+
+    fn write_nested(&self, nested: &mut NestedMutactions) -> ConnectorResult<()> {
+        for cn in nested.creates.iter_mut() {
+            self.write_nested_create(cn)?;
+        }
+
+        for un in nested.updates.iter_mut() {
+            self.write_nested_update(un)?;
+        }
+
+        for ups in nested.upserts.iter_mut() {
+            self.write_opt_node_selector(&mut ups.where_)?;
+            self.write_nested_create(&mut ups.create)?;
+            self.write_nested_update(&mut ups.update)?;
+        }
+
+        for dn in nested.deletes.iter_mut() {
+            self.write_opt_node_selector(&mut dn.where_)?;
+        }
+
+        for connect in nested.connects.iter_mut() {
+            self.write_node_selector(&mut connect.where_)?;
+        }
+
+        for disconnect in nested.disconnects.iter_mut() {
+            self.write_opt_node_selector(&mut disconnect.where_)?;
+        }
+
+        for set in nested.sets.iter_mut() {
+            for selector in set.wheres.iter_mut() {
+                self.write_node_selector(selector)?;
+            }
+        }
+
+        for uns in nested.update_manys.iter_mut() {
+            let model = uns.relation_field.related_model();
+
+            if let Some(ref mut filter) = uns.filter {
+                self.write_filter(filter)?;
+            }
+
+            self.write_args(&model, &mut uns.non_list_args, &mut uns.list_args)?;
+        }
+
+        for dns in nested.delete_manys.iter_mut() {
+            if let Some(ref mut filter) = dns.filter {
+                self.write_filter(filter)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_opt_node_selector(&self, node_selector: &mut Option<NodeSelector>) -> ConnectorResult<()> {
+        match node_selector {
+            Some(ref mut selector) => self.write_node_selector(selector),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn write_args(
+        &self,
+        model: &Model,
+        non_list_args: &mut PrismaArgs,
+        list_args: &mut Vec<(String, PrismaListValue)>,
+    ) -> ConnectorResult<()> {
+        for (name, value) in non_list_args.args.iter_mut() {
+            if let Some(mapping) = model.fields().find_from_scalar(name).ok().and_then(|f| self.find(&f)) {
+                Self::convert(value, |v| mapping.to_database(v))?;
+            }
+        }
+
+        for (name, list_value) in list_args.iter_mut() {
+            let field = model.fields().find_from_scalar(name).ok();
+
+            if let (Some(mapping), Some(ref mut values)) = (field.and_then(|f| self.find(&f)), list_value) {
+                for value in values.iter_mut() {
+                    Self::convert(value, |v| mapping.to_database(v))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn convert<F>(value: &mut PrismaValue, f: F) -> ConnectorResult<()>
+    where
+        F: Fn(PrismaValue) -> ConnectorResult<PrismaValue>,
+    {
+        if !value.is_null() {
+            let original = mem::replace(value, PrismaValue::Null);
+            *value = f(original)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The mapping of `UUID` fields with `UuidStorage::Binary` without a
+/// registered mapping. The values are bound as blobs by the connector, see
+/// `binary_uuid`. Reading a blob into a UUID needs no mapping.
+struct BinaryUuid;
+
+impl TypeMapping for BinaryUuid {
+    fn from_database(&self, value: PrismaValue) -> ConnectorResult<PrismaValue> {
+        Ok(value)
+    }
+
+    fn to_database(&self, value: PrismaValue) -> ConnectorResult<PrismaValue> {
+        match value {
+            PrismaValue::Uuid(uuid) | PrismaValue::GraphqlId(GraphqlId::UUID(uuid)) => {
+                Ok(PrismaValue::String(format!("{}{}", BINARY_UUID_PREFIX, uuid)))
+            }
+            value => Ok(value),
+        }
+    }
+
+    /// Blobs have no text to match a pattern against.
+    fn supports_condition(&self, condition: &ScalarCondition) -> bool {
+        match condition {
+            ScalarCondition::Contains(_)
+            | ScalarCondition::NotContains(_)
+            | ScalarCondition::StartsWith(_)
+            | ScalarCondition::NotStartsWith(_)
+            | ScalarCondition::EndsWith(_)
+            | ScalarCondition::NotEndsWith(_)
+            | ScalarCondition::Pattern(_)
+            | ScalarCondition::NotPattern(_) => false,
+            _ => true,
+        }
+    }
+}
+
+/// The 16 bytes to bind for a text parameter written by `BinaryUuid`.
+pub(crate) fn binary_uuid(param: &str) -> Option<Vec<u8>> {
+    if param.starts_with(BINARY_UUID_PREFIX) {
+        Uuid::parse_str(&param[BINARY_UUID_PREFIX.len()..])
+            .ok()
+            .map(|uuid| uuid.as_bytes().to_vec())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_uuids_are_bound_as_bytes() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+        match BinaryUuid.to_database(PrismaValue::Uuid(uuid)).unwrap() {
+            PrismaValue::String(param) => assert_eq!(Some(uuid.as_bytes().to_vec()), binary_uuid(&param)),
+            value => panic!("Expected a text parameter, got {:?}", value),
+        }
+    }
+
+    #[test]
+    fn other_text_is_bound_as_is() {
+        assert_eq!(None, binary_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert_eq!(None, binary_uuid("\u{0}binary-uuid:nope"));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    database::SqlDatabase, quote_identifier, transactional::ReadOnly, ReadOperations, Transaction, Transactional,
+};
+use connector::{filter::*, ConnectorResult, QueryArguments};
+use prisma_models::PrismaValue;
+use prisma_query::ast::*;
+
+/// `In` lists with more values than this are read from a temporary table.
+/// Every value of a list is a parameter of the statement, so long lists take
+/// long to parse and can exceed the parameter limit of the database.
+pub const MAX_IN_LIST_LENGTH: usize = 1000;
+
+/// The temporary tables holding the values of the long `In` lists of a
+/// filter. Temporary tables stay on the connection after a commit, so they
+/// are dropped again in the same transaction, a rollback removes them as
+/// well.
+pub struct ValueTables {
+    names: Vec<String>,
+}
+
+impl ValueTables {
+    /// True if the filter has a list to move into a table.
+    pub fn needed(filter: &Filter) -> bool {
+        match filter {
+            Filter::And(filters) | Filter::Or(filters) | Filter::Not(filters) => {
+                filters.iter().any(|filter| Self::needed(filter))
+            }
+            Filter::Scalar(ScalarFilter {
+                condition: ScalarCondition::In(Some(values)),
+                ..
+            })
+            | Filter::Scalar(ScalarFilter {
+                condition: ScalarCondition::NotIn(Some(values)),
+                ..
+            }) => values.len() > MAX_IN_LIST_LENGTH,
+            Filter::Relation(filter) => Self::needed(&filter.nested_filter),
+            _ => false,
+        }
+    }
+
+    /// Writes the long lists of the filter into temporary tables, replacing
+    /// their conditions with ones reading the tables.
+    pub fn create(conn: &mut Transaction, filter: &mut Filter) -> ConnectorResult<Self> {
+        let mut tables = Self { names: Vec::new() };
+        tables.replace_lists(conn, filter)?;
+
+        Ok(tables)
+    }
+
+    /// Drops the tables, before the transaction commits.
+    pub fn drop_tables(self, conn: &mut Transaction) -> ConnectorResult<()> {
+        for name in self.names {
+            let drop = format!("DROP TABLE \"temp\".{}", quote_identifier(&name));
+            conn.write(Query::from(drop.as_str()))?;
+        }
+
+        Ok(())
+    }
+
+    fn replace_lists(&mut self, conn: &mut Transaction, filter: &mut Filter) -> ConnectorResult<()> {
+        match filter {
+            Filter::And(filters) | Filter::Or(filters) | Filter::Not(filters) => {
+                for filter in filters.iter_mut() {
+                    self.replace_lists(conn, filter)?;
+                }
+            }
+            Filter::Scalar(sf) => {
+                let condition = match sf.condition {
+                    ScalarCondition::In(Some(ref values)) if values.len() > MAX_IN_LIST_LENGTH => {
+                        ScalarCondition::InTemporaryTable(self.create_table(conn, values)?)
+                    }
+                    ScalarCondition::NotIn(Some(ref values)) if values.len() > MAX_IN_LIST_LENGTH => {
+                        ScalarCondition::NotInTemporaryTable(self.create_table(conn, values)?)
+                    }
+                    _ => return Ok(()),
+                };
+
+                sf.condition = condition;
+            }
+            Filter::Relation(rf) => self.replace_lists(conn, &mut rf.nested_filter)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn create_table(&mut self, conn: &mut Transaction, values: &[PrismaValue]) -> ConnectorResult<String> {
+        let name = format!("_prisma_values_{}", self.names.len());
+
+        let create = format!("CREATE TEMP TABLE {} (\"value\")", quote_identifier(&name));
+        conn.write(Query::from(create.as_str()))?;
+        self.names.push(name.clone());
+
+        for value in values {
+            let insert = Insert::single_into(Table::from(("temp", name.as_str()))).value("value", value.clone());
+            conn.insert(insert)?;
+        }
+
+        Ok(name)
+    }
+}
+
+impl<T> SqlDatabase<T>
+where
+    T: Transactional,
+{
+    /// Reads in a transaction with the long `In` lists of the filter moved
+    /// into `ValueTables`. Only these reads need a transaction able to
+    /// write, all others get a read transaction.
+    pub(crate) fn read_with_value_tables<F, R>(
+        &self,
+        db_name: &str,
+        mut query_arguments: QueryArguments,
+        f: F,
+    ) -> ConnectorResult<R>
+    where
+        F: FnOnce(&mut ReadOperations, QueryArguments) -> ConnectorResult<R>,
+    {
+        let needed = query_arguments
+            .filter
+            .as_ref()
+            .map(ValueTables::needed)
+            .unwrap_or(false);
+
+        if !needed {
+            return self
+                .executor
+                .with_read_transaction(db_name, |conn| f(conn, query_arguments));
+        }
+
+        self.executor.with_transaction(db_name, |conn| {
+            // UNWRAP: Only filters need tables.
+            let tables = ValueTables::create(conn, query_arguments.filter.as_mut().unwrap())?;
+            let result = f(&mut ReadOnly(conn), query_arguments)?;
+
+            tables.drop_tables(conn)?;
+
+            Ok(result)
+        })
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{CoreError, CoreResult};
-use connector::{filter::Filter, RelationCompare, ScalarCompare};
+use connector::{
+    filter::{CountCondition, Filter},
+    RelationCompare, ScalarCompare,
+};
 use graphql_parser::query::Value;
 use prisma_models::{Field, ModelRef, PrismaListValue, PrismaValue};
 use std::{collections::BTreeMap, convert::TryFrom, sync::Arc};
@@ -19,9 +22,16 @@ enum FilterOp {
     NotStartsWith,
     EndsWith,
     NotEndsWith,
+    Pattern,
+    NotPattern,
     Some,
     None,
     Every,
+    CountLt,
+    CountLte,
+    CountGt,
+    CountGte,
+    Count,
     NestedAnd,
     NestedOr,
     NestedNot,
@@ -44,9 +54,16 @@ impl From<&FilterOp> for &'static str {
             FilterOp::NotStartsWith => "_not_starts_with",
             FilterOp::EndsWith => "_ends_with",
             FilterOp::NotEndsWith => "_not_ends_with",
+            FilterOp::Pattern => "_pattern",
+            FilterOp::NotPattern => "_not_pattern",
             FilterOp::Some => "_some",
             FilterOp::None => "_none",
             FilterOp::Every => "_every",
+            FilterOp::CountLt => "_count_lt",
+            FilterOp::CountLte => "_count_lte",
+            FilterOp::CountGt => "_count_gt",
+            FilterOp::CountGte => "_count_gte",
+            FilterOp::Count => "_count",
             FilterOp::NestedAnd => "AND",
             FilterOp::NestedOr => "OR",
             FilterOp::NestedNot => "NOT",
@@ -55,12 +72,27 @@ impl From<&FilterOp> for &'static str {
     }
 }
 
+impl FilterOp {
+    fn is_count(&self) -> bool {
+        match self {
+            FilterOp::CountLt | FilterOp::CountLte | FilterOp::CountGt | FilterOp::CountGte | FilterOp::Count => true,
+            _ => false,
+        }
+    }
+}
+
 pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreResult<Filter> {
     let ops = vec![
+        FilterOp::CountLt,
+        FilterOp::CountLte,
+        FilterOp::CountGt,
+        FilterOp::CountGte,
+        FilterOp::Count,
         FilterOp::NotIn,
         FilterOp::NotContains,
         FilterOp::NotStartsWith,
         FilterOp::NotEndsWith,
+        FilterOp::NotPattern,
         FilterOp::In,
         FilterOp::Not,
         FilterOp::Lt,
@@ -70,6 +102,7 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
         FilterOp::Contains,
         FilterOp::StartsWith,
         FilterOp::EndsWith,
+        FilterOp::Pattern,
         FilterOp::Some,
         FilterOp::None,
         FilterOp::Every,
@@ -84,7 +117,14 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
         .map(|(k, v): (&String, &Value)| {
             let op = ops.iter().find(|op| {
                 let op_name: &'static str = (*op).into();
+
+                // Scalar fields can end with `_count` too, e.g. `view_count_gt`.
                 k.as_str().ends_with(op_name)
+                    && (!op.is_count()
+                        || model
+                            .fields()
+                            .find_from_relation_fields(k.trim_end_matches(op_name))
+                            .is_ok())
             });
 
             let op = match op {
@@ -140,10 +180,46 @@ pub fn extract_filter(map: &BTreeMap<String, Value>, model: ModelRef) -> CoreRes
                                 FilterOp::NotStartsWith => s.not_starts_with(value),
                                 FilterOp::EndsWith => s.ends_with(value),
                                 FilterOp::NotEndsWith => s.not_ends_with(value),
+                                FilterOp::Pattern => s.matches_pattern(value),
+                                FilterOp::NotPattern => s.not_matches_pattern(value),
                                 FilterOp::Field => s.equals(value),
                                 _ => unreachable!(),
                             })
                         }
+                        Field::Relation(r) if op.is_count() => {
+                            let count = match PrismaValue::from_value(v) {
+                                PrismaValue::Int(i) if i >= 0 => i as usize,
+                                _ => {
+                                    return Err(CoreError::QueryValidationError(format!(
+                                        "Query argument {} expects a count of at least 0",
+                                        k
+                                    )))
+                                }
+                            };
+
+                            Ok(r.count_related(match op {
+                                FilterOp::CountLt => CountCondition::LessThan(count),
+                                FilterOp::CountLte => CountCondition::LessThanOrEquals(count),
+                                FilterOp::CountGt => CountCondition::GreaterThan(count),
+                                FilterOp::CountGte => CountCondition::GreaterThanOrEquals(count),
+                                FilterOp::Count => CountCondition::Equals(count),
+                                _ => unreachable!(),
+                            }))
+                        }
+                        // `author: null` and `author_not: null` on to-one relations.
+                        Field::Relation(r) if *v == Value::Null && (op == &FilterOp::Field || op == &FilterOp::Not) => {
+                            if r.is_list {
+                                return Err(CoreError::QueryValidationError(format!(
+                                    "Query argument {} can't be null for a list relation",
+                                    k
+                                )));
+                            }
+
+                            Ok(match op {
+                                FilterOp::Field => r.one_relation_is_null(),
+                                _ => r.one_relation_is_not_null(),
+                            })
+                        }
                         Field::Relation(r) => {
                             let value = match v {
                                 Value::Object(o) => o,
@@ -16,7 +16,10 @@ pub use single::*;
 
 use self::inflector::Inflector;
 use crate::{CoreError, CoreResult, ReadQuery};
-use connector::{filter::NodeSelector, QueryArguments};
+use connector::{
+    filter::{NodeSelector, RecordSelector},
+    CursorToken, QueryArguments,
+};
 use graphql_parser::query::{Field, Selection, Value};
 use prisma_models::{
     Field as ModelField, GraphqlId, ModelRef, OrderBy, PrismaValue, RelationFieldRef, SchemaRef, SelectedField,
@@ -106,21 +109,47 @@ pub trait BuilderExt {
     /// Last step that invokes query building
     fn build(self) -> CoreResult<Self::Output>;
 
-    /// Get node selector from field and model
-    fn extract_node_selector(field: &Field, model: ModelRef) -> CoreResult<NodeSelector> {
+    /// Get node selector from field and model. A compound unique index is
+    /// selected by its name, e.g. `where: { fullName: { firstName: "a", lastName: "b" } }`.
+    fn extract_node_selector(field: &Field, model: ModelRef) -> CoreResult<RecordSelector> {
         println!("NODE SELECT");
         // FIXME: this expects at least one query arg...
         let (_, value) = field.arguments.first().expect("no arguments found");
         match value {
             Value::Object(obj) => {
                 let (field_name, value) = obj.iter().next().expect("object was empty");
-                let field = model.fields().find_from_scalar(field_name).unwrap();
-                let value = Self::value_to_prisma_value(value);
 
-                Ok(NodeSelector {
-                    field: Arc::clone(&field),
-                    value: value,
-                })
+                match (model.find_unique_index(field_name), value) {
+                    (Some(index), Value::Object(values)) => {
+                        let mut node_selectors = Vec::with_capacity(index.fields.len());
+
+                        for field_name in index.fields.iter() {
+                            let field = model.fields().find_from_scalar(field_name)?;
+                            let value = values.get(field_name).ok_or_else(|| {
+                                CoreError::QueryValidationError(format!(
+                                    "Missing value for `{}` of the unique index `{}`",
+                                    field_name, index.name
+                                ))
+                            })?;
+
+                            node_selectors.push(NodeSelector {
+                                field,
+                                value: Self::value_to_prisma_value(value),
+                            });
+                        }
+
+                        Ok(RecordSelector::Compound(node_selectors))
+                    }
+                    _ => {
+                        let field = model.fields().find_from_scalar(field_name).unwrap();
+                        let value = Self::value_to_prisma_value(value);
+
+                        Ok(RecordSelector::Field(NodeSelector {
+                            field: Arc::clone(&field),
+                            value: value,
+                        }))
+                    }
+                }
             }
             _ => unimplemented!(),
         }
@@ -158,12 +187,14 @@ pub trait BuilderExt {
                             Some(num) => Ok(QueryArguments { last: Some(num as u32), ..res }),
                             None => Err(CoreError::QueryValidationError("Invalid number provided".into())),
                         },
+                        ("after", Value::String(s)) if CursorToken::is_token(s) => CursorToken::decode(s).map(|_| QueryArguments { after: Some(s.clone().into()), ..res }).map_err(CoreError::from),
                         ("after", Value::String(s)) if s.is_uuid() => Ok(QueryArguments { after: Some(GraphqlId::UUID(s.as_uuid())), ..res }),
                         ("after", Value::String(s)) => Ok(QueryArguments { after: Some(s.clone().into()), ..res }),
                         ("after", Value::Int(num)) => match num.as_i64() {
                             Some(num) => Ok(QueryArguments { after: Some((num as usize).into()), ..res }),
                             None => Err(CoreError::QueryValidationError("Invalid number provided".into())),
                         },
+                        ("before", Value::String(s)) if CursorToken::is_token(s) => CursorToken::decode(s).map(|_| QueryArguments { before: Some(s.clone().into()), ..res }).map_err(CoreError::from),
                         ("before", Value::String(s)) if s.is_uuid() => Ok(QueryArguments { before: Some(GraphqlId::UUID(s.as_uuid())), ..res }),
                         ("before", Value::String(s)) => Ok(QueryArguments { before: Some(s.clone().into()), ..res }),
                         ("before", Value::Int(num)) => match num.as_i64() {
@@ -1,6 +1,6 @@
 //! Prisma read query AST module
 
-use connector::{filter::NodeSelector, QueryArguments};
+use connector::{filter::RecordSelector, QueryArguments};
 use prisma_models::prelude::*;
 
 #[derive(Debug, Clone)]
@@ -14,7 +14,7 @@ pub enum ReadQuery {
 #[derive(Debug, Clone)]
 pub struct RecordQuery {
     pub name: String,
-    pub selector: NodeSelector,
+    pub selector: RecordSelector,
     pub selected_fields: SelectedFields,
     pub nested: Vec<ReadQuery>,
     // TODO: rename to something more obvious maybe?
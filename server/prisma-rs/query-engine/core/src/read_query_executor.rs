@@ -1,22 +1,66 @@
 use crate::{query_ast, query_results::*, CoreResult};
-use connector::{ConnectorResult, DataResolver, ScalarListValues};
+use connector::{ConnectorResult, DataResolver, RequestContext, ScalarListValues};
+use crossbeam_utils::thread;
 use prisma_models::{GraphqlId, ScalarField, SelectedFields, SingleNode};
 use query_ast::*;
-use std::{convert::TryFrom, sync::Arc};
+use std::{convert::TryFrom, slice, sync::Arc};
 
 pub struct ReadQueryExecutor {
     pub data_resolver: Arc<DataResolver + Send + Sync + 'static>,
+    /// The number of root queries of a request executed at the same time,
+    /// each on its own connection. `1` executes them one after another.
+    pub read_concurrency: usize,
 }
 
 impl ReadQueryExecutor {
     pub fn execute(&self, queries: &[ReadQuery]) -> CoreResult<Vec<ReadQueryResult>> {
-        self.execute_internal(queries, vec![])
+        if self.read_concurrency > 1 && queries.len() > 1 {
+            self.execute_parallel(queries)
+        } else {
+            self.execute_internal(queries, vec![])
+        }
+    }
+
+    /// Executes the root queries in batches of `read_concurrency` threads.
+    /// The results keep the order of the queries, and the query tags, session
+    /// settings, cancellation, tenant and database role of the request are
+    /// passed on to the threads.
+    fn execute_parallel(&self, queries: &[ReadQuery]) -> CoreResult<Vec<ReadQueryResult>> {
+        let context = RequestContext::current();
+        let mut results = vec![];
+
+        for batch in queries.chunks(self.read_concurrency) {
+            let batch_results: Vec<CoreResult<Vec<ReadQueryResult>>> = thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|query| {
+                        // Selected fields cache their columns without
+                        // synchronization, so every thread gets its own copy.
+                        let query = query.clone();
+                        let context = context.clone();
+
+                        scope.spawn(move |_| context.run(|| self.execute_internal(slice::from_ref(&query), vec![])))
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("Read query thread panicked"))
+                    .collect()
+            })
+            .expect("Read query thread panicked");
+
+            for result in batch_results {
+                results.append(&mut result?);
+            }
+        }
+
+        Ok(results)
     }
 
     #[warn(warnings)]
     fn execute_internal(&self, queries: &[ReadQuery], parent_ids: Vec<GraphqlId>) -> CoreResult<Vec<ReadQueryResult>> {
         let mut results = vec![];
-        dbg!(&queries);
 
         for query in queries {
             match query {
@@ -29,7 +73,7 @@ impl ReadQueryExecutor {
 
                     match scalars {
                         Some(ref record) => {
-                            let model = Arc::clone(&query.selector.field.model());
+                            let model = query.selector.model();
                             let ids = vec![record.get_id_value(model)?.clone()];
                             let list_fields = selected_fields.scalar_lists();
                             let lists = self.resolve_scalar_list_fields(ids.clone(), list_fields)?;
@@ -71,12 +115,12 @@ impl ReadQueryExecutor {
                 ReadQuery::RelatedRecordQuery(query) => {
                     let selected_fields = Self::inject_required_fields(query.selected_fields.clone());
 
-                    let result = dbg!(self.data_resolver.get_related_nodes(
+                    let result = self.data_resolver.get_related_nodes(
                         Arc::clone(&query.parent_field),
                         &parent_ids,
                         query.args.clone(),
                         &selected_fields,
-                    )?);
+                    )?;
 
                     // If our result set contains more than one entry
                     // we need to handle all of them!
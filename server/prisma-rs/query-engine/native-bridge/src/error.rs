@@ -60,10 +60,62 @@ impl From<BridgeError> for protobuf::prisma::error::Value {
                 protobuf::prisma::error::Value::ConnectionError(format!("{}", e))
             }
 
+            BridgeError::ConnectorError(e @ ConnectorError::ConnectionPoolExhausted { .. }) => {
+                protobuf::prisma::error::Value::ConnectionError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::Overloaded { .. }) => {
+                protobuf::prisma::error::Value::ConnectionError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::QueryCancelled) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
             BridgeError::ConnectorError(e @ ConnectorError::QueryError(_)) => {
                 protobuf::prisma::error::Value::QueryError(format!("{}", e))
             }
 
+            BridgeError::ConnectorError(e @ ConnectorError::BatchFailure { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::UnsupportedByDatabase { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::ReadOnlyModel { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::SchemaDrift { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::TenantRequired) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::InvalidTenant { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::ShardKeyRequired { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::ShardKeyChanged { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::UnparameterizedSql { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::OperationDenied { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
             BridgeError::ConnectorError(e @ ConnectorError::InvalidConnectionArguments) => {
                 protobuf::prisma::error::Value::QueryError(format!("{}", e))
             }
@@ -72,8 +124,20 @@ impl From<BridgeError> for protobuf::prisma::error::Value {
                 protobuf::prisma::error::Value::FieldCannotBeNull(field)
             }
 
-            BridgeError::ConnectorError(ConnectorError::UniqueConstraintViolation { field_name }) => {
-                protobuf::prisma::error::Value::UniqueConstraintViolation(field_name)
+            BridgeError::ConnectorError(e @ ConnectorError::ValueTooLong { .. }) => {
+                protobuf::prisma::error::Value::InvalidInputError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::InvalidCursor) => {
+                protobuf::prisma::error::Value::InvalidInputError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(e @ ConnectorError::UnsupportedCondition { .. }) => {
+                protobuf::prisma::error::Value::InvalidInputError(format!("{}", e))
+            }
+
+            BridgeError::ConnectorError(ConnectorError::UniqueConstraintViolation { constraint, .. }) => {
+                protobuf::prisma::error::Value::UniqueConstraintViolation(constraint)
             }
 
             BridgeError::ConnectorError(ConnectorError::RelationViolation {
@@ -90,6 +154,10 @@ impl From<BridgeError> for protobuf::prisma::error::Value {
                 protobuf::prisma::error::Value::RelationViolation(error)
             }
 
+            BridgeError::ConnectorError(e @ ConnectorError::ForeignKeyViolation { .. }) => {
+                protobuf::prisma::error::Value::QueryError(format!("{}", e))
+            }
+
             BridgeError::ConnectorError(ConnectorError::NodeNotFoundForWhere(info)) => {
                 let node_selector = protobuf::prisma::NodeSelector {
                     model_name: info.model,
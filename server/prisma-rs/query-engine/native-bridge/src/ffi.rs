@@ -0,0 +1,262 @@
+//! A C interface with JSON in and out, for runtimes embedding the connector
+//! instead of spawning the server. Every function taking a string takes a
+//! NUL-terminated UTF-8 string owned by the caller, every returned string is
+//! owned by the connector and must be freed with `json_destroy`.
+//!
+//! Responses are either `{"data": ...}` or `{"error": {"code": ..., "message": ...}}`,
+//! with the codes of `ConnectorError::code`. Panics don't unwind into the
+//! caller, they are answered with the code `PANIC`.
+//!
+//! The interface is kept small: records are read by equal field values with
+//! `json_query` and created with `json_load_fixture`. Other filters and
+//! mutations need the GraphQL API of the server.
+use crate::{BridgeError, BridgeResult};
+use connector::{filter::Filter, DataResolver, QueryArguments, ScalarCompare};
+use prisma_models::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use sql_connector::{
+    database::{SqlDatabase, Sqlite, SqliteBuilder},
+    Fixture,
+};
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::Arc,
+};
+
+/// A connected database with the project it serves, opaque to the caller.
+pub struct JsonConnection {
+    database: SqlDatabase<Sqlite>,
+    project: ProjectRef,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectInput {
+    databases_folder: String,
+    project: ProjectTemplate,
+    /// Files of databases not in the databases folder, by database name.
+    #[serde(default)]
+    database_files: BTreeMap<String, String>,
+    connection_limit: Option<u32>,
+    #[serde(default)]
+    test_mode: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryInput {
+    model: String,
+    /// The values the scalar fields must equal, all records if empty.
+    #[serde(rename = "where", default)]
+    where_: BTreeMap<String, PrismaValue>,
+    skip: Option<u32>,
+    first: Option<u32>,
+    /// The scalar fields to return, all of them if not given.
+    fields: Option<Vec<String>>,
+}
+
+/// Connects to the databases of the project, returning null on failure. The
+/// error response is then written to `error` if it is not null.
+///
+/// # Safety
+///
+/// `input` must be null or a NUL-terminated string, `error` null or valid
+/// for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn json_connect(input: *const c_char, error: *mut *mut c_char) -> *mut JsonConnection {
+    match catch_panic(|| read_input(input).and_then(connect)) {
+        Ok(connection) => Box::into_raw(Box::new(connection)),
+        Err(response) => {
+            if !error.is_null() {
+                *error = into_c_string(response);
+            }
+
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Closes the connections of the pool. The connection can't be used after.
+///
+/// # Safety
+///
+/// `connection` must be null or returned by `json_connect`, and not be
+/// disconnected already or used by another thread.
+#[no_mangle]
+pub unsafe extern "C" fn json_disconnect(connection: *mut JsonConnection) {
+    if !connection.is_null() {
+        // Nothing is left to answer a panic while closing to.
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(connection))));
+    }
+}
+
+/// Reads records of a model matching all values of `where`, answering with
+/// the field names and the values of the records in the same order. Only
+/// equality is supported, e.g. `{"where": {"name": "alice"}}`.
+///
+/// # Safety
+///
+/// `connection` must be null or returned by `json_connect` and not
+/// disconnected, `input` null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn json_query(connection: *const JsonConnection, input: *const c_char) -> *mut c_char {
+    respond(connection, input, |connection, input: QueryInput| {
+        let model = connection.project.schema().find_model(&input.model)?;
+
+        let selected_fields = match input.fields {
+            Some(names) => {
+                let fields: Result<Vec<_>, DomainError> =
+                    names.iter().map(|name| model.fields().find_from_scalar(name)).collect();
+
+                SelectedFields::from(fields?)
+            }
+            None => SelectedFields::from(Arc::clone(&model)),
+        };
+
+        let mut conditions = Vec::with_capacity(input.where_.len());
+
+        for (name, value) in input.where_ {
+            conditions.push(model.fields().find_from_scalar(&name)?.equals(value));
+        }
+
+        let query_arguments = QueryArguments {
+            skip: input.skip,
+            first: input.first,
+            filter: if conditions.is_empty() {
+                None
+            } else {
+                Some(Filter::and(conditions))
+            },
+            ..Default::default()
+        };
+
+        let result = connection
+            .database
+            .get_nodes(model, query_arguments, &selected_fields)?;
+
+        let nodes: Vec<Vec<PrismaValue>> = result.nodes.into_iter().map(|node| node.values).collect();
+
+        Ok(json!({ "fields": result.field_names, "nodes": nodes }))
+    })
+}
+
+/// Creates the records of a fixture with their relations in one transaction,
+/// see `Fixture`, answering with the ids of the records by their keys. Records
+/// can't be updated or deleted through this interface.
+///
+/// # Safety
+///
+/// `connection` must be null or returned by `json_connect` and not
+/// disconnected, `input` null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn json_load_fixture(connection: *const JsonConnection, input: *const c_char) -> *mut c_char {
+    respond(connection, input, |connection, fixture: Fixture| {
+        let project = Arc::clone(&connection.project);
+        let db_name = project.schema().db_name.clone();
+        let ids = connection.database.load_fixture(&db_name, project, &fixture)?;
+
+        Ok(serde_json::to_value(ids)?)
+    })
+}
+
+/// Frees a string returned by the connector.
+///
+/// # Safety
+///
+/// `string` must be null or returned by the connector, and not be freed
+/// already.
+#[no_mangle]
+pub unsafe extern "C" fn json_destroy(string: *mut c_char) {
+    if !string.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(CString::from_raw(string))));
+    }
+}
+
+fn connect(input: ConnectInput) -> BridgeResult<JsonConnection> {
+    let project: ProjectRef = input.project.into();
+    let mut builder = SqliteBuilder::new(input.databases_folder).test_mode(input.test_mode);
+
+    if let Some(limit) = input.connection_limit {
+        builder = builder.connection_limit(limit);
+    }
+
+    for (db_name, path) in input.database_files {
+        builder = builder.database_file(db_name, path);
+    }
+
+    Ok(JsonConnection {
+        database: SqlDatabase::new(builder.build()?),
+        project,
+    })
+}
+
+unsafe fn respond<I, F>(connection: *const JsonConnection, input: *const c_char, f: F) -> *mut c_char
+where
+    I: DeserializeOwned,
+    F: FnOnce(&JsonConnection, I) -> BridgeResult<Value>,
+{
+    let response = catch_panic(|| match connection.as_ref() {
+        Some(connection) => read_input(input).and_then(|input| f(connection, input)),
+        None => Err(BridgeError::InvalidConnectionArguments("The connection is null.")),
+    });
+
+    into_c_string(response.map(|data| json!({ "data": data })).unwrap_or_else(|e| e))
+}
+
+/// Runs `f`, with the error response if it fails or panics. Unwinding into
+/// the caller is undefined behavior.
+fn catch_panic<T, F>(f: F) -> Result<T, Value>
+where
+    F: FnOnce() -> BridgeResult<T>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result.map_err(error_response),
+        Err(payload) => Err(panic_response(payload)),
+    }
+}
+
+unsafe fn read_input<I>(input: *const c_char) -> BridgeResult<I>
+where
+    I: DeserializeOwned,
+{
+    if input.is_null() {
+        return Err(BridgeError::InvalidConnectionArguments("The input is null."));
+    }
+
+    Ok(serde_json::from_slice(CStr::from_ptr(input).to_bytes())?)
+}
+
+fn error_response(error: BridgeError) -> Value {
+    let (code, message) = match error {
+        BridgeError::ConnectorError(e) => (e.code(), format!("{}", e)),
+        BridgeError::DomainError(e) => ("DOMAIN_ERROR", format!("{}", e)),
+        BridgeError::JsonDecodeError(e) => ("JSON_DECODE_ERROR", format!("{}", e)),
+        BridgeError::InvalidConnectionArguments(message) => ("INVALID_INPUT", message.to_string()),
+        e => ("INVALID_INPUT", format!("{}", e)),
+    };
+
+    json!({ "error": { "code": code, "message": message } })
+}
+
+fn panic_response(payload: Box<Any + Send>) -> Value {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => String::from("The connector panicked."),
+        },
+    };
+
+    json!({ "error": { "code": "PANIC", "message": message } })
+}
+
+fn into_c_string(value: Value) -> *mut c_char {
+    // UNWRAP: JSON escapes NUL characters in strings.
+    CString::new(value.to_string()).unwrap().into_raw()
+}
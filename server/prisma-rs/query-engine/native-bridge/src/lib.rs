@@ -7,6 +7,8 @@ extern crate prost_derive;
 mod error;
 mod protobuf;
 
+pub mod ffi;
+
 use lazy_static::lazy_static;
 use prisma_common::config::{self, PrismaConfig};
 
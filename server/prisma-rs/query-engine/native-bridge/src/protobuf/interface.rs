@@ -3,11 +3,15 @@ use crate::{
     protobuf::{mutaction::*, prelude::*, InputValidation},
     BridgeError, BridgeResult, ExternalInterface,
 };
-use connector::{error::ConnectorError, filter::NodeSelector, DataResolver, DatabaseMutactionExecutor};
+use connector::{
+    error::ConnectorError,
+    filter::{NodeSelector, RecordSelector},
+    DataResolver, DatabaseMutactionExecutor,
+};
 use prisma_common::config::*;
 use prisma_models::prelude::*;
 use prost::Message;
-use sql_connector::{database::SqlDatabase, database::Sqlite};
+use sql_connector::{database::SqlDatabase, database::Sqlite, QueryLog};
 use std::sync::Arc;
 
 pub struct ProtoBufInterface {
@@ -22,7 +26,30 @@ impl ProtoBufInterface {
                 if config.connector == "sqlite-native" || config.connector == "native-integration-tests" =>
             {
                 let server_root = std::env::var("SERVER_ROOT").expect("Env var SERVER_ROOT required but not found.");
-                let sqlite = Sqlite::new(format!("{}/db", server_root).into(), config.limit(), true).unwrap();
+                let mut sqlite = Sqlite::new(format!("{}/db", server_root).into(), config.limit(), true).unwrap();
+                let mut query_log = QueryLog::new(
+                    config.query_log_level(),
+                    config.redact_query_parameters(),
+                    config.slow_query_threshold_ms(),
+                )
+                .unwrap();
+
+                for column in config.scrubbed_query_columns() {
+                    query_log.scrub_column(column.as_str());
+                }
+
+                sqlite.set_query_log(query_log);
+                sqlite.set_query_tagging(config.tag_queries());
+
+                if let Some(capacity) = config.statement_cache_size {
+                    sqlite.set_statement_cache_capacity(capacity);
+                }
+
+                if let Some(max_in_flight) = config.max_in_flight_queries {
+                    sqlite.set_max_in_flight(max_in_flight);
+                }
+
+                sqlite.set_single_flight(config.single_flight_reads.unwrap_or(false));
 
                 Arc::new(SqlDatabase::new(sqlite))
             }
@@ -80,7 +107,9 @@ impl ExternalInterface for ProtoBufInterface {
             let field = model.fields().find_from_scalar(&input.field_name)?;
             let node_selector = NodeSelector { field, value };
 
-            let query_result = self.data_resolver.get_node_by_where(&node_selector, &selected_fields)?;
+            let query_result = self
+                .data_resolver
+                .get_node_by_where(&RecordSelector::from(node_selector), &selected_fields)?;
 
             let (nodes, fields) = match query_result {
                 Some(node) => (vec![node.node.into()], node.field_names),
@@ -230,7 +259,7 @@ impl ExternalInterface for ProtoBufInterface {
             let count = match project.schema().find_model(&input.model_name) {
                 Ok(model) => self
                     .data_resolver
-                    .count_by_table(project.schema().db_name.as_ref(), model.db_name()),
+                    .count_by_table(project.schema().db_name.as_ref(), &model.table_name()),
                 Err(_) => self
                     .data_resolver
                     .count_by_table(project.schema().db_name.as_ref(), &input.model_name),
@@ -1,16 +1,24 @@
-use crate::{data_model, PrismaResult};
-use core::ReadQueryExecutor;
-use prisma_common::config::{self, ConnectionLimit, PrismaConfig, PrismaDatabase};
+use crate::{data_model, PrismaError, PrismaResult};
+use connector::{ConnectorResult, DataResolver};
+use core::{CoreError, ReadQueryExecutor};
+use prisma_common::config::{self, ConnectionLimit, FileConfig, PrismaConfig, PrismaDatabase, QueryLogging};
 use prisma_models::SchemaRef;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-#[cfg(feature = "sql")]
-use sql_connector::{database::SqlDatabase, database::Sqlite};
+#[cfg(feature = "sqlite")]
+use sql_connector::{
+    database::{SqlDatabase, Sqlite, SqliteBuilder},
+    validate_schema, QueryLog, ShardRouter, TenantRouter, Transactional,
+};
+
+/// The number of tenant databases kept open, if not configured.
+#[cfg(feature = "sqlite")]
+const DEFAULT_MAX_TENANTS: usize = 64;
 
 #[derive(DebugStub)]
 pub struct PrismaContext {
     pub config: PrismaConfig,
-    pub schema: SchemaRef,
+    schema: RwLock<SchemaRef>,
 
     #[debug_stub = "#QueryExecutor#"]
     pub read_query_executor: ReadQueryExecutor,
@@ -19,34 +27,168 @@ pub struct PrismaContext {
 impl PrismaContext {
     pub fn new() -> PrismaResult<Self> {
         let config = config::load().unwrap();
-        let data_resolver = match config.databases.get("default") {
-            Some(PrismaDatabase::File(ref config)) if config.connector == "sqlite-native" => {
-                let db_name = config.db_name();
-                let db_folder = config
-                    .database_file
-                    .trim_end_matches(&format!("{}.db", db_name))
-                    .trim_end_matches("/");
-
-                let sqlite = Sqlite::new(db_folder.to_owned(), config.limit(), false).unwrap();
-                Arc::new(SqlDatabase::new(sqlite))
-            }
-            _ => panic!("Database connector is not supported, use sqlite with a file for now!"),
-        };
 
-        let read_query_executor: ReadQueryExecutor = ReadQueryExecutor { data_resolver };
+        let default_database = config.databases.get("default").unwrap();
+        let db_name = default_database.db_name().expect("database was not set");
+        let schema = data_model::load(db_name, default_database.table_prefix())?;
+        let trusted_gateway = config.trusted_gateway.unwrap_or(false);
+
+        let (data_resolver, read_concurrency): (Arc<DataResolver + Send + Sync + 'static>, usize) =
+            match config.databases.get("default") {
+                #[cfg(feature = "sqlite")]
+                Some(PrismaDatabase::File(ref config)) if config.connector == "sqlite-native" => {
+                    let mut sqlite = connect_sqlite(config, config.database_file.clone()).unwrap();
+
+                    // Every root query of a request can use its own connection.
+                    let read_concurrency = config.limit() as usize;
+                    let validate = config.validate_schema.unwrap_or(false);
+
+                    if config.tenants_folder.is_some() && !trusted_gateway {
+                        warn!("Requests will fail without a tenant, prisma-tenant needs trustedGateway.");
+                    }
+
+                    match config.tenants_folder.clone() {
+                        Some(tenants_folder) if config.shared_tenant_connections.unwrap_or(false) => {
+                            // Each request attaches its tenant to a shared
+                            // connection, there is no database to validate.
+                            if validate {
+                                return Err(PrismaError::ConfigurationError(String::from(
+                                    "validateSchema needs separate tenant connections.",
+                                )));
+                            }
+
+                            sqlite.set_tenants_folder(tenants_folder);
+
+                            (Arc::new(SqlDatabase::new(sqlite)), read_concurrency)
+                        }
+                        Some(tenants_folder) => {
+                            let max_tenants = config.max_tenants.unwrap_or(DEFAULT_MAX_TENANTS);
+                            let file_config = config.clone();
+                            let tenant_schema = Arc::clone(&schema);
+
+                            // Each tenant database is validated when it is opened,
+                            // failing the requests of the tenant.
+                            let router = TenantRouter::new(sqlite.capabilities(), max_tenants, move |tenant| {
+                                let sqlite = connect_sqlite(&file_config, format!("{}/{}.db", tenants_folder, tenant))?;
+
+                                if validate {
+                                    validate_schema(&sqlite, &tenant_schema.db_name, &tenant_schema)?;
+                                }
 
-        let db_name = config
-            .databases
-            .get("default")
-            .unwrap()
-            .db_name()
-            .expect("database was not set");
+                                Ok(sqlite)
+                            });
+
+                            (Arc::new(SqlDatabase::new(router)), read_concurrency)
+                        }
+                        None if config.shard_files.is_some() => {
+                            let mut shards = vec![SqlDatabase::new(sqlite)];
+
+                            for shard_file in config.shard_files.iter().flatten() {
+                                shards.push(SqlDatabase::new(connect_sqlite(config, shard_file.clone()).unwrap()));
+                            }
+
+                            if validate {
+                                for shard in shards.iter() {
+                                    shard
+                                        .validate_schema(&schema.db_name, &schema)
+                                        .map_err(CoreError::from)?;
+                                }
+                            }
+
+                            (Arc::new(ShardRouter::new(shards)), read_concurrency)
+                        }
+                        None => {
+                            let database = SqlDatabase::new(sqlite);
+
+                            if validate {
+                                database
+                                    .validate_schema(&schema.db_name, &schema)
+                                    .map_err(CoreError::from)?;
+                            }
+
+                            (Arc::new(database), read_concurrency)
+                        }
+                    }
+                }
+                _ => panic!("Database connector is not supported, use sqlite with a file for now!"),
+            };
+
+        let read_query_executor: ReadQueryExecutor = ReadQueryExecutor {
+            data_resolver,
+            read_concurrency,
+        };
 
-        let schema = data_model::load(db_name)?;
         Ok(Self {
             config: config,
-            schema: schema,
+            schema: RwLock::new(schema),
             read_query_executor,
         })
     }
+
+    /// The current data model. Requests keep the one they started with if it
+    /// is reloaded while they run.
+    pub fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema.read().unwrap())
+    }
+
+    /// Loads the data model again and uses it for all following requests,
+    /// e.g. after deploying a new data model. The connections are kept, but
+    /// the connector drops everything it cached for the old data model.
+    pub fn reload_schema(&self) -> PrismaResult<SchemaRef> {
+        let default_database = self.config.databases.get("default").unwrap();
+        let db_name = default_database.db_name().expect("database was not set");
+        let schema = data_model::load(db_name, default_database.table_prefix())?;
+
+        *self.schema.write().unwrap() = Arc::clone(&schema);
+        self.read_query_executor
+            .data_resolver
+            .invalidate_caches(&schema.db_name);
+
+        Ok(schema)
+    }
+}
+
+/// A SQLite connector with the settings of `config`, reading the database
+/// from `database_file`.
+#[cfg(feature = "sqlite")]
+fn connect_sqlite(config: &FileConfig, database_file: String) -> ConnectorResult<Sqlite> {
+    let db_name = config.db_name();
+    let db_folder = config
+        .database_file
+        .trim_end_matches(&format!("{}.db", db_name))
+        .trim_end_matches("/");
+
+    let mut sqlite = SqliteBuilder::new(db_folder)
+        .connection_limit(config.limit())
+        .database_file(config.schema_name(), database_file)
+        .build()?;
+
+    let mut query_log = QueryLog::new(
+        config.query_log_level(),
+        config.redact_query_parameters(),
+        config.slow_query_threshold_ms(),
+    )?;
+
+    for column in config.scrubbed_query_columns() {
+        query_log.scrub_column(column.as_str());
+    }
+
+    sqlite.set_query_log(query_log);
+    sqlite.set_query_tagging(config.tag_queries());
+
+    if let Some(capacity) = config.statement_cache_size {
+        sqlite.set_statement_cache_capacity(capacity);
+    }
+
+    if let Some(max_in_flight) = config.max_in_flight_queries {
+        sqlite.set_max_in_flight(max_in_flight);
+    }
+
+    sqlite.set_single_flight(config.single_flight_reads.unwrap_or(false));
+
+    for role in config.read_only_roles.iter().flatten() {
+        sqlite.add_read_only_role(role.clone());
+    }
+
+    Ok(sqlite)
 }
@@ -30,10 +30,17 @@ impl Validatable for SchemaRef {
     }
 }
 
-/// Loads and builds the internal schema from the data model
-pub fn load(db_name: String) -> PrismaResult<SchemaRef> {
+/// Loads and builds the internal schema from the data model. A configured
+/// `table_prefix` replaces the one of the data model.
+pub fn load(db_name: String, table_prefix: Option<String>) -> PrismaResult<SchemaRef> {
     let data_model_json = load_string()?;
-    Ok(serde_json::from_str::<SchemaTemplate>(&data_model_json)?.build(db_name))
+    let mut template = serde_json::from_str::<SchemaTemplate>(&data_model_json)?;
+
+    if table_prefix.is_some() {
+        template.table_prefix = table_prefix;
+    }
+
+    Ok(template.build(db_name))
 }
 
 /// Loads the config as unparsed json string.
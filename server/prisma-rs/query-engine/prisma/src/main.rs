@@ -54,7 +54,10 @@ fn main() {
                 r.method(Method::POST).with(http_handler);
                 r.method(Method::GET).with(playground_handler);
             })
-            .resource("/datamodel", |r| r.method(Method::GET).with(data_model_handler))
+            .resource("/datamodel", |r| {
+                r.method(Method::GET).with(data_model_handler);
+                r.method(Method::POST).with(reload_data_model_handler);
+            })
     })
     .bind(address)
     .unwrap()
@@ -87,6 +90,14 @@ fn data_model_handler<T>(_: HttpRequest<T>) -> impl Responder {
     data_model::load_sdl_string().unwrap()
 }
 
+/// Switches to the data model currently deployed, without restarting.
+fn reload_data_model_handler(req: HttpRequest<Arc<RequestContext>>) -> impl Responder {
+    match req.state().context.reload_schema() {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "reloaded": true })),
+        Err(err) => HttpResponse::InternalServerError().json(serde_json::json!({ "reason": format!("{}", err) })),
+    }
+}
+
 fn playground_handler<T>(_: HttpRequest<T>) -> impl Responder {
     let index_html = StaticFiles::get("playground.html").unwrap();
     HttpResponse::Ok().content_type("text/html").body(index_html)
@@ -11,6 +11,8 @@ use crate::serializer::json;
 
 type JsonMap = Map<String, Value>;
 
+const SESSION_SETTING_HEADER_PREFIX: &str = "prisma-setting-";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GraphQlBody {
@@ -45,21 +47,55 @@ fn handle_safely(req: PrismaRequest<GraphQlBody>, ctx: &PrismaContext) -> Prisma
         Err(e) => return Err(PrismaError::QueryParsingError(format!("{:?}", e))),
     };
 
+    let schema = ctx.schema();
+
     // Let's validate the schema!
-    if let Err(_) = ctx.schema.validate(&query_doc) {
+    if let Err(_) = schema.validate(&query_doc) {
         return Err(PrismaError::QueryValidationError(
             "Schema validation failed for unknown reasons".into(),
         ));
     }
 
+    let trusted_gateway = ctx.config.trusted_gateway.unwrap_or(false);
+    let tags = query_tags(&req);
+    let settings = session_settings(&req, trusted_gateway);
+    let tenant = gateway_header(&req, trusted_gateway, "prisma-tenant");
+    let role = gateway_header(&req, trusted_gateway, "prisma-role");
+    let actor = gateway_header(&req, trusted_gateway, "prisma-actor");
     let rb = RootBuilder {
         query: query_doc,
-        schema: ctx.schema.clone(),
+        schema: schema,
         operation_name: req.body.operation_name,
     };
 
     let queries: Vec<ReadQuery> = rb.build()?;
-    let ir = dbg!(ctx.read_query_executor.execute(&queries)?)
+    // Records read by id are read once per request.
+    let execute = || {
+        connector::with_node_cache(connector::NodeCache::default(), || {
+            connector::with_query_tags(tags, || {
+                connector::with_session_settings(settings, || ctx.read_query_executor.execute(&queries))
+            })
+        })
+    };
+
+    // A database routed by tenant fails the queries without one.
+    let execute = || match tenant {
+        Some(tenant) => connector::with_tenant(tenant, execute),
+        None => execute(),
+    };
+
+    let execute = || match role {
+        Some(role) => connector::with_database_role(role, execute),
+        None => execute(),
+    };
+
+    // Recorded in the audit log for the mutations of the request.
+    let results = match actor {
+        Some(actor) => connector::with_actor(actor, execute)?,
+        None => execute()?,
+    };
+
+    let ir = results
         .into_iter()
         .fold(Builder::new(), |builder, result| builder.add(result))
         .build();
@@ -67,9 +103,104 @@ fn handle_safely(req: PrismaRequest<GraphQlBody>, ctx: &PrismaContext) -> Prisma
     Ok(json::serialize(ir))
 }
 
+/// The operation name and the W3C trace context of the request, for
+/// correlating the queries in the database.
+fn query_tags(req: &PrismaRequest<GraphQlBody>) -> Vec<(String, String)> {
+    let mut tags = Vec::new();
+
+    if let Some(traceparent) = req.headers.get("traceparent") {
+        tags.push((String::from("traceparent"), traceparent.clone()));
+    }
+
+    if let Some(ref operation_name) = req.body.operation_name {
+        tags.push((String::from("operation"), operation_name.clone()));
+    }
+
+    tags
+}
+
+/// The header `name`, if the server runs behind a trusted gateway. Clients
+/// could claim anything with it otherwise, e.g. a role exempt from
+/// redactions.
+fn gateway_header(req: &PrismaRequest<GraphQlBody>, trusted_gateway: bool, name: &str) -> Option<String> {
+    if trusted_gateway {
+        req.headers.get(name).cloned()
+    } else {
+        None
+    }
+}
+
+/// The settings of the `prisma-setting-{name}` headers, e.g.
+/// `prisma-setting-app.current_user` for the setting `app.current_user`. As
+/// policies rely on them, they are ignored unless the server runs behind a
+/// trusted gateway.
+fn session_settings(req: &PrismaRequest<GraphQlBody>, trusted_gateway: bool) -> Vec<(String, String)> {
+    if !trusted_gateway {
+        return Vec::new();
+    }
+
+    req.headers
+        .iter()
+        .filter(|(name, _)| name.starts_with(SESSION_SETTING_HEADER_PREFIX))
+        .map(|(name, value)| (name[SESSION_SETTING_HEADER_PREFIX.len()..].to_string(), value.clone()))
+        .collect()
+}
+
 /// Create a json envelope
 fn json_envelope(id: &str, map: serde_json::Map<String, Value>) -> Value {
     let mut envelope = JsonMap::new();
     envelope.insert(id.to_owned(), Value::Object(map));
     Value::Object(envelope)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&str, &str)]) -> PrismaRequest<GraphQlBody> {
+        PrismaRequest {
+            body: GraphQlBody {
+                query: String::from("{ items { id } }"),
+                operation_name: None,
+                variables: HashMap::new(),
+            },
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            path: String::from("/"),
+        }
+    }
+
+    #[test]
+    fn untrusted_servers_ignore_spoofed_session_settings() {
+        let req = request(&[("prisma-setting-app.current_user", "admin")]);
+
+        assert!(session_settings(&req, false).is_empty());
+    }
+
+    #[test]
+    fn untrusted_servers_ignore_the_tenant() {
+        let req = request(&[("prisma-tenant", "other")]);
+
+        assert_eq!(None, gateway_header(&req, false, "prisma-tenant"));
+        assert_eq!(Some(String::from("other")), gateway_header(&req, true, "prisma-tenant"));
+    }
+
+    #[test]
+    fn untrusted_servers_ignore_the_actor() {
+        let req = request(&[("prisma-actor", "someone-else")]);
+
+        assert_eq!(None, gateway_header(&req, false, "prisma-actor"));
+    }
+
+    #[test]
+    fn trusted_gateways_set_the_session_settings() {
+        let req = request(&[("prisma-setting-app.current_user", "alice"), ("prisma-role", "reader")]);
+
+        assert_eq!(
+            vec![(String::from("app.current_user"), String::from("alice"))],
+            session_settings(&req, true)
+        );
+    }
+}